@@ -4,6 +4,8 @@ pub use sea_orm_migration::prelude::*;
 mod m20220101_000001_users;
 
 mod m20251128_075444_documents;
+mod m20261016_093000_add_parent_id_to_documents;
+mod m20261017_090000_embedding_caches;
 pub struct Migrator;
 
 #[async_trait::async_trait]
@@ -12,6 +14,8 @@ impl MigratorTrait for Migrator {
         vec![
             Box::new(m20220101_000001_users::Migration),
             Box::new(m20251128_075444_documents::Migration),
+            Box::new(m20261016_093000_add_parent_id_to_documents::Migration),
+            Box::new(m20261017_090000_embedding_caches::Migration),
             // inject-above (do not remove this comment)
         ]
     }
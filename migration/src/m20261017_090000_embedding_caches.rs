@@ -0,0 +1,39 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        create_table(m, "embedding_caches",
+            &[
+            
+            ("id", ColType::PkAuto),
+            
+            ("model", ColType::String),
+            ("content_hash", ColType::String),
+            ("dimension", ColType::Integer),
+            ("vector", ColType::Blob),
+            ],
+            &[
+            ]
+        ).await?;
+
+        m.create_index(
+            Index::create()
+                .name("idx-embedding_caches-model-content_hash")
+                .table(Alias::new("embedding_caches"))
+                .col(Alias::new("model"))
+                .col(Alias::new("content_hash"))
+                .unique()
+                .to_owned(),
+        )
+        .await
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        drop_table(m, "embedding_caches").await
+    }
+}
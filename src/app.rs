@@ -12,10 +12,16 @@ use loco_rs::{
 };
 use migration::Migrator;
 use std::path::Path;
+use std::sync::Arc;
 
 #[allow(unused_imports)]
 use crate::{
     controllers, initializers, models::_entities::users, tasks, 
+    ingestion::{
+        chunking::{ChunkStrategy, ChunkingOptions},
+        embeddings::{create_embedder, with_cache, Embedder, EmbeddingCacheSettings, EmbeddingSettings},
+        extractors::{ExtractorRegistry, ExtractorSettings},
+    },
     workers::downloader::DownloadWorker,
     workers::ingest::IngestWorker,
 };
@@ -45,6 +51,37 @@ impl Hooks for App {
         create_app::<Self, Migrator>(mode, environment, config).await
     }
 
+    /// Build the extractor registry, chunking defaults and embedder from
+    /// `settings.extractors`, `settings.chunking`, `settings.embeddings` and
+    /// `settings.embedding_cache`, and share them with the workers and tasks
+    async fn after_context(ctx: AppContext) -> Result<AppContext> {
+        let extractors: ExtractorSettings = setting(&ctx, "extractors")?;
+        let registry = ExtractorRegistry::from_settings(&extractors)
+            .map_err(|e| loco_rs::Error::string(&e.to_string()))?;
+        ctx.shared_store.insert(Arc::new(registry));
+
+        let chunking: ChunkingOptions = setting(&ctx, "chunking")?;
+        chunking
+            .validate()
+            .map_err(|e| loco_rs::Error::string(&format!("Invalid settings.chunking: {}", e)))?;
+        let embeddings: Option<EmbeddingSettings> = setting(&ctx, "embeddings")?;
+        if chunking.strategy == ChunkStrategy::Semantic && embeddings.is_none() {
+            return Err(loco_rs::Error::string(
+                "Semantic chunking needs an embedding model; configure one under settings.embeddings",
+            ));
+        }
+        ctx.shared_store.insert(chunking);
+
+        if let Some(embeddings) = embeddings {
+            let cache: EmbeddingCacheSettings = setting(&ctx, "embedding_cache")?;
+            let embedder: Arc<dyn Embedder> = create_embedder(&embeddings)
+                .and_then(|embedder| with_cache(embedder, &cache, &ctx.db))
+                .map_err(|e| loco_rs::Error::string(&e.to_string()))?;
+            ctx.shared_store.insert(embedder);
+        }
+        Ok(ctx)
+    }
+
     async fn initializers(_ctx: &AppContext) -> Result<Vec<Box<dyn Initializer>>> {
         Ok(vec![Box::new(
             initializers::view_engine::ViewEngineInitializer,
@@ -76,4 +113,13 @@ impl Hooks for App {
             .await?;
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// The `settings.<key>` section of the config, or its default when it is absent
+fn setting<T: serde::de::DeserializeOwned + Default>(ctx: &AppContext, key: &str) -> Result<T> {
+    match ctx.config.settings.as_ref().and_then(|settings| settings.get(key)) {
+        Some(value) => serde_json::from_value(value.clone())
+            .map_err(|e| loco_rs::Error::string(&format!("Invalid {} settings: {}", key, e))),
+        None => Ok(T::default()),
+    }
+}
@@ -5,7 +5,12 @@ use loco_rs::prelude::*;
 use axum::extract::Multipart;
 use serde::{Deserialize, Serialize};
 use crate::models::_entities::documents::{self, Entity as Documents};
-use crate::ingestion::{IngestionTarget, GraphDbType};
+use crate::ingestion::{
+    IngestionTarget, GraphDbType, ExtractOptions,
+    chunking::{ChunkStrategy, ChunkingOptions},
+    embeddings::Embedder,
+};
+use std::sync::Arc;
 use sea_orm::{EntityTrait, Set, ActiveModelTrait};
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -28,6 +33,10 @@ pub struct StatusResponse {
     pub status: Option<String>,
     pub progress: Option<i32>,
     pub error_message: Option<String>,
+    /// Document this one was unpacked from (an archive or email), if any
+    pub parent_id: Option<i32>,
+    /// Documents unpacked from this one; its status aggregates theirs
+    pub child_ids: Vec<i32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -38,6 +47,19 @@ pub struct TextIngestRequest {
     pub metadata: Option<serde_json::Value>,
 }
 
+/// A form field naming one of an enum's variants, e.g. `chunk_unit=tokens`
+fn parse_choice<T: serde::de::DeserializeOwned>(name: &str, value: &str) -> Result<T> {
+    serde_json::from_value(serde_json::Value::String(value.trim().to_string()))
+        .map_err(|_| Error::BadRequest(format!("Invalid {}: {}", name, value.trim())))
+}
+
+fn parse_number(name: &str, value: &str) -> Result<usize> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| Error::BadRequest(format!("Invalid {}: {}", name, value.trim())))
+}
+
 /// Upload and ingest a file
 #[debug_handler]
 pub async fn upload_file(
@@ -48,6 +70,7 @@ pub async fn upload_file(
     let mut filename: Option<String> = None;
     let mut target = IngestionTarget::Both;
     let mut graph_db: Option<GraphDbType> = None;
+    let mut options = ExtractOptions::default();
 
     // Process multipart form data
     while let Some(field) = multipart.next_field().await.map_err(|e| Error::BadRequest(e.to_string()))? {
@@ -57,6 +80,7 @@ pub async fn upload_file(
             "file" => {
                 let field_filename = field.file_name().unwrap_or("unknown").to_string();
                 filename = Some(field_filename.clone());
+                options.content_type = field.content_type().map(str::to_string);
                 
                 // Save file to temp directory
                 let data = field.bytes().await.map_err(|e| Error::BadRequest(e.to_string()))?;
@@ -78,11 +102,77 @@ pub async fn upload_file(
                 let text = field.text().await.map_err(|e| Error::BadRequest(e.to_string()))?;
                 graph_db = serde_json::from_str(&format!("\"{}\"", text)).ok();
             }
+            "ocr_languages" => {
+                let text = field.text().await.map_err(|e| Error::BadRequest(e.to_string()))?;
+                options.ocr_languages = ExtractOptions::parse_languages(&text);
+            }
+            "drop_code_blocks" => {
+                let text = field.text().await.map_err(|e| Error::BadRequest(e.to_string()))?;
+                options.drop_code_blocks = ExtractOptions::parse_flag(&text);
+            }
+            "include_outputs" => {
+                let text = field.text().await.map_err(|e| Error::BadRequest(e.to_string()))?;
+                options.include_outputs = ExtractOptions::parse_flag(&text);
+            }
+            "text_field" => {
+                let text = field.text().await.map_err(|e| Error::BadRequest(e.to_string()))?;
+                options.text_field = Some(text.trim().to_string()).filter(|f| !f.is_empty());
+            }
+            "metadata_fields" => {
+                let text = field.text().await.map_err(|e| Error::BadRequest(e.to_string()))?;
+                options.metadata_fields = ExtractOptions::parse_fields(&text);
+            }
+            "id_field" => {
+                let text = field.text().await.map_err(|e| Error::BadRequest(e.to_string()))?;
+                options.id_field = Some(text.trim().to_string()).filter(|f| !f.is_empty());
+            }
+            "id_namespace" => {
+                let text = field.text().await.map_err(|e| Error::BadRequest(e.to_string()))?;
+                options.id_namespace = Some(text.trim().to_string()).filter(|n| !n.is_empty());
+            }
+            "encoding" => {
+                let text = field.text().await.map_err(|e| Error::BadRequest(e.to_string()))?;
+                options.encoding = Some(text.trim().to_string()).filter(|e| !e.is_empty());
+            }
+            "chunk_strategy" => {
+                let text = field.text().await.map_err(|e| Error::BadRequest(e.to_string()))?;
+                options.chunk_strategy = Some(parse_choice("chunk_strategy", &text)?);
+            }
+            "chunk_size" => {
+                let text = field.text().await.map_err(|e| Error::BadRequest(e.to_string()))?;
+                options.chunk_size = Some(parse_number("chunk_size", &text)?);
+            }
+            "chunk_overlap" => {
+                let text = field.text().await.map_err(|e| Error::BadRequest(e.to_string()))?;
+                options.chunk_overlap = Some(parse_number("chunk_overlap", &text)?);
+            }
+            "chunk_unit" => {
+                let text = field.text().await.map_err(|e| Error::BadRequest(e.to_string()))?;
+                options.chunk_unit = Some(parse_choice("chunk_unit", &text)?);
+            }
             _ => {}
         }
     }
 
     let file_path = file_path.ok_or_else(|| Error::BadRequest("No file uploaded".to_string()))?;
+    let chunking = ctx
+        .shared_store
+        .get::<ChunkingOptions>()
+        .unwrap_or_default()
+        .with_overrides(&options);
+    let invalid = match chunking.validate() {
+        Err(e) => Some(e.to_string()),
+        Ok(()) if chunking.strategy == ChunkStrategy::Semantic
+            && !ctx.shared_store.contains::<Arc<dyn Embedder>>() =>
+        {
+            Some("Semantic chunking needs an embedding model; configure one under settings.embeddings".to_string())
+        }
+        Ok(()) => None,
+    };
+    if let Some(message) = invalid {
+        let _ = tokio::fs::remove_file(&file_path).await;
+        return Err(Error::BadRequest(message));
+    }
     let filename = filename.unwrap_or_else(|| "unknown".to_string());
 
     // Create document record
@@ -106,6 +196,7 @@ pub async fn upload_file(
         text: None,
         target: format!("{:?}", target),
         graph_db: graph_db.map(|g| format!("{:?}", g)),
+        options,
     })
     .await?;
     
@@ -144,6 +235,7 @@ pub async fn ingest_text(
         text: Some(req.text),
         target: format!("{:?}", req.target),
         graph_db: req.graph_db.map(|g| format!("{:?}", g)),
+        options: ExtractOptions::default(),
     })
     .await?;
     
@@ -165,12 +257,20 @@ pub async fn status(
         .await?
         .ok_or_else(|| Error::NotFound)?;
 
+    let child_ids = Documents::find_children(&ctx.db, doc.id)
+        .await?
+        .into_iter()
+        .map(|child| child.id)
+        .collect();
+
     format::json(StatusResponse {
         document_id: doc.id,
         filename: doc.filename,
         status: doc.status,
         progress: doc.progress,
         error_message: doc.error_message,
+        parent_id: doc.parent_id,
+        child_ids,
     })
 }
 
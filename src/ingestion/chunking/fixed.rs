@@ -0,0 +1,35 @@
+use super::{split_sections, windows, ChunkUnit, Chunker};
+use crate::ingestion::Section;
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// Chunker cutting sections into windows of a fixed number of characters or tokens,
+/// regardless of where sentences end
+pub struct FixedSizeChunker {
+    size: usize,
+    overlap: usize,
+    unit: ChunkUnit,
+}
+
+impl FixedSizeChunker {
+    pub fn new(size: usize, overlap: usize, unit: ChunkUnit) -> Self {
+        Self {
+            size: size.max(1),
+            overlap: overlap.min(size.saturating_sub(1)),
+            unit,
+        }
+    }
+}
+
+#[async_trait]
+impl Chunker for FixedSizeChunker {
+    async fn chunk(&self, sections: &[Section]) -> Result<Vec<Section>> {
+        Ok(split_sections(sections, |text| {
+            windows(text, self.size, self.overlap, self.unit)
+                .into_iter()
+                .map(|window| window.trim().to_string())
+                .filter(|window| !window.is_empty())
+                .collect()
+        }))
+    }
+}
@@ -0,0 +1,84 @@
+use super::{ChunkUnit, Chunker, RecursiveChunker};
+use crate::ingestion::Section;
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// Chunker that starts a new chunk at every Markdown heading (`#` to `######`) and
+/// never joins text across one. Each chunk is tagged with the `heading` it falls
+/// under; blocks larger than a chunk are split like [`RecursiveChunker`] does.
+pub struct HeadingChunker {
+    splitter: RecursiveChunker,
+}
+
+impl HeadingChunker {
+    pub fn new(size: usize, overlap: usize, unit: ChunkUnit) -> Self {
+        Self {
+            splitter: RecursiveChunker::new(size, overlap, unit),
+        }
+    }
+}
+
+#[async_trait]
+impl Chunker for HeadingChunker {
+    async fn chunk(&self, sections: &[Section]) -> Result<Vec<Section>> {
+        let mut chunks = Vec::new();
+        for section in sections {
+            let mut first = true;
+            for (heading, block) in heading_blocks(&section.text) {
+                for text in self.splitter.split(block) {
+                    let mut chunk = Section {
+                        text,
+                        metadata: section.metadata.clone(),
+                        ..Section::default()
+                    };
+                    if let Some(heading) = heading {
+                        chunk
+                            .metadata
+                            .insert("heading".to_string(), heading.to_string().into());
+                    }
+                    if first {
+                        chunk.records = section.records.clone();
+                        first = false;
+                    }
+                    chunks.push(chunk);
+                }
+            }
+        }
+        Ok(chunks)
+    }
+}
+
+/// Split text before each heading line, pairing every block with its heading's title
+fn heading_blocks(text: &str) -> Vec<(Option<&str>, &str)> {
+    let mut blocks = Vec::new();
+    let mut heading = None;
+    let mut start = 0;
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        if let Some(title) = heading_title(line) {
+            if offset > start {
+                blocks.push((heading, &text[start..offset]));
+            }
+            heading = Some(title);
+            start = offset;
+        }
+        offset += line.len();
+    }
+    if offset > start {
+        blocks.push((heading, &text[start..offset]));
+    }
+    blocks
+}
+
+/// Title of an ATX heading line such as `## Install`
+fn heading_title(line: &str) -> Option<&str> {
+    let line = line.trim();
+    let hashes = line.len() - line.trim_start_matches('#').len();
+    let title = &line[hashes..];
+    if (1..=6).contains(&hashes) && title.starts_with(char::is_whitespace) {
+        Some(title.trim().trim_end_matches('#').trim_end()).filter(|t| !t.is_empty())
+    } else {
+        None
+    }
+}
@@ -0,0 +1,278 @@
+mod fixed;
+mod heading;
+mod paragraph;
+mod recursive;
+mod semantic;
+mod sentence;
+
+pub use fixed::FixedSizeChunker;
+pub use heading::HeadingChunker;
+pub use paragraph::{paragraph_chunks, ParagraphChunker};
+pub use recursive::RecursiveChunker;
+pub use semantic::SemanticChunker;
+pub use sentence::SentenceChunker;
+
+use super::embeddings::Embedder;
+use super::{ExtractOptions, Section};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::ops::Range;
+use std::sync::Arc;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Trait for splitting extracted sections into the chunks that are stored and searched
+#[async_trait]
+pub trait Chunker: Send + Sync {
+    /// Split sections into chunks. Each chunk keeps the metadata of the section it
+    /// came from, and the section's records travel with its first chunk.
+    async fn chunk(&self, sections: &[Section]) -> Result<Vec<Section>>;
+}
+
+/// Chunking strategy
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChunkStrategy {
+    /// One chunk per paragraph, whatever its size
+    #[default]
+    Paragraph,
+    /// Windows of `chunk_size` units overlapping by `chunk_overlap`
+    Fixed,
+    /// Split at paragraphs, then lines, sentences and words until chunks fit
+    Recursive,
+    /// Whole sentences packed into chunks
+    Sentence,
+    /// Never across a Markdown heading
+    Heading,
+    /// Where the topic shifts, judged by the embeddings of adjacent sentences
+    Semantic,
+}
+
+/// Unit chunk sizes are measured in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChunkUnit {
+    #[default]
+    Chars,
+    /// Words and punctuation marks, which approximates what embedding models count
+    Tokens,
+}
+
+impl ChunkUnit {
+    /// Size of `text` in this unit
+    pub fn measure(self, text: &str) -> usize {
+        match self {
+            Self::Chars => text.chars().count(),
+            Self::Tokens => self.spans(text).len(),
+        }
+    }
+
+    /// Byte ranges of the units of `text`; whitespace is not a token
+    fn spans(self, text: &str) -> Vec<Range<usize>> {
+        match self {
+            Self::Chars => text
+                .char_indices()
+                .map(|(i, c)| i..i + c.len_utf8())
+                .collect(),
+            Self::Tokens => text
+                .split_word_bound_indices()
+                .filter(|(_, word)| !word.trim().is_empty())
+                .map(|(i, word)| i..i + word.len())
+                .collect(),
+        }
+    }
+}
+
+/// How documents are chunked, configured under `settings.chunking` and overridable
+/// per request
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ChunkingOptions {
+    #[serde(default)]
+    pub strategy: ChunkStrategy,
+    /// Largest chunk, in `unit`s; paragraph chunks are not limited
+    #[serde(default = "default_chunk_size")]
+    pub chunk_size: usize,
+    /// Units repeated from the end of a chunk at the start of the next
+    #[serde(default)]
+    pub chunk_overlap: usize,
+    #[serde(default)]
+    pub unit: ChunkUnit,
+    /// Semantic chunks are cut where adjacent sentences are less similar than this
+    /// percentile of all adjacent similarities in the section
+    #[serde(default = "default_breakpoint_percentile")]
+    pub breakpoint_percentile: f32,
+    /// Smallest semantic chunk, in `unit`s
+    #[serde(default)]
+    pub min_chunk_size: usize,
+}
+
+/// Smallest `chunk_size` accepted, in any unit
+pub const MIN_CHUNK_SIZE: usize = 32;
+
+fn default_chunk_size() -> usize {
+    1000
+}
+
+fn default_breakpoint_percentile() -> f32 {
+    10.0
+}
+
+impl Default for ChunkingOptions {
+    fn default() -> Self {
+        Self {
+            strategy: ChunkStrategy::default(),
+            chunk_size: default_chunk_size(),
+            chunk_overlap: 0,
+            unit: ChunkUnit::default(),
+            breakpoint_percentile: default_breakpoint_percentile(),
+            min_chunk_size: 0,
+        }
+    }
+}
+
+impl ChunkingOptions {
+    /// Check that chunks are at least [`MIN_CHUNK_SIZE`] long and overlap by less than
+    /// half, so that each chunk moves at least half a chunk past the previous one
+    pub fn validate(&self) -> Result<()> {
+        if self.chunk_size < MIN_CHUNK_SIZE {
+            anyhow::bail!(
+                "chunk_size must be at least {}, got {}",
+                MIN_CHUNK_SIZE,
+                self.chunk_size
+            );
+        }
+        if self.chunk_overlap * 2 >= self.chunk_size {
+            anyhow::bail!(
+                "chunk_overlap must be less than half of chunk_size ({}), got {}",
+                self.chunk_size,
+                self.chunk_overlap
+            );
+        }
+        Ok(())
+    }
+
+    /// These options with the chunking fields a request set
+    pub fn with_overrides(&self, options: &ExtractOptions) -> Self {
+        Self {
+            strategy: options.chunk_strategy.unwrap_or(self.strategy),
+            chunk_size: options.chunk_size.unwrap_or(self.chunk_size),
+            chunk_overlap: options.chunk_overlap.unwrap_or(self.chunk_overlap),
+            unit: options.chunk_unit.unwrap_or(self.unit),
+            ..self.clone()
+        }
+    }
+}
+
+/// Create a chunker for the given options; semantic chunkers embed with `embedder`,
+/// and cannot be created without one
+pub fn create_chunker(
+    options: &ChunkingOptions,
+    embedder: Option<Arc<dyn Embedder>>,
+) -> Result<Box<dyn Chunker>> {
+    let (size, overlap) = (options.chunk_size, options.chunk_overlap);
+    Ok(match options.strategy {
+        ChunkStrategy::Paragraph => Box::new(ParagraphChunker),
+        ChunkStrategy::Fixed => Box::new(FixedSizeChunker::new(size, overlap, options.unit)),
+        ChunkStrategy::Recursive => Box::new(RecursiveChunker::new(size, overlap, options.unit)),
+        ChunkStrategy::Sentence => Box::new(SentenceChunker::new(size, overlap, options.unit)),
+        ChunkStrategy::Heading => Box::new(HeadingChunker::new(size, overlap, options.unit)),
+        ChunkStrategy::Semantic => Box::new(SemanticChunker::new(
+            embedder.context(
+                "Semantic chunking needs an embedding model; configure one under settings.embeddings",
+            )?,
+            options.breakpoint_percentile,
+            options.min_chunk_size,
+            size,
+            options.unit,
+        )),
+    })
+}
+
+/// Split every section with `split`, keeping its metadata on each chunk and its
+/// records on the first
+fn split_sections(sections: &[Section], split: impl Fn(&str) -> Vec<String>) -> Vec<Section> {
+    sections
+        .iter()
+        .flat_map(|section| section_chunks(section, split(&section.text)))
+        .collect()
+}
+
+/// Chunks of `section` with the given texts
+fn section_chunks(section: &Section, texts: Vec<String>) -> Vec<Section> {
+    texts
+        .into_iter()
+        .enumerate()
+        .map(|(index, text)| Section {
+            text,
+            metadata: section.metadata.clone(),
+            records: if index == 0 {
+                section.records.clone()
+            } else {
+                Vec::new()
+            },
+            ..Section::default()
+        })
+        .collect()
+}
+
+/// Consecutive windows of `size` units, each starting `overlap` units before the
+/// previous one ends. Without overlap the windows are contiguous, so they can be
+/// joined back into `text`.
+fn windows(text: &str, size: usize, overlap: usize, unit: ChunkUnit) -> Vec<&str> {
+    let spans = unit.spans(text);
+    let step = size.saturating_sub(overlap).max(1);
+
+    let mut windows = Vec::new();
+    let mut start = 0;
+    while start < spans.len() {
+        let end = (start + size).min(spans.len());
+        let from = if start == 0 { 0 } else { spans[start].start };
+        let to = spans.get(end).map_or(text.len(), |span| span.start);
+        windows.push(&text[from..to]);
+        if end == spans.len() {
+            break;
+        }
+        start += step;
+    }
+    windows
+}
+
+/// Greedily join consecutive pieces into chunks of at most `size` units, starting
+/// each chunk with the last pieces of the previous one that fit in `overlap`. Pieces
+/// must already fit in `size` and keep the separators between them.
+fn pack(pieces: &[&str], size: usize, overlap: usize, unit: ChunkUnit) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current: VecDeque<(&str, usize)> = VecDeque::new();
+    let mut current_size = 0;
+
+    for piece in pieces {
+        let piece_size = unit.measure(piece);
+        if current_size + piece_size > size && !current.is_empty() {
+            chunks.push(join(&current));
+            while current_size > overlap || current_size + piece_size > size {
+                let Some((_, dropped)) = current.pop_front() else {
+                    break;
+                };
+                current_size -= dropped;
+            }
+        }
+        current.push_back((piece, piece_size));
+        current_size += piece_size;
+    }
+    if !current.is_empty() {
+        chunks.push(join(&current));
+    }
+
+    chunks.retain(|chunk| !chunk.is_empty());
+    chunks
+}
+
+fn join(pieces: &VecDeque<(&str, usize)>) -> String {
+    pieces
+        .iter()
+        .map(|(piece, _)| *piece)
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
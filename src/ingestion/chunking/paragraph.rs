@@ -0,0 +1,72 @@
+use super::Chunker;
+use crate::ingestion::Section;
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// Chunker producing one chunk per paragraph, see [`paragraph_chunks`]
+pub struct ParagraphChunker;
+
+#[async_trait]
+impl Chunker for ParagraphChunker {
+    async fn chunk(&self, sections: &[Section]) -> Result<Vec<Section>> {
+        Ok(paragraph_chunks(sections))
+    }
+}
+
+/// Split sections into paragraph chunks that keep the metadata of their section.
+///
+/// Paged sections get `page` and `page_end`. A paragraph cut by a page break (no
+/// terminal punctuation before it, lowercase continuation after it) is stitched back
+/// into one chunk spanning both pages.
+pub fn paragraph_chunks(sections: &[Section]) -> Vec<Section> {
+    let mut chunks: Vec<Section> = Vec::new();
+
+    for section in sections {
+        let page = section.metadata.get("page").cloned();
+        let paragraphs = section
+            .text
+            .split("\n\n")
+            .map(str::trim)
+            .filter(|p| !p.is_empty());
+
+        for (idx, paragraph) in paragraphs.enumerate() {
+            if idx == 0 {
+                if let (Some(page), Some(previous)) = (&page, chunks.last_mut()) {
+                    if continues_across_page(&previous.text, paragraph) {
+                        previous.text.push(' ');
+                        previous.text.push_str(paragraph);
+                        previous
+                            .metadata
+                            .insert("page_end".to_string(), page.clone());
+                        previous.records.extend(section.records.iter().cloned());
+                        continue;
+                    }
+                }
+            }
+
+            let mut chunk = Section {
+                text: paragraph.to_string(),
+                metadata: section.metadata.clone(),
+                ..Section::default()
+            };
+            if idx == 0 {
+                chunk.records = section.records.clone();
+            }
+            if let Some(page) = &page {
+                chunk.metadata.insert("page_end".to_string(), page.clone());
+            }
+            chunks.push(chunk);
+        }
+    }
+
+    chunks
+}
+
+fn continues_across_page(previous: &str, next: &str) -> bool {
+    let ends_open = previous
+        .chars()
+        .last()
+        .is_some_and(|c| !matches!(c, '.' | '!' | '?' | ':' | ';' | '"' | '\u{201D}'));
+    let starts_lower = next.chars().next().is_some_and(char::is_lowercase);
+    ends_open && starts_lower
+}
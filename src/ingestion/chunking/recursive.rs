@@ -0,0 +1,61 @@
+use super::{pack, split_sections, windows, ChunkUnit, Chunker};
+use crate::ingestion::Section;
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// Separators tried in order, from the coarsest to the finest
+const SEPARATORS: &[&str] = &["\n\n", "\n", ". ", " "];
+
+/// Chunker splitting text at the coarsest separator that makes it fit: paragraphs
+/// first, then lines, sentences and words, and fixed windows for anything longer
+/// than a chunk without a space. The pieces are then packed back into chunks as
+/// large as allowed.
+pub struct RecursiveChunker {
+    size: usize,
+    overlap: usize,
+    unit: ChunkUnit,
+}
+
+impl RecursiveChunker {
+    pub fn new(size: usize, overlap: usize, unit: ChunkUnit) -> Self {
+        Self {
+            size: size.max(1),
+            overlap: overlap.min(size.saturating_sub(1)),
+            unit,
+        }
+    }
+
+    /// Split `text` into chunks of at most the chunk size
+    pub fn split(&self, text: &str) -> Vec<String> {
+        pack(
+            &self.pieces(text, SEPARATORS),
+            self.size,
+            self.overlap,
+            self.unit,
+        )
+    }
+
+    /// Contiguous pieces of `text` that each fit in a chunk
+    fn pieces<'a>(&self, text: &'a str, separators: &[&str]) -> Vec<&'a str> {
+        if self.unit.measure(text) <= self.size {
+            return vec![text];
+        }
+        match separators
+            .iter()
+            .position(|separator| text.contains(separator))
+        {
+            Some(index) => text
+                .split_inclusive(separators[index])
+                .flat_map(|part| self.pieces(part, &separators[index + 1..]))
+                .collect(),
+            None => windows(text, self.size, 0, self.unit),
+        }
+    }
+}
+
+#[async_trait]
+impl Chunker for RecursiveChunker {
+    async fn chunk(&self, sections: &[Section]) -> Result<Vec<Section>> {
+        Ok(split_sections(sections, |text| self.split(text)))
+    }
+}
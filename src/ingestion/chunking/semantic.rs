@@ -0,0 +1,123 @@
+use super::{section_chunks, windows, ChunkUnit, Chunker};
+use crate::ingestion::embeddings::{cosine_similarity, Embedder};
+use crate::ingestion::Section;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::sync::Arc;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Chunker that embeds every sentence and cuts between adjacent sentences whose
+/// similarity falls below a percentile of all adjacent similarities in the section,
+/// i.e. where the topic shifts.
+///
+/// A chunk is never cut before it reaches `min_size` units and always cut before it
+/// would exceed `max_size`. Sentences longer than `max_size` are cut into fixed windows.
+pub struct SemanticChunker {
+    embedder: Arc<dyn Embedder>,
+    percentile: f32,
+    min_size: usize,
+    max_size: usize,
+    unit: ChunkUnit,
+}
+
+impl SemanticChunker {
+    pub fn new(
+        embedder: Arc<dyn Embedder>,
+        percentile: f32,
+        min_size: usize,
+        max_size: usize,
+        unit: ChunkUnit,
+    ) -> Self {
+        let max_size = max_size.max(1);
+        Self {
+            embedder,
+            percentile: percentile.clamp(0.0, 100.0),
+            min_size: min_size.min(max_size),
+            max_size,
+            unit,
+        }
+    }
+
+    /// Split a section's text at its semantic breakpoints
+    async fn split(&self, text: &str) -> Result<Vec<String>> {
+        let sentences: Vec<&str> = text
+            .split_sentence_bounds()
+            .filter(|sentence| !sentence.trim().is_empty())
+            .flat_map(|sentence| {
+                if self.unit.measure(sentence) <= self.max_size {
+                    vec![sentence]
+                } else {
+                    windows(sentence, self.max_size, 0, self.unit)
+                }
+            })
+            .collect();
+        if sentences.len() < 2 {
+            return Ok(sentences
+                .iter()
+                .map(|sentence| sentence.trim().to_string())
+                .collect());
+        }
+
+        let texts: Vec<String> = sentences.iter().map(|s| s.trim().to_string()).collect();
+        let embeddings = self
+            .embedder
+            .embed(&texts)
+            .await
+            .context("Failed to embed sentences")?;
+        if embeddings.len() != sentences.len() {
+            anyhow::bail!(
+                "Expected {} sentence embeddings, got {}",
+                sentences.len(),
+                embeddings.len()
+            );
+        }
+        let similarities: Vec<f32> = embeddings
+            .windows(2)
+            .map(|pair| cosine_similarity(&pair[0], &pair[1]))
+            .collect();
+        let threshold = percentile(&similarities, self.percentile);
+
+        let mut chunks = Vec::new();
+        let mut current = String::new();
+        let mut current_size = 0;
+        for (index, sentence) in sentences.iter().enumerate() {
+            current.push_str(sentence);
+            current_size += self.unit.measure(sentence);
+
+            let Some(next) = sentences.get(index + 1) else {
+                break;
+            };
+            let breakpoint = similarities[index] < threshold && current_size >= self.min_size;
+            let full = current_size + self.unit.measure(next) > self.max_size;
+            if breakpoint || full {
+                chunks.push(current.trim().to_string());
+                current.clear();
+                current_size = 0;
+            }
+        }
+        chunks.push(current.trim().to_string());
+
+        chunks.retain(|chunk| !chunk.is_empty());
+        Ok(chunks)
+    }
+}
+
+#[async_trait]
+impl Chunker for SemanticChunker {
+    async fn chunk(&self, sections: &[Section]) -> Result<Vec<Section>> {
+        let mut chunks = Vec::new();
+        for section in sections {
+            chunks.extend(section_chunks(section, self.split(&section.text).await?));
+        }
+        Ok(chunks)
+    }
+}
+
+/// The `p`th percentile of `values`, interpolating between the closest ranks
+fn percentile(values: &[f32], p: f32) -> f32 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f32::total_cmp);
+    let rank = p / 100.0 * (sorted.len() - 1) as f32;
+    let (low, high) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[low] + (sorted[high] - sorted[low]) * (rank - low as f32)
+}
@@ -0,0 +1,42 @@
+use super::{pack, split_sections, windows, ChunkUnit, Chunker};
+use crate::ingestion::Section;
+use anyhow::Result;
+use async_trait::async_trait;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Chunker packing whole sentences into chunks, overlapping by whole sentences.
+/// Sentences longer than a chunk are cut into fixed windows.
+pub struct SentenceChunker {
+    size: usize,
+    overlap: usize,
+    unit: ChunkUnit,
+}
+
+impl SentenceChunker {
+    pub fn new(size: usize, overlap: usize, unit: ChunkUnit) -> Self {
+        Self {
+            size: size.max(1),
+            overlap: overlap.min(size.saturating_sub(1)),
+            unit,
+        }
+    }
+}
+
+#[async_trait]
+impl Chunker for SentenceChunker {
+    async fn chunk(&self, sections: &[Section]) -> Result<Vec<Section>> {
+        Ok(split_sections(sections, |text| {
+            let pieces: Vec<&str> = text
+                .split_sentence_bounds()
+                .flat_map(|sentence| {
+                    if self.unit.measure(sentence) <= self.size {
+                        vec![sentence]
+                    } else {
+                        windows(sentence, self.size, 0, self.unit)
+                    }
+                })
+                .collect();
+            pack(&pieces, self.size, self.overlap, self.unit)
+        }))
+    }
+}
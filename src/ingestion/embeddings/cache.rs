@@ -0,0 +1,258 @@
+use super::Embedder;
+use crate::models::embedding_caches;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use sea_orm::{DatabaseConnection, Set};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Where embeddings are kept between ingestions, keyed by the embedder's
+/// [`Embedder::cache_key`] and content hash
+#[async_trait]
+pub trait EmbeddingCache: Send + Sync {
+    /// Cached vectors of the embedder keyed `model` for the given hashes, keyed by hash
+    async fn get(&self, model: &str, hashes: &[String]) -> Result<HashMap<String, Vec<f32>>>;
+
+    /// Remember vectors of the embedder keyed `model` by hash
+    async fn put(&self, model: &str, entries: &[(String, Vec<f32>)]) -> Result<()>;
+}
+
+/// Cache in the application database's `embedding_caches` table
+pub struct DbEmbeddingCache {
+    db: DatabaseConnection,
+}
+
+impl DbEmbeddingCache {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl EmbeddingCache for DbEmbeddingCache {
+    async fn get(&self, model: &str, hashes: &[String]) -> Result<HashMap<String, Vec<f32>>> {
+        let rows = embedding_caches::Entity::find_by_hashes(&self.db, model, hashes)
+            .await
+            .context("Failed to read cached embeddings")?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.content_hash, decode_vector(&row.vector)))
+            .collect())
+    }
+
+    async fn put(&self, model: &str, entries: &[(String, Vec<f32>)]) -> Result<()> {
+        let rows = entries
+            .iter()
+            .map(|(hash, vector)| embedding_caches::ActiveModel {
+                model: Set(model.to_string()),
+                content_hash: Set(hash.clone()),
+                dimension: Set(vector.len() as i32),
+                vector: Set(encode_vector(vector)),
+                ..Default::default()
+            })
+            .collect();
+        embedding_caches::Entity::insert_missing(&self.db, rows)
+            .await
+            .context("Failed to cache embeddings")
+    }
+}
+
+/// Cache in Redis, one key per vector holding its little-endian `f32`s
+pub struct RedisEmbeddingCache {
+    client: redis::Client,
+    prefix: String,
+    ttl_secs: Option<u64>,
+}
+
+impl RedisEmbeddingCache {
+    pub fn new(url: &str, prefix: &str, ttl_secs: Option<u64>) -> Result<Self> {
+        Ok(Self {
+            client: redis::Client::open(url).context("Failed to create Redis client")?,
+            prefix: prefix.to_string(),
+            ttl_secs,
+        })
+    }
+
+    fn key(&self, model: &str, hash: &str) -> String {
+        format!("{}{}:{}", self.prefix, model, hash)
+    }
+}
+
+#[async_trait]
+impl EmbeddingCache for RedisEmbeddingCache {
+    async fn get(&self, model: &str, hashes: &[String]) -> Result<HashMap<String, Vec<f32>>> {
+        if hashes.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let mut con = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .context("Failed to get Redis connection")?;
+        let keys: Vec<String> = hashes.iter().map(|hash| self.key(model, hash)).collect();
+        let values: Vec<Option<Vec<u8>>> = redis::cmd("MGET")
+            .arg(&keys)
+            .query_async(&mut con)
+            .await
+            .context("Failed to read cached embeddings from Redis")?;
+
+        Ok(hashes
+            .iter()
+            .zip(values)
+            .filter_map(|(hash, value)| Some((hash.clone(), decode_vector(&value?))))
+            .collect())
+    }
+
+    async fn put(&self, model: &str, entries: &[(String, Vec<f32>)]) -> Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let mut con = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .context("Failed to get Redis connection")?;
+        let mut pipe = redis::pipe();
+        for (hash, vector) in entries {
+            let cmd = pipe
+                .cmd("SET")
+                .arg(self.key(model, hash))
+                .arg(encode_vector(vector));
+            if let Some(ttl) = self.ttl_secs {
+                cmd.arg("EX").arg(ttl);
+            }
+            cmd.ignore();
+        }
+        pipe.query_async::<()>(&mut con)
+            .await
+            .context("Failed to cache embeddings in Redis")
+    }
+}
+
+fn encode_vector(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+fn decode_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+/// SHA-256 of a text, in hex, identifying it in the cache
+pub fn content_hash(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+/// Embedder reusing cached vectors and only asking `inner` for texts it has not
+/// seen with the same model, dimensions and server (its [`Embedder::cache_key`]).
+///
+/// The cache is an optimisation: when it cannot be read or written, a warning is
+/// logged and the texts are embedded as if it were empty.
+pub struct CachedEmbedder {
+    inner: Arc<dyn Embedder>,
+    cache: Arc<dyn EmbeddingCache>,
+}
+
+impl CachedEmbedder {
+    pub fn new(inner: Arc<dyn Embedder>, cache: Arc<dyn EmbeddingCache>) -> Self {
+        Self { inner, cache }
+    }
+}
+
+#[async_trait]
+impl Embedder for CachedEmbedder {
+    fn model(&self) -> &str {
+        self.inner.model()
+    }
+
+    fn cache_key(&self) -> String {
+        self.inner.cache_key()
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let key = self.inner.cache_key();
+        let hashes: Vec<String> = texts.iter().map(|text| content_hash(text)).collect();
+        let mut cached = self.cache.get(&key, &hashes).await.unwrap_or_else(|e| {
+            tracing::warn!("Embedding cache unavailable: {:#}", e);
+            HashMap::new()
+        });
+
+        // Embed each missing text once, even if it appears several times
+        let mut missing: Vec<(String, String)> = Vec::new();
+        let mut seen: HashSet<&str> = HashSet::new();
+        for (hash, text) in hashes.iter().zip(texts) {
+            if !cached.contains_key(hash) && seen.insert(hash) {
+                missing.push((hash.clone(), text.clone()));
+            }
+        }
+        if !missing.is_empty() {
+            let missing_texts: Vec<String> = missing.iter().map(|(_, text)| text.clone()).collect();
+            let vectors = self.inner.embed(&missing_texts).await?;
+            let entries: Vec<(String, Vec<f32>)> = missing
+                .into_iter()
+                .map(|(hash, _)| hash)
+                .zip(vectors)
+                .collect();
+            if let Err(e) = self.cache.put(&key, &entries).await {
+                tracing::warn!("Failed to cache embeddings: {:#}", e);
+            }
+            cached.extend(entries);
+        }
+
+        hashes
+            .iter()
+            .map(|hash| {
+                cached
+                    .get(hash)
+                    .cloned()
+                    .context("Embedding missing from the cache")
+            })
+            .collect()
+    }
+}
+
+/// Embedding cache configured under `settings.embedding_cache`; only used when
+/// `settings.embeddings` is set
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(tag = "backend", rename_all = "lowercase")]
+pub enum EmbeddingCacheSettings {
+    /// The `embedding_caches` table of the application database
+    #[default]
+    Database,
+    Redis {
+        url: String,
+        #[serde(default = "default_redis_prefix")]
+        prefix: String,
+        /// Expire cached vectors after this long; kept forever by default
+        #[serde(default)]
+        ttl_secs: Option<u64>,
+    },
+    /// Always embed
+    None,
+}
+
+fn default_redis_prefix() -> String {
+    "akashic:embedding:".to_string()
+}
+
+/// Wrap `embedder` in the configured cache
+pub fn with_cache(
+    embedder: Arc<dyn Embedder>,
+    settings: &EmbeddingCacheSettings,
+    db: &DatabaseConnection,
+) -> Result<Arc<dyn Embedder>> {
+    let cache: Arc<dyn EmbeddingCache> = match settings {
+        EmbeddingCacheSettings::Database => Arc::new(DbEmbeddingCache::new(db.clone())),
+        EmbeddingCacheSettings::Redis {
+            url,
+            prefix,
+            ttl_secs,
+        } => Arc::new(RedisEmbeddingCache::new(url, prefix, *ttl_secs)?),
+        EmbeddingCacheSettings::None => return Ok(embedder),
+    };
+    Ok(Arc::new(CachedEmbedder::new(embedder, cache)))
+}
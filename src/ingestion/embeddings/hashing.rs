@@ -0,0 +1,70 @@
+use super::Embedder;
+use anyhow::Result;
+use async_trait::async_trait;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Test double hashing the words of a text into a fixed number of dimensions (the
+/// "hashing trick").
+///
+/// It needs no model files or network and always gives the same vector for the same
+/// text, so tests can embed without a model; it only measures shared words and is not
+/// offered as a provider in the settings.
+pub struct HashingEmbedder {
+    dimension: usize,
+    model: String,
+}
+
+impl HashingEmbedder {
+    pub fn new(dimension: usize) -> Self {
+        let dimension = dimension.max(1);
+        Self {
+            dimension,
+            model: format!("hashing-{}", dimension),
+        }
+    }
+
+    /// Length of the vectors
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    fn embed_one(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0; self.dimension];
+        for word in text.unicode_words() {
+            let hash = fnv1a(word.to_lowercase().as_bytes());
+            let index = (hash % self.dimension as u64) as usize;
+            // A hash bit picks the sign so that collisions tend to cancel out
+            vector[index] += if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        }
+
+        let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            vector.iter_mut().for_each(|x| *x /= norm);
+        }
+        vector
+    }
+}
+
+impl Default for HashingEmbedder {
+    fn default() -> Self {
+        Self::new(384)
+    }
+}
+
+#[async_trait]
+impl Embedder for HashingEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|text| self.embed_one(text)).collect())
+    }
+}
+
+/// 64-bit FNV-1a, which unlike the std hasher is stable across Rust releases
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
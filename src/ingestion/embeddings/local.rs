@@ -0,0 +1,155 @@
+use super::{check_vectors, Embedder};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use candle_core::{DType, Device, Tensor};
+use candle_nn::VarBuilder;
+use candle_transformers::models::bert::{BertModel, Config, DTYPE};
+use std::path::Path;
+use std::sync::Arc;
+use tokenizers::{PaddingParams, PaddingStrategy, Tokenizer, TruncationParams};
+
+/// Sentence-transformer model (BERT architecture, e.g. `all-MiniLM-L6-v2` or
+/// `bge-small-en-v1.5`) run on the CPU with candle.
+///
+/// The model directory is the one published on Hugging Face: `config.json`,
+/// `tokenizer.json` and `model.safetensors`. Token embeddings are mean-pooled over
+/// the attention mask and normalized to unit length.
+pub struct LocalEmbedder {
+    model: String,
+    /// Model directory and truncation length, which together with the name decide
+    /// the vectors
+    source: String,
+    inner: Arc<LocalModel>,
+    batch_size: usize,
+}
+
+struct LocalModel {
+    bert: BertModel,
+    tokenizer: Tokenizer,
+    device: Device,
+}
+
+impl LocalEmbedder {
+    /// Load the model in `dir`, truncating texts to `max_length` tokens
+    pub fn load(dir: &Path, max_length: usize) -> Result<Self> {
+        let config = std::fs::read_to_string(dir.join("config.json"))
+            .with_context(|| format!("Failed to read {}", dir.join("config.json").display()))?;
+        let config: Config = serde_json::from_str(&config).context("Invalid model config.json")?;
+
+        let mut tokenizer = Tokenizer::from_file(dir.join("tokenizer.json")).map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to load {}: {}",
+                dir.join("tokenizer.json").display(),
+                e
+            )
+        })?;
+        tokenizer.with_padding(Some(PaddingParams {
+            strategy: PaddingStrategy::BatchLongest,
+            ..Default::default()
+        }));
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: max_length.min(config.max_position_embeddings),
+                ..Default::default()
+            }))
+            .map_err(|e| anyhow::anyhow!("Invalid truncation settings: {}", e))?;
+
+        let device = Device::Cpu;
+        let weights = dir.join("model.safetensors");
+        // SAFETY: the weights file is memory-mapped read-only and must not be modified
+        // while the model is loaded, as with any model served from disk
+        let vb = unsafe { VarBuilder::from_mmaped_safetensors(&[&weights], DTYPE, &device) }
+            .with_context(|| format!("Failed to load {}", weights.display()))?;
+        let bert = BertModel::load(vb, &config).context("Failed to load the BERT model")?;
+
+        let model = dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "local".to_string());
+        Ok(Self {
+            model,
+            source: format!("{}@{}", dir.display(), max_length),
+            inner: Arc::new(LocalModel {
+                bert,
+                tokenizer,
+                device,
+            }),
+            batch_size: 32,
+        })
+    }
+
+    /// Name recorded alongside the vectors, instead of the directory name
+    pub fn with_model_name(mut self, model: &str) -> Self {
+        self.model = model.to_string();
+        self
+    }
+
+    /// Most texts run through the model at once
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+}
+
+impl LocalModel {
+    fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let encodings = self
+            .tokenizer
+            .encode_batch(texts.to_vec(), true)
+            .map_err(|e| anyhow::anyhow!("Failed to tokenize: {}", e))?;
+
+        let ids: Vec<Tensor> = encodings
+            .iter()
+            .map(|encoding| Tensor::new(encoding.get_ids(), &self.device))
+            .collect::<candle_core::Result<_>>()?;
+        let masks: Vec<Tensor> = encodings
+            .iter()
+            .map(|encoding| Tensor::new(encoding.get_attention_mask(), &self.device))
+            .collect::<candle_core::Result<_>>()?;
+        let input_ids = Tensor::stack(&ids, 0)?;
+        let attention_mask = Tensor::stack(&masks, 0)?;
+        let token_type_ids = input_ids.zeros_like()?;
+
+        let hidden = self
+            .bert
+            .forward(&input_ids, &token_type_ids, Some(&attention_mask))?;
+
+        // Mean of the token embeddings, ignoring padding
+        let mask = attention_mask.to_dtype(DType::F32)?.unsqueeze(2)?;
+        let summed = hidden.broadcast_mul(&mask)?.sum(1)?;
+        let counts = mask.sum(1)?.clamp(1e-9, f32::MAX)?;
+        let pooled = summed.broadcast_div(&counts)?;
+        let norms = pooled
+            .sqr()?
+            .sum_keepdim(1)?
+            .sqrt()?
+            .clamp(1e-12, f32::MAX)?;
+        Ok(pooled.broadcast_div(&norms)?.to_vec2::<f32>()?)
+    }
+}
+
+#[async_trait]
+impl Embedder for LocalEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn cache_key(&self) -> String {
+        format!("{} {}", self.model, self.source)
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(self.batch_size) {
+            // Inference is CPU-bound, so keep it off the async runtime's threads
+            let inner = self.inner.clone();
+            let batch = batch.to_vec();
+            vectors.extend(
+                tokio::task::spawn_blocking(move || inner.embed_batch(&batch))
+                    .await
+                    .context("Embedding task panicked")??,
+            );
+        }
+        check_vectors(texts, vectors)
+    }
+}
@@ -0,0 +1,174 @@
+mod cache;
+mod hashing;
+mod local;
+mod ollama;
+mod openai;
+
+pub use cache::{
+    content_hash, with_cache, CachedEmbedder, DbEmbeddingCache, EmbeddingCache,
+    EmbeddingCacheSettings, RedisEmbeddingCache,
+};
+pub use hashing::HashingEmbedder;
+pub use local::LocalEmbedder;
+pub use ollama::OllamaEmbedder;
+pub use openai::OpenAiEmbedder;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// Trait for turning texts into embedding vectors
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    /// Name of the model, recorded alongside the vectors it produced
+    fn model(&self) -> &str;
+
+    /// What the vectors are cached under: the model plus anything else that changes
+    /// them, such as the dimensions asked for or the server running the model
+    fn cache_key(&self) -> String {
+        self.model().to_string()
+    }
+
+    /// One vector per text, in order
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>>;
+}
+
+/// Embedding model configured under `settings.embeddings`:
+///
+/// ```yaml
+/// settings:
+///   embeddings:
+///     provider: openai
+///     model: text-embedding-3-small
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "provider", rename_all = "lowercase")]
+pub enum EmbeddingSettings {
+    /// An OpenAI-compatible `/embeddings` endpoint
+    #[serde(rename = "openai")]
+    OpenAi {
+        model: String,
+        #[serde(default = "default_openai_url")]
+        url: String,
+        /// Falls back to the `OPENAI_API_KEY` environment variable
+        #[serde(default)]
+        api_key: Option<String>,
+        /// Shorter vectors, for models that support it
+        #[serde(default)]
+        dimensions: Option<usize>,
+        #[serde(default = "default_batch_size")]
+        batch_size: usize,
+    },
+    /// A local Ollama server
+    Ollama {
+        model: String,
+        #[serde(default = "default_ollama_url")]
+        url: String,
+        #[serde(default = "default_batch_size")]
+        batch_size: usize,
+    },
+    /// A sentence-transformer model on disk, run on the CPU
+    Local {
+        /// Directory holding `config.json`, `tokenizer.json` and `model.safetensors`
+        path: std::path::PathBuf,
+        /// Name recorded with the vectors; defaults to the directory name
+        #[serde(default)]
+        model: Option<String>,
+        /// Longest text in tokens; longer texts are truncated
+        #[serde(default = "default_max_length")]
+        max_length: usize,
+        #[serde(default = "default_local_batch_size")]
+        batch_size: usize,
+    },
+}
+
+fn default_openai_url() -> String {
+    "https://api.openai.com/v1".to_string()
+}
+
+fn default_ollama_url() -> String {
+    "http://localhost:11434".to_string()
+}
+
+fn default_batch_size() -> usize {
+    64
+}
+
+fn default_max_length() -> usize {
+    256
+}
+
+fn default_local_batch_size() -> usize {
+    32
+}
+
+/// Create an embedder from its settings
+pub fn create_embedder(settings: &EmbeddingSettings) -> Result<Arc<dyn Embedder>> {
+    match settings {
+        EmbeddingSettings::OpenAi {
+            model,
+            url,
+            api_key,
+            dimensions,
+            batch_size,
+        } => {
+            let api_key = api_key
+                .clone()
+                .or_else(|| std::env::var("OPENAI_API_KEY").ok());
+            Ok(Arc::new(
+                OpenAiEmbedder::new(url, model, api_key)?
+                    .with_dimensions(*dimensions)
+                    .with_batch_size(*batch_size),
+            ))
+        }
+        EmbeddingSettings::Ollama {
+            model,
+            url,
+            batch_size,
+        } => Ok(Arc::new(
+            OllamaEmbedder::new(url, model)?.with_batch_size(*batch_size),
+        )),
+        EmbeddingSettings::Local {
+            path,
+            model,
+            max_length,
+            batch_size,
+        } => {
+            let mut embedder = LocalEmbedder::load(path, *max_length)
+                .with_context(|| {
+                    format!("Failed to load the embedding model in {}", path.display())
+                })?
+                .with_batch_size(*batch_size);
+            if let Some(model) = model {
+                embedder = embedder.with_model_name(model);
+            }
+            Ok(Arc::new(embedder))
+        }
+    }
+}
+
+/// Cosine of the angle between two vectors; 0 when either is all zeros
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+/// Make sure a provider returned one vector per text, all of the same length
+fn check_vectors(texts: &[String], vectors: Vec<Vec<f32>>) -> Result<Vec<Vec<f32>>> {
+    if vectors.len() != texts.len() {
+        anyhow::bail!("Expected {} embeddings, got {}", texts.len(), vectors.len());
+    }
+    if let Some(first) = vectors.first() {
+        if vectors.iter().any(|vector| vector.len() != first.len()) {
+            anyhow::bail!("Embeddings of different lengths");
+        }
+    }
+    Ok(vectors)
+}
@@ -0,0 +1,82 @@
+use super::{check_vectors, Embedder};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+
+/// Embedder calling a local Ollama server's `/api/embed` endpoint
+pub struct OllamaEmbedder {
+    client: reqwest::Client,
+    url: String,
+    model: String,
+    batch_size: usize,
+}
+
+#[derive(Deserialize)]
+struct EmbedResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+impl OllamaEmbedder {
+    /// `url` is the server, e.g. `http://localhost:11434`
+    pub fn new(url: &str, model: &str) -> Result<Self> {
+        Ok(Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(300))
+                .build()
+                .context("Failed to create HTTP client")?,
+            url: url.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+            batch_size: 64,
+        })
+    }
+
+    /// Most texts sent in one request
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let response = self
+            .client
+            .post(format!("{}/api/embed", self.url))
+            .json(&json!({
+                "model": self.model,
+                "input": texts,
+            }))
+            .send()
+            .await
+            .context("Failed to send request to Ollama")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Ollama request failed ({}): {}", status, error_text);
+        }
+
+        let response: EmbedResponse = response.json().await.context("Malformed Ollama response")?;
+        Ok(response.embeddings)
+    }
+}
+
+#[async_trait]
+impl Embedder for OllamaEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// The model and the server, e.g. `nomic-embed-text http://localhost:11434`
+    fn cache_key(&self) -> String {
+        format!("{} {}", self.model, self.url)
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(self.batch_size) {
+            vectors.extend(self.embed_batch(batch).await?);
+        }
+        check_vectors(texts, vectors)
+    }
+}
@@ -0,0 +1,120 @@
+use super::{check_vectors, Embedder};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+
+/// Embedder calling an OpenAI-compatible `/embeddings` endpoint (OpenAI, Azure
+/// OpenAI deployments, vLLM, LM Studio, LocalAI, ...)
+pub struct OpenAiEmbedder {
+    client: reqwest::Client,
+    url: String,
+    model: String,
+    api_key: Option<String>,
+    dimensions: Option<usize>,
+    batch_size: usize,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+impl OpenAiEmbedder {
+    /// `url` is the API base, e.g. `https://api.openai.com/v1`
+    pub fn new(url: &str, model: &str, api_key: Option<String>) -> Result<Self> {
+        Ok(Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(120))
+                .build()
+                .context("Failed to create HTTP client")?,
+            url: url.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+            api_key,
+            dimensions: None,
+            batch_size: 64,
+        })
+    }
+
+    /// Ask for shorter vectors, for models that support it (`text-embedding-3-*`)
+    pub fn with_dimensions(mut self, dimensions: Option<usize>) -> Self {
+        self.dimensions = dimensions;
+        self
+    }
+
+    /// Most texts sent in one request
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut body = json!({
+            "model": self.model,
+            "input": texts,
+        });
+        if let Some(dimensions) = self.dimensions {
+            body["dimensions"] = json!(dimensions);
+        }
+
+        let mut request = self
+            .client
+            .post(format!("{}/embeddings", self.url))
+            .json(&body);
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+        let response = request
+            .send()
+            .await
+            .context("Failed to send request to the embedding API")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Embedding request failed ({}): {}", status, error_text);
+        }
+
+        let mut response: EmbeddingResponse = response
+            .json()
+            .await
+            .context("Malformed embedding response")?;
+        response.data.sort_by_key(|data| data.index);
+        Ok(response
+            .data
+            .into_iter()
+            .map(|data| data.embedding)
+            .collect())
+    }
+}
+
+#[async_trait]
+impl Embedder for OpenAiEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// `model@dimensions` and the API base, e.g.
+    /// `text-embedding-3-small@256 https://api.openai.com/v1`
+    fn cache_key(&self) -> String {
+        match self.dimensions {
+            Some(dimensions) => format!("{}@{} {}", self.model, dimensions, self.url),
+            None => format!("{} {}", self.model, self.url),
+        }
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(self.batch_size) {
+            vectors.extend(self.embed_batch(batch).await?);
+        }
+        check_vectors(texts, vectors)
+    }
+}
@@ -0,0 +1,187 @@
+use super::attachment_path;
+use crate::ingestion::{Attachment, ExtractOptions, ExtractedDocument, Extractor};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use flate2::read::GzDecoder;
+use serde_json::{Map, Value};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use zip::ZipArchive;
+
+/// Most files a single archive may unpack to. Archives inside archives are not unpacked,
+/// so these limits also bound everything ingested from one upload.
+pub const MAX_ARCHIVE_ENTRIES: usize = 10_000;
+/// Largest uncompressed size of a single archive entry
+pub const MAX_ENTRY_BYTES: u64 = 256 * 1024 * 1024;
+/// Largest uncompressed size of all entries of an archive together
+pub const MAX_ARCHIVE_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Archive Extractor (ZIP, tar and gzip-compressed tar).
+///
+/// Archives have no text of their own: their entries are unpacked and ingested as
+/// child documents.
+pub struct ArchiveExtractor;
+
+#[async_trait]
+impl Extractor for ArchiveExtractor {
+    /// Nothing: the entries are ingested as documents of their own
+    async fn extract(
+        &self,
+        _file_path: &str,
+        _options: &ExtractOptions,
+    ) -> Result<ExtractedDocument> {
+        Ok(ExtractedDocument::default())
+    }
+
+    async fn extract_attachments(&self, file_path: &str, dir: &Path) -> Result<Vec<Attachment>> {
+        let path = file_path.to_string();
+        let dir = dir.to_path_buf();
+        tokio::task::spawn_blocking(move || unpack_archive(&path, &dir))
+            .await
+            .context("Archive extraction task panicked")?
+    }
+
+    fn supports(&self, file_path: &str) -> bool {
+        let path = file_path.to_lowercase();
+        [".zip", ".tar", ".tar.gz", ".tgz"]
+            .iter()
+            .any(|ext| path.ends_with(ext))
+    }
+
+    fn supports_mime_type(&self, mime_type: &str) -> bool {
+        matches!(
+            mime_type,
+            "application/zip"
+                | "application/x-zip-compressed"
+                | "application/x-tar"
+                | "application/gzip"
+                | "application/x-gzip"
+                | "application/x-gtar"
+        )
+    }
+}
+
+/// Unpack the regular files of a ZIP, tar or tar.gz archive into `dir`.
+///
+/// Directories, links and hidden files (including macOS `__MACOSX` resource forks) are
+/// skipped. Fails once the archive exceeds [`MAX_ARCHIVE_ENTRIES`] files, an entry
+/// exceeds [`MAX_ENTRY_BYTES`] or all entries together exceed [`MAX_ARCHIVE_BYTES`],
+/// counting the bytes actually unpacked rather than the sizes the archive declares.
+pub fn unpack_archive(file_path: &str, dir: &Path) -> Result<Vec<Attachment>> {
+    let mut magic = [0u8; 2];
+    File::open(file_path)
+        .and_then(|mut f| f.read_exact(&mut magic))
+        .context("Failed to read archive")?;
+
+    let mut unpacker = Unpacker {
+        dir,
+        entries: 0,
+        bytes: 0,
+        attachments: Vec::new(),
+    };
+    match magic {
+        [b'P', b'K'] => unpack_zip(file_path, &mut unpacker)?,
+        [0x1f, 0x8b] => {
+            let file = File::open(file_path).context("Failed to open archive")?;
+            unpack_tar(GzDecoder::new(file), &mut unpacker)?;
+        }
+        _ => {
+            let file = File::open(file_path).context("Failed to open archive")?;
+            unpack_tar(file, &mut unpacker)?;
+        }
+    }
+    Ok(unpacker.attachments)
+}
+
+fn unpack_zip(file_path: &str, unpacker: &mut Unpacker) -> Result<()> {
+    let file = File::open(file_path).context("Failed to open archive")?;
+    let mut archive = ZipArchive::new(file).context("Failed to read ZIP archive")?;
+
+    for index in 0..archive.len() {
+        let entry = match archive.by_index(index) {
+            Ok(entry) => entry,
+            // Encrypted or unsupported entries should not stop the others
+            Err(e) => {
+                tracing::warn!("Skipping ZIP entry {} of {}: {}", index, file_path, e);
+                continue;
+            }
+        };
+        if !entry.is_file() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        unpacker.unpack(&name, entry)?;
+    }
+    Ok(())
+}
+
+fn unpack_tar(reader: impl Read, unpacker: &mut Unpacker) -> Result<()> {
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries().context("Failed to read tar archive")? {
+        let entry = entry.context("Failed to read tar archive")?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let name = entry
+            .path()
+            .context("Invalid path in tar archive")?
+            .to_string_lossy()
+            .to_string();
+        unpacker.unpack(&name, entry)?;
+    }
+    Ok(())
+}
+
+/// Writes archive entries to disk while enforcing the archive limits
+struct Unpacker<'a> {
+    dir: &'a Path,
+    entries: usize,
+    bytes: u64,
+    attachments: Vec<Attachment>,
+}
+
+impl Unpacker<'_> {
+    fn unpack(&mut self, name: &str, reader: impl Read) -> Result<()> {
+        self.entries += 1;
+        if self.entries > MAX_ARCHIVE_ENTRIES {
+            anyhow::bail!("Archive has more than {} files", MAX_ARCHIVE_ENTRIES);
+        }
+        if is_hidden(name) {
+            return Ok(());
+        }
+
+        let path = attachment_path(self.dir, self.attachments.len(), name);
+        let mut file = File::create(&path).context("Failed to write archive entry")?;
+        // Read one byte past the limit to tell a file of exactly the limit from a larger one
+        let written = std::io::copy(&mut reader.take(MAX_ENTRY_BYTES + 1), &mut file)
+            .with_context(|| format!("Failed to unpack archive entry {}", name))?;
+        if written > MAX_ENTRY_BYTES {
+            anyhow::bail!(
+                "Archive entry {} is larger than {} bytes",
+                name,
+                MAX_ENTRY_BYTES
+            );
+        }
+        self.bytes += written;
+        if self.bytes > MAX_ARCHIVE_BYTES {
+            anyhow::bail!("Archive unpacks to more than {} bytes", MAX_ARCHIVE_BYTES);
+        }
+
+        let filename = name.rsplit('/').next().unwrap_or(name).to_string();
+        let mut metadata = Map::new();
+        metadata.insert("archive_path".to_string(), Value::String(name.to_string()));
+        self.attachments.push(Attachment {
+            filename,
+            path,
+            content_type: None,
+            metadata,
+        });
+        Ok(())
+    }
+}
+
+fn is_hidden(name: &str) -> bool {
+    name.split('/')
+        .any(|part| (part.starts_with('.') && part != "." && part != "..") || part == "__MACOSX")
+}
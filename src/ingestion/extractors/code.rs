@@ -0,0 +1,484 @@
+use super::decode_text;
+use crate::ingestion::{ExtractOptions, ExtractedDocument, Extractor, Section};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use regex::Regex;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Source Code Extractor (Rust, Python, TypeScript, JavaScript, Go and Java)
+pub struct CodeExtractor;
+
+#[async_trait]
+impl Extractor for CodeExtractor {
+    /// One section per top-level item, tagged with `language`, `symbol`, `line_start`
+    /// and `line_end`; the document is tagged with its `language`
+    async fn extract(
+        &self,
+        file_path: &str,
+        options: &ExtractOptions,
+    ) -> Result<ExtractedDocument> {
+        let language = CodeLanguage::from_path(file_path)
+            .or_else(|| {
+                options
+                    .content_type
+                    .as_deref()
+                    .and_then(CodeLanguage::from_mime_type)
+            })
+            .context("Unrecognised programming language")?;
+
+        let bytes = tokio::fs::read(file_path)
+            .await
+            .context("Failed to read source file")?;
+        let (source, _) = decode_text(&bytes, options.encoding.as_deref())?;
+        Ok(ExtractedDocument::new(split_code(&source, language)).with("language", language.name()))
+    }
+
+    fn supports(&self, file_path: &str) -> bool {
+        CodeLanguage::from_path(file_path).is_some()
+    }
+
+    fn supports_mime_type(&self, mime_type: &str) -> bool {
+        CodeLanguage::from_mime_type(mime_type).is_some()
+    }
+}
+
+/// Programming languages the code extractor splits into items
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodeLanguage {
+    Rust,
+    Python,
+    TypeScript,
+    JavaScript,
+    Go,
+    Java,
+}
+
+impl CodeLanguage {
+    /// Detect the language from a file's extension
+    pub fn from_path(file_path: &str) -> Option<Self> {
+        let extension = Path::new(file_path).extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "rs" => Some(Self::Rust),
+            "py" | "pyi" | "pyw" => Some(Self::Python),
+            "ts" | "tsx" | "mts" | "cts" => Some(Self::TypeScript),
+            "js" | "jsx" | "mjs" | "cjs" => Some(Self::JavaScript),
+            "go" => Some(Self::Go),
+            "java" => Some(Self::Java),
+            _ => None,
+        }
+    }
+
+    pub fn from_mime_type(mime_type: &str) -> Option<Self> {
+        match mime_type {
+            "text/rust" | "text/x-rust" => Some(Self::Rust),
+            "text/x-python" | "text/x-script.python" | "application/x-python" => Some(Self::Python),
+            "application/typescript" | "text/typescript" | "text/x-typescript" => {
+                Some(Self::TypeScript)
+            }
+            "application/javascript" | "text/javascript" | "application/x-javascript" => {
+                Some(Self::JavaScript)
+            }
+            "text/x-go" => Some(Self::Go),
+            "text/x-java" | "text/x-java-source" => Some(Self::Java),
+            _ => None,
+        }
+    }
+
+    /// Lowercase name stored in chunk metadata, e.g. `rust`
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Rust => "rust",
+            Self::Python => "python",
+            Self::TypeScript => "typescript",
+            Self::JavaScript => "javascript",
+            Self::Go => "go",
+            Self::Java => "java",
+        }
+    }
+
+    fn line_comment(self) -> &'static str {
+        match self {
+            Self::Python => "#",
+            _ => "//",
+        }
+    }
+
+    /// Whether a trimmed line starts an attribute, annotation or decorator
+    fn is_attribute(self, line: &str) -> bool {
+        match self {
+            Self::Rust => line.starts_with("#[") || line.starts_with("#!["),
+            Self::Go => false,
+            _ => line.starts_with('@') && !line.starts_with("@interface"),
+        }
+    }
+}
+
+/// Split source code into one section per top-level item.
+///
+/// Items are found from bracket nesting and indentation, skipping strings and comments,
+/// rather than parsed: a top-level item starts on an unindented line outside any
+/// bracket that does not continue the previous line. Comments, attributes and
+/// decorators directly above an item belong to it. Consecutive items without a
+/// `symbol` (imports, constants, top-level statements) are merged into one section.
+pub fn split_code(source: &str, language: CodeLanguage) -> Vec<Section> {
+    let lines: Vec<&str> = source.lines().collect();
+    let scanned = scan_lines(source, language);
+
+    // Whether each line can start an item. Comment lines at the margin only do when the
+    // code below them does, so commented-out code inside a Python function stays in it
+    let mut boundaries = vec![false; lines.len()];
+    let mut previous_code: Option<&ScannedLine> = None;
+    for (index, line) in lines.iter().enumerate() {
+        let info = &scanned[index];
+        boundaries[index] = is_boundary(line, info, previous_code);
+        if info.last.is_some() {
+            previous_code = Some(info);
+        }
+    }
+    let mut code_below = true;
+    for index in (0..lines.len()).rev() {
+        let info = &scanned[index];
+        if info.first.is_some() {
+            code_below = boundaries[index];
+        } else {
+            boundaries[index] &= code_below;
+        }
+    }
+
+    // Contiguous line ranges, each starting at a boundary
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() && !scanned[index].continued {
+            continue;
+        }
+        if ranges.is_empty() || boundaries[index] {
+            ranges.push((index, index));
+        } else if let Some(range) = ranges.last_mut() {
+            range.1 = index;
+        }
+    }
+
+    // Attach comments, attributes and decorators to the item right below them
+    let mut items: Vec<(usize, usize, Option<String>)> = Vec::new();
+    let mut pending: Option<usize> = None;
+    for (position, &(start, end)) in ranges.iter().enumerate() {
+        let start = pending.take().unwrap_or(start);
+        let is_prefix = (start..=end).all(|i| {
+            scanned[i].last.is_none()
+                || scanned[i].depth_start > 0
+                || language.is_attribute(lines[i].trim())
+        });
+        let next_adjacent = ranges
+            .get(position + 1)
+            .is_some_and(|&(next, _)| next == end + 1);
+        if is_prefix && next_adjacent {
+            pending = Some(start);
+            continue;
+        }
+
+        let symbol = (start..=end)
+            .find(|&i| {
+                scanned[i].last.is_some()
+                    && scanned[i].depth_start == 0
+                    && !language.is_attribute(lines[i].trim())
+            })
+            .and_then(|i| item_symbol(language, lines[i].trim()));
+        match items.last_mut() {
+            Some(last) if symbol.is_none() && last.2.is_none() => last.1 = end,
+            _ => items.push((start, end, symbol)),
+        }
+    }
+
+    items
+        .into_iter()
+        .map(|(start, end, symbol)| {
+            let text = lines[start..=end]
+                .iter()
+                .map(|line| line.trim_end())
+                .collect::<Vec<_>>()
+                .join("\n");
+            let mut section = Section::new(text).with("language", language.name());
+            if let Some(symbol) = symbol {
+                section = section.with("symbol", symbol);
+            }
+            section
+                .with("line_start", start + 1)
+                .with("line_end", end + 1)
+        })
+        .collect()
+}
+
+/// Tokens that, at the start of an unindented line, continue the item above
+const CONTINUATION_STARTS: &[&str] = &[
+    "{", "}", ")", "]", ".", "?", ":", "=", "+", "-", "*", "&&", "||", "|", "where", "else",
+    "elif", "except", "finally", "catch",
+];
+
+/// Characters that, at the end of a line, make the next line part of the same item
+const CONTINUATION_ENDS: &[char] = &[',', '=', '\\', '+', '-', '*', '&', '|'];
+
+fn is_boundary(line: &str, info: &ScannedLine, previous_code: Option<&ScannedLine>) -> bool {
+    if info.continued || info.depth_start > 0 || line.starts_with(char::is_whitespace) {
+        return false;
+    }
+    let trimmed = line.trim();
+    let continues_above = CONTINUATION_STARTS.iter().any(|token| {
+        let Some(rest) = trimmed.strip_prefix(token) else {
+            return false;
+        };
+        // Keywords must be whole words: `elsewhere` starts an item, `else` does not
+        !token.starts_with(char::is_alphabetic)
+            || !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_')
+    });
+    if info.first.is_some() && continues_above {
+        return false;
+    }
+    !previous_code
+        .and_then(|previous| previous.last)
+        .is_some_and(|last| CONTINUATION_ENDS.contains(&last))
+}
+
+/// The name of the item declared on `line`, e.g. `parse` or `impl Display for Token`
+fn item_symbol(language: CodeLanguage, line: &str) -> Option<String> {
+    static PATTERNS: OnceLock<Vec<(CodeLanguage, Regex)>> = OnceLock::new();
+    let patterns = PATTERNS.get_or_init(|| {
+        [
+            (
+                CodeLanguage::Rust,
+                r#"^(?:pub(?:\s*\([^)]*\))?\s+)?(?:(?:default|const|async|unsafe|extern(?:\s+"[^"]*")?)\s+)*(?:fn|struct|enum|union|trait|type|mod|static|const)\s+(?:mut\s+)?([A-Za-z_][A-Za-z0-9_]*)"#,
+            ),
+            (CodeLanguage::Rust, r"^macro_rules!\s*([A-Za-z_][A-Za-z0-9_]*)"),
+            (CodeLanguage::Python, r"^(?:async\s+)?(?:def|class)\s+([A-Za-z_][A-Za-z0-9_]*)"),
+            (
+                CodeLanguage::Go,
+                r"^func\s*\(\s*(?:[A-Za-z_][A-Za-z0-9_]*\s+)?\*?\s*([A-Za-z_][A-Za-z0-9_]*)[^)]*\)\s*([A-Za-z_][A-Za-z0-9_]*)",
+            ),
+            (CodeLanguage::Go, r"^(?:func|type)\s+([A-Za-z_][A-Za-z0-9_]*)"),
+            (
+                CodeLanguage::TypeScript,
+                r"^(?:export\s+)?(?:default\s+)?(?:declare\s+)?(?:abstract\s+)?(?:async\s+)?(?:function\*?|class|interface|type|enum|namespace|module|const|let|var)\s+([A-Za-z_$][A-Za-z0-9_$]*)",
+            ),
+            (
+                CodeLanguage::JavaScript,
+                r"^(?:export\s+)?(?:default\s+)?(?:async\s+)?(?:function\*?|class|const|let|var)\s+([A-Za-z_$][A-Za-z0-9_$]*)",
+            ),
+            (
+                CodeLanguage::Java,
+                r"(?:^|\s)(?:class|interface|enum|record|@interface)\s+([A-Za-z_$][A-Za-z0-9_$]*)",
+            ),
+        ]
+        .into_iter()
+        .map(|(language, pattern)| {
+            (
+                language,
+                Regex::new(pattern).expect("Failed to compile regex"),
+            )
+        })
+        .collect()
+    });
+
+    if language == CodeLanguage::Rust {
+        if let Some(symbol) = impl_symbol(line) {
+            return Some(symbol);
+        }
+    }
+    patterns
+        .iter()
+        .filter(|(pattern_language, _)| *pattern_language == language)
+        .find_map(|(_, pattern)| {
+            let captures = pattern.captures(line)?;
+            // Go methods are named after their receiver type, e.g. `Server.Start`
+            Some(match captures.get(2) {
+                Some(method) => format!("{}.{}", &captures[1], method.as_str()),
+                None => captures[1].to_string(),
+            })
+        })
+}
+
+/// `impl<T> Trait for Type<T> where ... {` becomes `impl Trait for Type<T>`
+fn impl_symbol(line: &str) -> Option<String> {
+    let rest = line.strip_prefix("unsafe ").unwrap_or(line);
+    let rest = rest.strip_prefix("impl")?;
+    if !rest.starts_with(['<', ' ']) {
+        return None;
+    }
+
+    let mut rest = rest.trim_start();
+    if rest.starts_with('<') {
+        let mut depth = 0;
+        let end = rest.char_indices().find_map(|(i, c)| {
+            match c {
+                '<' => depth += 1,
+                '>' => depth -= 1,
+                _ => {}
+            }
+            (depth == 0).then_some(i)
+        })?;
+        rest = &rest[end + 1..];
+    }
+    let rest = rest.split('{').next().unwrap_or(rest);
+    let rest = rest.split(" where").next().unwrap_or(rest).trim();
+    (!rest.is_empty()).then(|| format!("impl {}", rest))
+}
+
+/// What scanning a line of source found outside strings and comments
+#[derive(Debug, Default, Clone)]
+struct ScannedLine {
+    /// Bracket nesting at the start of the line
+    depth_start: usize,
+    /// Whether the line starts inside a string or block comment
+    continued: bool,
+    /// First and last characters of code, counting string delimiters
+    first: Option<char>,
+    last: Option<char>,
+}
+
+enum ScanState {
+    Code,
+    BlockComment(usize),
+    Str { close: Vec<char>, escapes: bool },
+}
+
+/// Track bracket nesting line by line, skipping the contents of strings and comments
+fn scan_lines(source: &str, language: CodeLanguage) -> Vec<ScannedLine> {
+    let chars: Vec<char> = source.chars().collect();
+    let comment: Vec<char> = language.line_comment().chars().collect();
+    let starts_with = |i: usize, pattern: &[char]| chars[i..].starts_with(pattern);
+
+    let mut lines = vec![ScannedLine::default()];
+    let mut state = ScanState::Code;
+    let mut depth = 0usize;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\n' {
+            lines.push(ScannedLine {
+                depth_start: depth,
+                continued: !matches!(state, ScanState::Code),
+                ..ScannedLine::default()
+            });
+            i += 1;
+            continue;
+        }
+        let line = lines.last_mut().expect("lines is never empty");
+
+        match state {
+            ScanState::BlockComment(ref mut nesting) => {
+                if starts_with(i, &['*', '/']) {
+                    *nesting -= 1;
+                    if *nesting == 0 {
+                        state = ScanState::Code;
+                    }
+                    i += 2;
+                } else if language == CodeLanguage::Rust && starts_with(i, &['/', '*']) {
+                    *nesting += 1;
+                    i += 2;
+                } else {
+                    i += 1;
+                }
+            }
+            ScanState::Str { ref close, escapes } => {
+                if escapes && c == '\\' {
+                    i += 2;
+                } else if starts_with(i, close) {
+                    i += close.len();
+                    line.first.get_or_insert(c);
+                    line.last = Some(c);
+                    state = ScanState::Code;
+                } else {
+                    i += 1;
+                }
+            }
+            ScanState::Code => {
+                if starts_with(i, &comment) {
+                    while i < chars.len() && chars[i] != '\n' {
+                        i += 1;
+                    }
+                    continue;
+                }
+                if language != CodeLanguage::Python && starts_with(i, &['/', '*']) {
+                    state = ScanState::BlockComment(1);
+                    i += 2;
+                    continue;
+                }
+                if !c.is_whitespace() {
+                    line.first.get_or_insert(c);
+                    line.last = Some(c);
+                }
+
+                let (opened, length) = open_string(language, &chars, i);
+                if let Some(opened) = opened {
+                    state = opened;
+                    i += length;
+                    continue;
+                }
+                match c {
+                    '(' | '[' | '{' => depth += 1,
+                    ')' | ']' | '}' => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+                i += 1;
+            }
+        }
+    }
+
+    lines
+}
+
+/// The string literal starting at `i`, if any, and the length of its opening delimiter
+fn open_string(language: CodeLanguage, chars: &[char], i: usize) -> (Option<ScanState>, usize) {
+    let c = chars[i];
+    let string = |close: &str, escapes: bool| ScanState::Str {
+        close: close.chars().collect(),
+        escapes,
+    };
+    let is_ident = |index: usize| {
+        chars
+            .get(index)
+            .is_some_and(|c| c.is_alphanumeric() || *c == '_')
+    };
+
+    match language {
+        CodeLanguage::Python | CodeLanguage::Java if chars[i..].starts_with(&['"'; 3]) => {
+            (Some(string("\"\"\"", true)), 3)
+        }
+        CodeLanguage::Python if chars[i..].starts_with(&['\''; 3]) => {
+            (Some(string("'''", true)), 3)
+        }
+        // Raw strings: r"...", r#"..."#, br"..."
+        CodeLanguage::Rust if c == 'r' && (i == 0 || !is_ident(i - 1) || chars[i - 1] == 'b') => {
+            let hashes = chars[i + 1..].iter().take_while(|c| **c == '#').count();
+            if chars.get(i + 1 + hashes) == Some(&'"') {
+                let close = format!("\"{}", "#".repeat(hashes));
+                (Some(string(&close, false)), hashes + 2)
+            } else {
+                (None, 0)
+            }
+        }
+        // Char literals, not lifetimes: 'a', '\n', '\u{1F600}'
+        CodeLanguage::Rust if c == '\'' => {
+            if chars.get(i + 1) == Some(&'\\') || chars.get(i + 2) == Some(&'\'') {
+                (Some(string("'", true)), 1)
+            } else {
+                (None, 0)
+            }
+        }
+        _ if c == '"' => (Some(string("\"", true)), 1),
+        CodeLanguage::Python
+        | CodeLanguage::TypeScript
+        | CodeLanguage::JavaScript
+        | CodeLanguage::Go
+        | CodeLanguage::Java
+            if c == '\'' =>
+        {
+            (Some(string("'", true)), 1)
+        }
+        CodeLanguage::TypeScript | CodeLanguage::JavaScript if c == '`' => {
+            (Some(string("`", true)), 1)
+        }
+        CodeLanguage::Go if c == '`' => (Some(string("`", false)), 1),
+        _ => (None, 0),
+    }
+}
@@ -0,0 +1,129 @@
+use super::decode_text;
+use super::spreadsheet::{sheet_sections, Sheet};
+use crate::ingestion::{ExtractOptions, ExtractedDocument, Extractor};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::path::Path;
+
+/// Delimiters recognised when a file does not say which one it uses
+const DELIMITERS: &[char] = &[',', ';', '\t', '|'];
+
+/// CSV Extractor (comma, semicolon, tab or pipe separated values)
+pub struct CsvExtractor;
+
+#[async_trait]
+impl Extractor for CsvExtractor {
+    /// Sections of up to 25 rows, tagged with `sheet` (the file name) and their row range
+    async fn extract(
+        &self,
+        file_path: &str,
+        options: &ExtractOptions,
+    ) -> Result<ExtractedDocument> {
+        let bytes = tokio::fs::read(file_path)
+            .await
+            .context("Failed to read CSV file")?;
+        let (text, _) = decode_text(&bytes, options.encoding.as_deref())?;
+
+        let lower = file_path.to_lowercase();
+        let delimiter = if lower.ends_with(".tsv") || lower.ends_with(".tab") {
+            '\t'
+        } else {
+            detect_delimiter(&text)
+        };
+
+        let name = Path::new(file_path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let rows = parse_delimited(&text, delimiter)
+            .into_iter()
+            .enumerate()
+            .map(|(idx, row)| (idx + 1, row))
+            .collect();
+
+        Ok(ExtractedDocument::new(sheet_sections(&[Sheet {
+            name,
+            rows,
+        }])))
+    }
+
+    fn supports(&self, file_path: &str) -> bool {
+        let lower = file_path.to_lowercase();
+        lower.ends_with(".csv") || lower.ends_with(".tsv") || lower.ends_with(".tab")
+    }
+
+    fn supports_mime_type(&self, mime_type: &str) -> bool {
+        matches!(
+            mime_type,
+            "text/csv" | "application/csv" | "text/tab-separated-values"
+        )
+    }
+}
+
+/// The delimiter that occurs most often (outside quotes) in the first line
+fn detect_delimiter(text: &str) -> char {
+    let mut counts = [0usize; DELIMITERS.len()];
+    let mut in_quotes = false;
+    for c in text.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '\n' | '\r' if !in_quotes => break,
+            _ if !in_quotes => {
+                if let Some(idx) = DELIMITERS.iter().position(|d| *d == c) {
+                    counts[idx] += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    counts
+        .iter()
+        .enumerate()
+        .filter(|(_, count)| **count > 0)
+        .max_by_key(|(idx, count)| (**count, std::cmp::Reverse(*idx)))
+        .map_or(',', |(idx, _)| DELIMITERS[idx])
+}
+
+/// Split delimited text into rows of fields following RFC 4180 quoting: quoted fields
+/// may contain delimiters and line breaks, and `""` is an escaped quote
+pub fn parse_delimited(text: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            '\r' | '\n' => {
+                if c == '\r' && chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ if c == delimiter => row.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
@@ -0,0 +1,410 @@
+use super::docx::extract_docx;
+use crate::ingestion::{ExtractOptions, ExtractedDocument, Extractor, Section};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::collections::HashSet;
+use std::io::Read;
+
+/// Magic number at the start of the Word 97-2003 File Information Block
+const WORD_IDENT: u16 = 0xA5EC;
+/// Lowest `nFib` written by Word 97; older formats have no piece table
+const MIN_WORD97_NFIB: u16 = 0x00C1;
+/// Index of the `fcClx`/`lcbClx` pair in `FibRgFcLcb97`
+const CLX_PAIR_INDEX: usize = 33;
+/// Index of the `fcPlcfBtePapx`/`lcbPlcfBtePapx` pair in `FibRgFcLcb97`
+const PAPX_PAIR_INDEX: usize = 13;
+/// Size of the formatted disk pages holding paragraph properties
+const FKP_SIZE: usize = 512;
+/// `sprmPFTtp`: the paragraph is the mark ending a table row
+const SPRM_P_F_TTP: u16 = 0x2417;
+/// `sprmTDefTable`, whose operand is preceded by a 2-byte size
+const SPRM_T_DEF_TABLE: u16 = 0xD608;
+
+/// DOC Extractor (Word 97-2003 binary documents stored in a Compound File)
+pub struct DocExtractor;
+
+#[async_trait]
+impl Extractor for DocExtractor {
+    async fn extract(
+        &self,
+        file_path: &str,
+        _options: &ExtractOptions,
+    ) -> Result<ExtractedDocument> {
+        let path = file_path.to_string();
+        let text = tokio::task::spawn_blocking(move || extract_doc(&path))
+            .await
+            .context("DOC extraction task panicked")??;
+        Ok(ExtractedDocument::new(vec![Section::new(text)]))
+    }
+
+    fn supports(&self, file_path: &str) -> bool {
+        file_path.to_lowercase().ends_with(".doc")
+    }
+
+    fn supports_mime_type(&self, mime_type: &str) -> bool {
+        mime_type == "application/msword"
+    }
+}
+
+/// Extract the main text, footnotes and endnotes of a Word 97-2003 document.
+///
+/// Files named `.doc` that are really DOCX packages are handed to the DOCX reader.
+pub fn extract_doc(file_path: &str) -> Result<String> {
+    let mut magic = [0u8; 4];
+    std::fs::File::open(file_path)
+        .and_then(|mut f| f.read_exact(&mut magic))
+        .context("Failed to read DOC file")?;
+    if magic == *b"PK\x03\x04" {
+        return extract_docx(file_path);
+    }
+
+    let mut compound = cfb::open(file_path).context("DOC file is not a Compound File")?;
+
+    let word_document = read_stream(&mut compound, "/WordDocument")
+        .context("DOC file has no WordDocument stream")?;
+    let fib = Fib::parse(&word_document)?;
+
+    let table_name = if fib.use_1table { "/1Table" } else { "/0Table" };
+    let table = read_stream(&mut compound, table_name)
+        .with_context(|| format!("DOC file has no {} stream", &table_name[1..]))?;
+
+    let pieces = parse_piece_table(&table, fib.fc_clx, fib.lcb_clx)?;
+    let text = read_pieces(&word_document, &pieces)?;
+    let row_ends = row_end_marks(&word_document, &table, &fib, &pieces);
+
+    let mut blocks = Vec::new();
+    for (start, len) in fib.story_ranges() {
+        let story: String = text.chars().skip(start).take(len).collect();
+        let row_ends = row_ends
+            .as_ref()
+            .map(|marks| move |index: usize| marks.contains(&(start + index)));
+        blocks.extend(clean_story(&story, row_ends));
+    }
+
+    Ok(blocks.join("\n\n"))
+}
+
+fn read_stream(compound: &mut cfb::CompoundFile<std::fs::File>, name: &str) -> Result<Vec<u8>> {
+    let mut stream = compound.open_stream(name)?;
+    let mut buffer = Vec::new();
+    stream.read_to_end(&mut buffer)?;
+    Ok(buffer)
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .context("Unexpected end of DOC structure")
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .context("Unexpected end of DOC structure")
+}
+
+/// The parts of the File Information Block needed to locate the text
+struct Fib {
+    use_1table: bool,
+    fc_clx: usize,
+    lcb_clx: usize,
+    fc_plcf_bte_papx: usize,
+    lcb_plcf_bte_papx: usize,
+    ccp_text: usize,
+    ccp_ftn: usize,
+    ccp_hdd: usize,
+    ccp_atn: usize,
+    ccp_edn: usize,
+}
+
+impl Fib {
+    fn parse(data: &[u8]) -> Result<Self> {
+        if read_u16(data, 0)? != WORD_IDENT {
+            anyhow::bail!("WordDocument stream has an invalid signature");
+        }
+        let n_fib = read_u16(data, 2)?;
+        if n_fib < MIN_WORD97_NFIB {
+            anyhow::bail!(
+                "Word documents older than Word 97 are not supported (nFib {:#x})",
+                n_fib
+            );
+        }
+
+        let flags = read_u16(data, 0x0A)?;
+        if flags & 0x0100 != 0 {
+            anyhow::bail!("Encrypted DOC files are not supported");
+        }
+
+        // FibBase is 32 bytes, followed by the variable-length FibRgW, FibRgLw and FibRgFcLcb
+        let csw = usize::from(read_u16(data, 32)?);
+        let rg_lw = 32 + 2 + csw * 2 + 2;
+        let cslw = usize::from(read_u16(data, rg_lw - 2)?);
+        let rg_fc_lcb = rg_lw + cslw * 4 + 2;
+        let lw =
+            |index: usize| -> Result<usize> { Ok(read_u32(data, rg_lw + index * 4)? as usize) };
+        let clx = rg_fc_lcb + CLX_PAIR_INDEX * 8;
+        let papx = rg_fc_lcb + PAPX_PAIR_INDEX * 8;
+
+        Ok(Self {
+            use_1table: flags & 0x0200 != 0,
+            fc_clx: read_u32(data, clx)? as usize,
+            lcb_clx: read_u32(data, clx + 4)? as usize,
+            fc_plcf_bte_papx: read_u32(data, papx)? as usize,
+            lcb_plcf_bte_papx: read_u32(data, papx + 4)? as usize,
+            ccp_text: lw(3)?,
+            ccp_ftn: lw(4)?,
+            ccp_hdd: lw(5)?,
+            ccp_atn: lw(7)?,
+            ccp_edn: lw(8)?,
+        })
+    }
+
+    /// Character ranges of the main document, footnotes and endnotes
+    fn story_ranges(&self) -> [(usize, usize); 3] {
+        let endnotes = self.ccp_text + self.ccp_ftn + self.ccp_hdd + self.ccp_atn;
+        [
+            (0, self.ccp_text),
+            (self.ccp_text, self.ccp_ftn),
+            (endnotes, self.ccp_edn),
+        ]
+    }
+}
+
+/// A run of characters stored contiguously in the WordDocument stream
+struct Piece {
+    char_count: usize,
+    offset: usize,
+    compressed: bool,
+}
+
+/// Parse the `Clx` structure: skip the `Prc` entries and decode the `PlcPcd`
+fn parse_piece_table(table: &[u8], fc_clx: usize, lcb_clx: usize) -> Result<Vec<Piece>> {
+    let clx = table
+        .get(fc_clx..fc_clx + lcb_clx)
+        .context("Piece table lies outside the table stream")?;
+
+    let mut pos = 0;
+    while clx.get(pos) == Some(&0x01) {
+        let cb_grpprl = usize::from(read_u16(clx, pos + 1)?);
+        pos += 3 + cb_grpprl;
+    }
+    if clx.get(pos) != Some(&0x02) {
+        anyhow::bail!("DOC piece table is missing");
+    }
+
+    let lcb = read_u32(clx, pos + 1)? as usize;
+    let plc = clx
+        .get(pos + 5..pos + 5 + lcb)
+        .context("DOC piece table is truncated")?;
+    if lcb < 4 {
+        return Ok(Vec::new());
+    }
+
+    // PlcPcd: (n + 1) character positions followed by n 8-byte piece descriptors
+    let count = (lcb - 4) / 12;
+    let descriptors = (count + 1) * 4;
+    let mut pieces = Vec::with_capacity(count);
+    for i in 0..count {
+        let cp_start = read_u32(plc, i * 4)? as usize;
+        let cp_end = read_u32(plc, (i + 1) * 4)? as usize;
+        let fc = read_u32(plc, descriptors + i * 8 + 2)?;
+        let compressed = fc & 0x4000_0000 != 0;
+        let fc = (fc & 0x3FFF_FFFF) as usize;
+
+        pieces.push(Piece {
+            char_count: cp_end.saturating_sub(cp_start),
+            offset: if compressed { fc / 2 } else { fc },
+            compressed,
+        });
+    }
+
+    Ok(pieces)
+}
+
+/// Decode every piece, 8-bit Windows-1252 or UTF-16LE, into one string of characters
+fn read_pieces(word_document: &[u8], pieces: &[Piece]) -> Result<String> {
+    let mut text = String::new();
+
+    for piece in pieces {
+        if piece.compressed {
+            let bytes = word_document
+                .get(piece.offset..piece.offset + piece.char_count)
+                .context("DOC text piece lies outside the WordDocument stream")?;
+            let (decoded, _) = encoding_rs::WINDOWS_1252.decode_without_bom_handling(bytes);
+            text.push_str(&decoded);
+        } else {
+            let bytes = word_document
+                .get(piece.offset..piece.offset + piece.char_count * 2)
+                .context("DOC text piece lies outside the WordDocument stream")?;
+            let units: Vec<u16> = bytes
+                .chunks_exact(2)
+                .map(|b| u16::from_le_bytes([b[0], b[1]]))
+                .collect();
+            text.push_str(&String::from_utf16_lossy(&units));
+        }
+    }
+
+    Ok(text)
+}
+
+/// Character positions of the `\x07` marks that end table rows rather than cells.
+///
+/// Those are the paragraphs whose properties carry `sprmPFTtp`, looked up through the
+/// `PlcBtePapx` and its formatted disk pages. `None` when the document has no paragraph
+/// properties to go by or they cannot be read.
+fn row_end_marks(
+    word_document: &[u8],
+    table: &[u8],
+    fib: &Fib,
+    pieces: &[Piece],
+) -> Option<HashSet<usize>> {
+    let plc = table.get(fib.fc_plcf_bte_papx..fib.fc_plcf_bte_papx + fib.lcb_plcf_bte_papx)?;
+    if plc.len() < 4 {
+        return None;
+    }
+
+    // PlcBtePapx: (n + 1) file offsets followed by n page numbers of PapxFkp pages
+    let count = (plc.len() - 4) / 8;
+    let mut row_end_runs = Vec::new();
+    for i in 0..count {
+        let pn = read_u32(plc, (count + 1) * 4 + i * 4).ok()? & 0x003F_FFFF;
+        let page = word_document.get(pn as usize * FKP_SIZE..(pn as usize + 1) * FKP_SIZE)?;
+        let runs = usize::from(page[FKP_SIZE - 1]);
+        for run in 0..runs {
+            // Each BxPap is a word offset of the run's PapxInFkp and 12 reserved bytes
+            let b_offset = usize::from(*page.get((runs + 1) * 4 + run * 13)?) * 2;
+            if b_offset == 0 {
+                continue;
+            }
+            let (start, len) = match *page.get(b_offset)? {
+                0 => (b_offset + 2, usize::from(*page.get(b_offset + 1)?) * 2),
+                cb => (b_offset + 1, usize::from(cb) * 2 - 1),
+            };
+            // GrpPrlAndIstd: the paragraph style, then its sprms
+            let grpprl = page.get(start + 2..start + len)?;
+            if ends_table_row(grpprl) {
+                let end = read_u32(page, (run + 1) * 4).ok()? as usize;
+                row_end_runs.push(end);
+            }
+        }
+    }
+
+    // The mark is the last character of its paragraph's run
+    let mut marks = HashSet::new();
+    let mut cp = 0;
+    for piece in pieces {
+        let width = if piece.compressed { 1 } else { 2 };
+        let bytes = piece.offset..piece.offset + piece.char_count * width;
+        for &end in &row_end_runs {
+            let mark = end.wrapping_sub(width);
+            if bytes.contains(&mark) {
+                marks.insert(cp + (mark - piece.offset) / width);
+            }
+        }
+        cp += piece.char_count;
+    }
+    Some(marks)
+}
+
+/// Whether a paragraph's sprms set `sprmPFTtp`
+fn ends_table_row(grpprl: &[u8]) -> bool {
+    let mut pos = 0;
+    while let Ok(sprm) = read_u16(grpprl, pos) {
+        pos += 2;
+        // The top three bits give the operand size, variable ones starting with it
+        let size = match sprm >> 13 {
+            0 | 1 => 1,
+            2 | 4 | 5 => 2,
+            3 => 4,
+            7 => 3,
+            _ if sprm == SPRM_T_DEF_TABLE => match read_u16(grpprl, pos) {
+                Ok(cb) => 2 + usize::from(cb).saturating_sub(1),
+                Err(_) => return false,
+            },
+            _ => match grpprl.get(pos) {
+                Some(&cb) => 1 + usize::from(cb),
+                None => return false,
+            },
+        };
+        if sprm == SPRM_P_F_TTP && grpprl.get(pos) == Some(&1) {
+            return true;
+        }
+        pos += size;
+    }
+    false
+}
+
+/// Turn raw Word characters into paragraphs, dropping field codes and control marks.
+///
+/// Table cells end with `\x07` and rows with an extra `\x07`, so each table becomes
+/// a single block with one `a | b | c` line per row. `row_ends` tells the row marks
+/// apart by their position in the story; without it, a `\x07` right after another
+/// one is taken to end the row.
+fn clean_story(story: &str, row_ends: Option<impl Fn(usize) -> bool>) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut paragraph = String::new();
+    let mut cells: Vec<String> = Vec::new();
+    let mut rows: Vec<String> = Vec::new();
+    // For each open field: true while inside its instruction part
+    let mut fields: Vec<bool> = Vec::new();
+
+    let flush_table = |rows: &mut Vec<String>, blocks: &mut Vec<String>| {
+        if !rows.is_empty() {
+            blocks.push(rows.join("\n"));
+            rows.clear();
+        }
+    };
+
+    for (index, ch) in story.chars().enumerate() {
+        match ch {
+            '\u{13}' => fields.push(true),
+            '\u{14}' => {
+                if let Some(in_instruction) = fields.last_mut() {
+                    *in_instruction = false;
+                }
+            }
+            '\u{15}' => {
+                fields.pop();
+            }
+            _ if fields.last() == Some(&true) => {}
+            '\r' | '\u{0C}' => {
+                let text = paragraph.trim().to_string();
+                paragraph.clear();
+                if !text.is_empty() {
+                    flush_table(&mut rows, &mut blocks);
+                    blocks.push(text);
+                }
+            }
+            '\u{07}' => {
+                let text = paragraph.trim().replace('\n', " ");
+                paragraph.clear();
+                let row_end = match &row_ends {
+                    Some(row_end) => row_end(index),
+                    None => text.is_empty() && !cells.is_empty(),
+                };
+                if !row_end {
+                    cells.push(text);
+                } else {
+                    // Rows of empty cells are dropped
+                    if cells.iter().any(|cell| !cell.is_empty()) {
+                        rows.push(cells.join(" | "));
+                    }
+                    cells.clear();
+                }
+            }
+            '\u{0B}' => paragraph.push('\n'),
+            '\u{1E}' => paragraph.push('-'),
+            '\t' => paragraph.push('\t'),
+            c if c < ' ' => {}
+            c => paragraph.push(c),
+        }
+    }
+
+    let text = paragraph.trim().to_string();
+    flush_table(&mut rows, &mut blocks);
+    if !text.is_empty() {
+        blocks.push(text);
+    }
+
+    blocks
+}
@@ -0,0 +1,254 @@
+use super::{read_zip_entry, resolve_xml_ref};
+use crate::ingestion::{ExtractOptions, ExtractedDocument, Extractor, Section};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::fs::File;
+use zip::ZipArchive;
+
+/// DOCX Extractor (Office Open XML word processing packages)
+pub struct DocxExtractor;
+
+#[async_trait]
+impl Extractor for DocxExtractor {
+    async fn extract(
+        &self,
+        file_path: &str,
+        _options: &ExtractOptions,
+    ) -> Result<ExtractedDocument> {
+        let path = file_path.to_string();
+        let text = tokio::task::spawn_blocking(move || extract_docx(&path))
+            .await
+            .context("DOCX extraction task panicked")??;
+        Ok(ExtractedDocument::new(vec![Section::new(text)]))
+    }
+
+    fn supports(&self, file_path: &str) -> bool {
+        file_path.to_lowercase().ends_with(".docx")
+    }
+
+    fn supports_mime_type(&self, mime_type: &str) -> bool {
+        mime_type == "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+    }
+}
+
+/// Extract the text of a DOCX package.
+///
+/// The main document comes first, followed by footnotes and endnotes, then the
+/// distinct paragraphs of headers and footers. Paragraphs, headings and table
+/// rows are separated by blank lines so the chunker keeps their boundaries.
+pub fn extract_docx(file_path: &str) -> Result<String> {
+    let file = File::open(file_path).context("Failed to open DOCX file")?;
+    let mut archive = ZipArchive::new(file).context("Failed to read DOCX package")?;
+
+    let document = read_zip_entry(&mut archive, "word/document.xml")?
+        .context("DOCX package has no word/document.xml")?;
+    let mut blocks = parse_blocks(&document)?;
+
+    for part in ["word/footnotes.xml", "word/endnotes.xml"] {
+        if let Some(xml) = read_zip_entry(&mut archive, part)? {
+            blocks.extend(parse_blocks(&xml)?);
+        }
+    }
+
+    // Headers and footers repeat across sections, so only keep new paragraphs
+    let mut chrome_parts: Vec<String> = archive
+        .file_names()
+        .filter(|name| is_header_or_footer(name))
+        .map(str::to_string)
+        .collect();
+    chrome_parts.sort();
+
+    let mut seen: Vec<String> = Vec::new();
+    for part in chrome_parts {
+        if let Some(xml) = read_zip_entry(&mut archive, &part)? {
+            for block in parse_blocks(&xml)? {
+                if !seen.contains(&block) && !blocks.contains(&block) {
+                    seen.push(block);
+                }
+            }
+        }
+    }
+    blocks.extend(seen);
+
+    Ok(blocks.join("\n\n"))
+}
+
+fn is_header_or_footer(name: &str) -> bool {
+    name.strip_prefix("word/")
+        .filter(|rest| !rest.contains('/'))
+        .is_some_and(|rest| {
+            (rest.starts_with("header") || rest.starts_with("footer")) && rest.ends_with(".xml")
+        })
+}
+
+/// A table being assembled: rows of cells of text
+#[derive(Default)]
+pub(super) struct Table {
+    pub(super) rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub(super) fn render(&self) -> String {
+        self.rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| cell.trim().replace('\n', " "))
+                    .collect::<Vec<_>>()
+                    .join(" | ")
+            })
+            .filter(|line| !line.replace('|', "").trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub(super) fn current_cell(&mut self) -> Option<&mut String> {
+        self.rows.last_mut().and_then(|row| row.last_mut())
+    }
+}
+
+/// Walk a WordprocessingML part and return its paragraphs and tables as text blocks.
+///
+/// Headings are prefixed with Markdown-style `#` markers matching their outline level
+/// and each table becomes a single block with one line per row.
+fn parse_blocks(xml: &str) -> Result<Vec<String>> {
+    let mut reader = Reader::from_str(xml);
+    let mut blocks = Vec::new();
+    // Text boxes nest paragraphs inside paragraphs, so keep a stack
+    let mut paragraphs: Vec<(String, usize)> = Vec::new();
+    let mut tables: Vec<Table> = Vec::new();
+    let mut in_text = false;
+    let mut in_properties = false;
+    // `mc:Fallback` duplicates the content of `mc:Choice` for older readers
+    let mut fallback_depth = 0usize;
+
+    loop {
+        let event = reader.read_event().context("Malformed DOCX XML")?;
+
+        if fallback_depth > 0 {
+            match event {
+                Event::Start(ref e) if e.local_name().as_ref() == b"Fallback" => {
+                    fallback_depth += 1;
+                }
+                Event::End(ref e) if e.local_name().as_ref() == b"Fallback" => {
+                    fallback_depth -= 1;
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+            continue;
+        }
+
+        match event {
+            Event::Start(ref e) => match e.local_name().as_ref() {
+                b"Fallback" => fallback_depth = 1,
+                b"p" => paragraphs.push((String::new(), 0)),
+                b"pPr" => in_properties = true,
+                b"t" => in_text = true,
+                b"tbl" => tables.push(Table::default()),
+                b"tr" => {
+                    if let Some(table) = tables.last_mut() {
+                        table.rows.push(Vec::new());
+                    }
+                }
+                b"tc" => {
+                    if let Some(row) = tables.last_mut().and_then(|t| t.rows.last_mut()) {
+                        row.push(String::new());
+                    }
+                }
+                _ => {}
+            },
+            Event::Empty(ref e) => match e.local_name().as_ref() {
+                b"pStyle" if in_properties => {
+                    if let Some((_, level)) = paragraphs.last_mut() {
+                        *level = heading_level(e);
+                    }
+                }
+                b"tab" if !in_properties => push_text(&mut paragraphs, "\t"),
+                b"br" | b"cr" => push_text(&mut paragraphs, "\n"),
+                _ => {}
+            },
+            Event::Text(ref e) if in_text => {
+                let text = e.xml10_content().context("Invalid text in DOCX")?;
+                push_text(&mut paragraphs, &text);
+            }
+            Event::GeneralRef(ref e) if in_text => {
+                let text = resolve_xml_ref(e)?;
+                push_text(&mut paragraphs, &text);
+            }
+            Event::End(ref e) => match e.local_name().as_ref() {
+                b"pPr" => in_properties = false,
+                b"t" => in_text = false,
+                b"p" => {
+                    if let Some((text, level)) = paragraphs.pop() {
+                        let text = text.trim();
+                        if text.is_empty() {
+                            continue;
+                        }
+                        if let Some(cell) = tables.last_mut().and_then(Table::current_cell) {
+                            if !cell.is_empty() {
+                                cell.push('\n');
+                            }
+                            cell.push_str(text);
+                        } else if level > 0 {
+                            blocks.push(format!("{} {}", "#".repeat(level), text));
+                        } else {
+                            blocks.push(text.to_string());
+                        }
+                    }
+                }
+                b"tbl" => {
+                    if let Some(table) = tables.pop() {
+                        let rendered = table.render();
+                        if rendered.is_empty() {
+                            continue;
+                        }
+                        // Nested tables are flattened into the enclosing cell
+                        if let Some(cell) = tables.last_mut().and_then(Table::current_cell) {
+                            if !cell.is_empty() {
+                                cell.push('\n');
+                            }
+                            cell.push_str(&rendered);
+                        } else {
+                            blocks.push(rendered);
+                        }
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(blocks)
+}
+
+fn push_text(paragraphs: &mut [(String, usize)], text: &str) {
+    if let Some((paragraph, _)) = paragraphs.last_mut() {
+        paragraph.push_str(text);
+    }
+}
+
+/// Map a paragraph style id such as `Heading2` or `Title` to a heading level (0 = body text)
+fn heading_level(style: &BytesStart<'_>) -> usize {
+    let Some(value) = style
+        .try_get_attribute("w:val")
+        .ok()
+        .flatten()
+        .and_then(|attr| attr.unescape_value().ok())
+    else {
+        return 0;
+    };
+
+    if value == "Title" {
+        return 1;
+    }
+    value
+        .strip_prefix("Heading")
+        .or_else(|| value.strip_prefix("heading"))
+        .and_then(|level| level.trim().parse::<usize>().ok())
+        .map_or(0, |level| level.clamp(1, 6))
+}
@@ -0,0 +1,286 @@
+use super::{attachment_path, HtmlDocument};
+use crate::ingestion::{Attachment, ExtractOptions, ExtractedDocument, Extractor, Section};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use mail_parser::mailbox::mbox::MessageIterator;
+use mail_parser::{Address, Message, MessageParser, MimeHeaders};
+use serde_json::{Map, Value};
+use std::io::BufReader;
+use std::path::Path;
+
+/// EML Extractor (RFC 5322 messages)
+pub struct EmlExtractor;
+
+#[async_trait]
+impl Extractor for EmlExtractor {
+    /// The message body as one section, tagged with the message's headers, which are
+    /// also the document's metadata
+    async fn extract(
+        &self,
+        file_path: &str,
+        _options: &ExtractOptions,
+    ) -> Result<ExtractedDocument> {
+        read_eml(file_path, message_document).await
+    }
+
+    async fn extract_attachments(&self, file_path: &str, dir: &Path) -> Result<Vec<Attachment>> {
+        let dir = dir.to_path_buf();
+        read_eml(file_path, move |message| {
+            message_attachments(message, &dir, 0)
+        })
+        .await?
+    }
+
+    async fn extract_with_attachments(
+        &self,
+        file_path: &str,
+        _options: &ExtractOptions,
+        dir: &Path,
+    ) -> Result<(ExtractedDocument, Vec<Attachment>)> {
+        let dir = dir.to_path_buf();
+        read_eml(file_path, move |message| {
+            Ok((
+                message_document(message),
+                message_attachments(message, &dir, 0)?,
+            ))
+        })
+        .await?
+    }
+
+    fn supports(&self, file_path: &str) -> bool {
+        file_path.to_lowercase().ends_with(".eml")
+    }
+
+    fn supports_mime_type(&self, mime_type: &str) -> bool {
+        mime_type == "message/rfc822"
+    }
+}
+
+/// Mbox Extractor (mailbox archives, e.g. mailing list exports)
+pub struct MboxExtractor;
+
+#[async_trait]
+impl Extractor for MboxExtractor {
+    /// One section per message, tagged with that message's headers
+    async fn extract(
+        &self,
+        file_path: &str,
+        _options: &ExtractOptions,
+    ) -> Result<ExtractedDocument> {
+        let path = file_path.to_string();
+        let sections = tokio::task::spawn_blocking(move || {
+            read_mbox(&path, |message, sections: &mut Vec<Section>| {
+                sections.push(message_section(message));
+                Ok(())
+            })
+        })
+        .await
+        .context("Mbox extraction task panicked")??;
+        Ok(ExtractedDocument::new(sections))
+    }
+
+    async fn extract_attachments(&self, file_path: &str, dir: &Path) -> Result<Vec<Attachment>> {
+        let path = file_path.to_string();
+        let dir = dir.to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            read_mbox(&path, |message, attachments: &mut Vec<Attachment>| {
+                let found = message_attachments(message, &dir, attachments.len())?;
+                attachments.extend(found);
+                Ok(())
+            })
+        })
+        .await
+        .context("Mbox extraction task panicked")?
+    }
+
+    /// Sections and attachments of every message from a single read of the archive
+    async fn extract_with_attachments(
+        &self,
+        file_path: &str,
+        _options: &ExtractOptions,
+        dir: &Path,
+    ) -> Result<(ExtractedDocument, Vec<Attachment>)> {
+        let path = file_path.to_string();
+        let dir = dir.to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            let mut attachments = Vec::new();
+            let sections = read_mbox(&path, |message, sections: &mut Vec<Section>| {
+                sections.push(message_section(message));
+                let found = message_attachments(message, &dir, attachments.len())?;
+                attachments.extend(found);
+                Ok(())
+            })?;
+            Ok((ExtractedDocument::new(sections), attachments))
+        })
+        .await
+        .context("Mbox extraction task panicked")?
+    }
+
+    fn supports(&self, file_path: &str) -> bool {
+        let path = file_path.to_lowercase();
+        path.ends_with(".mbox") || path.ends_with(".mbx")
+    }
+
+    fn supports_mime_type(&self, mime_type: &str) -> bool {
+        mime_type == "application/mbox"
+    }
+}
+
+/// Parse an EML file off the async runtime and read what is needed from the message
+async fn read_eml<T: Send + 'static>(
+    file_path: &str,
+    read: impl FnOnce(&Message) -> T + Send + 'static,
+) -> Result<T> {
+    let path = file_path.to_string();
+    tokio::task::spawn_blocking(move || {
+        let raw = std::fs::read(&path).context("Failed to read email file")?;
+        Ok(read(&parse_message(&raw)?))
+    })
+    .await
+    .context("EML extraction task panicked")?
+}
+
+fn parse_message(raw: &[u8]) -> Result<Message<'_>> {
+    MessageParser::default()
+        .parse(raw)
+        .context("Malformed email message")
+}
+
+/// Parse each message of an mbox archive in turn, skipping ones that cannot be parsed
+fn read_mbox<T>(
+    file_path: &str,
+    mut visit: impl FnMut(&Message, &mut Vec<T>) -> Result<()>,
+) -> Result<Vec<T>> {
+    let file = std::fs::File::open(file_path).context("Failed to open mbox file")?;
+    let mut items = Vec::new();
+    for (index, entry) in MessageIterator::new(BufReader::new(file)).enumerate() {
+        let entry = entry.context("Failed to read mbox file")?;
+        match MessageParser::default().parse(entry.contents()) {
+            Some(message) => visit(&message, &mut items)?,
+            None => tracing::warn!("Skipping malformed message {} in {}", index + 1, file_path),
+        }
+    }
+    Ok(items)
+}
+
+/// Render a message as its subject (as a `#` heading) followed by its body.
+///
+/// `text/plain` bodies are preferred; HTML-only messages are converted to text.
+fn message_section(message: &Message) -> Section {
+    let body = message
+        .text_bodies()
+        .filter_map(|part| {
+            let text = part.text_contents()?;
+            if part.is_text_html() {
+                Some(HtmlDocument::parse_content(text).text)
+            } else {
+                Some(text.trim().replace("\r\n", "\n"))
+            }
+        })
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    let text = match message.subject().map(str::trim).filter(|s| !s.is_empty()) {
+        Some(subject) if body.is_empty() => format!("# {}", subject),
+        Some(subject) => format!("# {}\n\n{}", subject, body),
+        None => body,
+    };
+
+    Section {
+        text,
+        metadata: message_headers(message),
+        ..Section::default()
+    }
+}
+
+/// A message as a document of one section, with the message's headers as metadata
+fn message_document(message: &Message) -> ExtractedDocument {
+    let section = message_section(message);
+    ExtractedDocument {
+        metadata: section.metadata.clone(),
+        sections: vec![section],
+    }
+}
+
+/// The `from`, `to`, `date`, `subject` and `message_id` of a message, where present
+fn message_headers(message: &Message) -> Map<String, Value> {
+    let mut headers = Map::new();
+    let mut insert = |key: &str, value: Option<String>| {
+        if let Some(value) = value.filter(|v| !v.is_empty()) {
+            headers.insert(key.to_string(), Value::String(value));
+        }
+    };
+
+    insert("from", message.from().map(format_addresses));
+    insert("to", message.to().map(format_addresses));
+    insert("date", message.date().map(|date| date.to_rfc3339()));
+    insert("subject", message.subject().map(|s| s.trim().to_string()));
+    insert("message_id", message.message_id().map(str::to_string));
+    headers
+}
+
+/// Format addresses as `Name <address>`, comma separated
+fn format_addresses(address: &Address) -> String {
+    address
+        .iter()
+        .filter_map(|addr| match (addr.name(), addr.address()) {
+            (Some(name), Some(address)) => Some(format!("{} <{}>", name, address)),
+            (None, Some(address)) => Some(address.to_string()),
+            (Some(name), None) => Some(name.to_string()),
+            (None, None) => None,
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Write the decoded attachments of a message into `dir`, tagged with the message they
+/// came from. `first_index` numbers the files on disk after those already written.
+///
+/// Unnamed attached messages are named `message-N.eml` so they are read as email
+/// again; other unnamed parts are left for content sniffing to identify.
+fn message_attachments(
+    message: &Message,
+    dir: &Path,
+    first_index: usize,
+) -> Result<Vec<Attachment>> {
+    let mut origin = Map::new();
+    if let Some(id) = message.message_id() {
+        origin.insert(
+            "email_message_id".to_string(),
+            Value::String(id.to_string()),
+        );
+    }
+    if let Some(subject) = message.subject() {
+        origin.insert(
+            "email_subject".to_string(),
+            Value::String(subject.trim().to_string()),
+        );
+    }
+
+    let mut attachments = Vec::new();
+    for (index, part) in message.attachments().enumerate() {
+        if part.is_empty() {
+            continue;
+        }
+        let filename = match part.attachment_name() {
+            Some(name) => name.to_string(),
+            None if part.is_message() => format!("message-{}.eml", index + 1),
+            None => format!("attachment-{}", index + 1),
+        };
+        let content_type = part.content_type().map(|ct| match ct.subtype() {
+            Some(subtype) => format!("{}/{}", ct.ctype(), subtype),
+            None => ct.ctype().to_string(),
+        });
+
+        let path = attachment_path(dir, first_index + attachments.len(), &filename);
+        std::fs::write(&path, part.contents()).context("Failed to write email attachment")?;
+        attachments.push(Attachment {
+            filename,
+            path,
+            content_type,
+            metadata: origin.clone(),
+        });
+    }
+    Ok(attachments)
+}
@@ -0,0 +1,232 @@
+use super::HtmlDocument;
+use crate::ingestion::{ExtractOptions, ExtractedDocument, Extractor, Section};
+use ::epub::doc::{EpubDoc, NavPoint};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use scraper::{Html, Selector};
+use serde_json::{Map, Value};
+use std::io::{Read, Seek};
+use std::path::{Component, Path, PathBuf};
+
+/// EPUB Extractor
+pub struct EpubExtractor;
+
+#[async_trait]
+impl Extractor for EpubExtractor {
+    /// One section per spine document, tagged with `chapter_index` (its position in the
+    /// reading order) and `chapter_title` from the table of contents when it has one.
+    /// The book's `title`, `author`, `language` and `isbn` come from the package document.
+    async fn extract(
+        &self,
+        file_path: &str,
+        _options: &ExtractOptions,
+    ) -> Result<ExtractedDocument> {
+        let path = file_path.to_string();
+        tokio::task::spawn_blocking(move || {
+            let mut doc = open_epub(&path)?;
+            let sections = read_chapters(&mut doc);
+            Ok(ExtractedDocument {
+                metadata: book_metadata(&doc),
+                sections,
+            })
+        })
+        .await
+        .context("EPUB extraction task panicked")?
+    }
+
+    fn supports(&self, file_path: &str) -> bool {
+        file_path.to_lowercase().ends_with(".epub")
+    }
+
+    fn supports_mime_type(&self, mime_type: &str) -> bool {
+        mime_type == "application/epub+zip"
+    }
+}
+
+fn open_epub(file_path: &str) -> Result<EpubDoc<std::io::BufReader<std::fs::File>>> {
+    EpubDoc::new(file_path).context("Failed to open EPUB file")
+}
+
+/// Convert every XHTML document of the spine, in reading order
+fn read_chapters<R: Read + Seek>(doc: &mut EpubDoc<R>) -> Vec<Section> {
+    let toc = toc_entries(doc);
+    let spine: Vec<String> = doc.spine.iter().map(|item| item.idref.clone()).collect();
+
+    let mut sections = Vec::new();
+    // A chapter split over several files is only listed once in the TOC
+    let mut title: Option<String> = None;
+
+    for (index, idref) in spine.iter().enumerate() {
+        let Some(resource) = doc.resources.get(idref) else {
+            continue;
+        };
+        let is_nav = resource
+            .properties
+            .as_deref()
+            .is_some_and(|props| props.split_whitespace().any(|p| p == "nav"));
+        if is_nav || !resource.mime.contains("html") {
+            continue;
+        }
+
+        let path = normalize(&resource.path);
+        if let Some((_, label)) = toc.iter().find(|(target, _)| *target == path) {
+            title = Some(label.clone());
+        }
+
+        let Some((content, _)) = doc.get_resource(idref) else {
+            tracing::warn!("EPUB spine item {} could not be read", idref);
+            continue;
+        };
+        let text = HtmlDocument::parse_content(&String::from_utf8_lossy(&content)).text;
+        if text.is_empty() {
+            continue;
+        }
+
+        let mut section = Section::new(text).with("chapter_index", index);
+        if let Some(title) = &title {
+            section = section.with("chapter_title", title.as_str());
+        }
+        sections.push(section);
+    }
+
+    sections
+}
+
+/// The table of contents as (document path, label) pairs in reading order.
+///
+/// EPUB 2 books carry an NCX file, which the `epub` crate parses; EPUB 3 books may
+/// only have an XHTML navigation document, which is read here.
+fn toc_entries<R: Read + Seek>(doc: &mut EpubDoc<R>) -> Vec<(PathBuf, String)> {
+    let mut entries = Vec::new();
+    flatten_toc(&doc.toc, &mut entries);
+    if !entries.is_empty() {
+        return entries;
+    }
+
+    let Some(nav_id) = doc.get_nav_id() else {
+        return entries;
+    };
+    let Some(nav_path) = doc.resources.get(&nav_id).map(|r| r.path.clone()) else {
+        return entries;
+    };
+    let Some((content, _)) = doc.get_resource(&nav_id) else {
+        return entries;
+    };
+
+    let html = Html::parse_document(&String::from_utf8_lossy(&content));
+    let (Ok(nav_selector), Ok(link_selector)) =
+        (Selector::parse("nav"), Selector::parse("a[href]"))
+    else {
+        return entries;
+    };
+    let navs: Vec<_> = html.select(&nav_selector).collect();
+    let Some(toc_nav) = navs
+        .iter()
+        .find(|nav| nav.attr("epub:type") == Some("toc"))
+        .or_else(|| navs.first())
+    else {
+        return entries;
+    };
+
+    let base = nav_path.parent().unwrap_or(Path::new(""));
+    for link in toc_nav.select(&link_selector) {
+        let label = link.text().collect::<Vec<_>>().join(" ");
+        let label = label.split_whitespace().collect::<Vec<_>>().join(" ");
+        let href = link.attr("href").unwrap_or_default();
+        let target = href.split('#').next().unwrap_or_default();
+        if label.is_empty() || target.is_empty() {
+            continue;
+        }
+        entries.push((normalize(&base.join(target)), label));
+    }
+
+    entries
+}
+
+fn flatten_toc(points: &[NavPoint], entries: &mut Vec<(PathBuf, String)>) {
+    for point in points {
+        let content = point.content.to_string_lossy();
+        let target = content.split('#').next().unwrap_or_default();
+        entries.push((normalize(Path::new(target)), point.label.trim().to_string()));
+        flatten_toc(&point.children, entries);
+    }
+}
+
+/// Resolve `.` and `..` so TOC links and manifest paths compare equal
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::Normal(part) => normalized.push(part),
+            _ => {}
+        }
+    }
+    normalized
+}
+
+fn book_metadata<R: Read + Seek>(doc: &EpubDoc<R>) -> Map<String, Value> {
+    let mut metadata = Map::new();
+    let values = |property: &str| -> Vec<String> {
+        doc.metadata
+            .iter()
+            .filter(|item| item.property == property)
+            .map(|item| item.value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .collect()
+    };
+
+    if let Some(title) = values("title").into_iter().next() {
+        metadata.insert("title".to_string(), Value::String(title));
+    }
+    let authors = values("creator");
+    if !authors.is_empty() {
+        metadata.insert("author".to_string(), Value::String(authors.join(", ")));
+    }
+    if let Some(language) = values("language").into_iter().next() {
+        metadata.insert("language".to_string(), Value::String(language));
+    }
+
+    let isbn = doc
+        .metadata
+        .iter()
+        .filter(|item| item.property == "identifier")
+        .find_map(|item| {
+            let scheme_is_isbn = item
+                .refinement("scheme")
+                .or_else(|| item.refinement("identifier-type"))
+                .is_some_and(|r| r.value.eq_ignore_ascii_case("isbn"));
+            parse_isbn(&item.value, scheme_is_isbn)
+        });
+    if let Some(isbn) = isbn {
+        metadata.insert("isbn".to_string(), Value::String(isbn));
+    }
+
+    metadata
+}
+
+/// Accept `urn:isbn:` identifiers, or bare 10/13 digit identifiers declared as ISBNs
+fn parse_isbn(identifier: &str, declared: bool) -> Option<String> {
+    let identifier = identifier.trim();
+    let value = match identifier.get(..9) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("urn:isbn:") => &identifier[9..],
+        _ if declared => identifier,
+        _ => return None,
+    };
+
+    let isbn: String = value
+        .chars()
+        .filter(|c| !matches!(c, '-' | ' '))
+        .collect::<String>()
+        .to_uppercase();
+    let valid = match isbn.len() {
+        13 => isbn.chars().all(|c| c.is_ascii_digit()),
+        10 => isbn
+            .char_indices()
+            .all(|(i, c)| c.is_ascii_digit() || (i == 9 && c == 'X')),
+        _ => false,
+    };
+    valid.then_some(isbn)
+}
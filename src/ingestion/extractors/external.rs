@@ -0,0 +1,156 @@
+use super::{decode_text, normalize_mime_type};
+use crate::ingestion::{ExtractOptions, ExtractedDocument, Extractor, Section};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Placeholder replaced by the path of the file to extract
+const FILE_PLACEHOLDER: &str = "{file}";
+
+/// A command-line tool that prints the text of a file, as configured in YAML:
+///
+/// ```yaml
+/// settings:
+///   extractors:
+///     external:
+///       - name: pandoc-org
+///         command: "pandoc --to plain {file}"
+///         extensions: [".org"]
+///         mime_types: ["text/org"]
+///         priority: 10
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExternalCommandConfig {
+    /// Name the extractor is registered under
+    pub name: String,
+    /// Program and arguments; `{file}` is replaced by the file's path
+    pub command: CommandTemplate,
+    /// File extensions the command reads, e.g. `.org`
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    /// MIME types the command reads
+    #[serde(default)]
+    pub mime_types: Vec<String>,
+    /// Extractors with a higher priority win; built-ins have priority 0
+    #[serde(default)]
+    pub priority: i32,
+    /// Seconds the command may run before it is killed
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_timeout_secs() -> u64 {
+    120
+}
+
+/// A command line, either as one string split at whitespace or as a list of arguments
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum CommandTemplate {
+    Line(String),
+    Args(Vec<String>),
+}
+
+impl CommandTemplate {
+    fn args(&self) -> Vec<String> {
+        match self {
+            Self::Line(line) => line.split_whitespace().map(str::to_string).collect(),
+            Self::Args(args) => args.clone(),
+        }
+    }
+}
+
+/// Extractor that runs an external command and reads the text it prints to stdout
+pub struct ExternalCommandExtractor {
+    program: String,
+    args: Vec<String>,
+    extensions: Vec<String>,
+    mime_types: Vec<String>,
+    timeout: Duration,
+}
+
+impl ExternalCommandExtractor {
+    pub fn new(config: ExternalCommandConfig) -> Result<Self> {
+        let mut args = config.command.args();
+        if args.is_empty() {
+            anyhow::bail!("External extractor {} has an empty command", config.name);
+        }
+        if !args.iter().any(|arg| arg.contains(FILE_PLACEHOLDER)) {
+            anyhow::bail!(
+                "External extractor {} needs a {} placeholder in its command",
+                config.name,
+                FILE_PLACEHOLDER
+            );
+        }
+        let program = args.remove(0);
+
+        Ok(Self {
+            program,
+            args,
+            extensions: config
+                .extensions
+                .iter()
+                .map(|ext| format!(".{}", ext.trim_start_matches('.').to_lowercase()))
+                .collect(),
+            mime_types: config
+                .mime_types
+                .iter()
+                .filter_map(|mime_type| normalize_mime_type(mime_type))
+                .collect(),
+            timeout: Duration::from_secs(config.timeout_secs),
+        })
+    }
+}
+
+#[async_trait]
+impl Extractor for ExternalCommandExtractor {
+    async fn extract(
+        &self,
+        file_path: &str,
+        _options: &ExtractOptions,
+    ) -> Result<ExtractedDocument> {
+        let mut command = tokio::process::Command::new(&self.program);
+        command
+            .args(
+                self.args
+                    .iter()
+                    .map(|arg| arg.replace(FILE_PLACEHOLDER, file_path)),
+            )
+            .stdin(std::process::Stdio::null())
+            .kill_on_drop(true);
+
+        let output = tokio::time::timeout(self.timeout, command.output())
+            .await
+            .with_context(|| {
+                format!(
+                    "{} timed out after {} seconds",
+                    self.program,
+                    self.timeout.as_secs()
+                )
+            })?
+            .with_context(|| {
+                format!("Failed to run {}. Make sure it's installed.", self.program)
+            })?;
+
+        if !output.status.success() {
+            anyhow::bail!(
+                "{} failed: {}",
+                self.program,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        let (text, _) = decode_text(&output.stdout, None)?;
+        Ok(ExtractedDocument::new(vec![Section::new(text.trim())]))
+    }
+
+    fn supports(&self, file_path: &str) -> bool {
+        let path = file_path.to_lowercase();
+        self.extensions.iter().any(|ext| path.ends_with(ext))
+    }
+
+    fn supports_mime_type(&self, mime_type: &str) -> bool {
+        self.mime_types.iter().any(|m| m == mime_type)
+    }
+}
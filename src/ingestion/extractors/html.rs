@@ -0,0 +1,368 @@
+use super::decode_text;
+use crate::ingestion::{ExtractOptions, ExtractedDocument, Extractor, Section};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use encoding_rs::Encoding;
+use regex::bytes::Regex;
+use scraper::{ElementRef, Html, Node, Selector};
+use serde_json::{Map, Value};
+use std::sync::OnceLock;
+
+/// Elements that never carry readable content
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "script", "style", "noscript", "template", "iframe", "svg", "canvas", "object", "button",
+    "select", "input", "textarea", "head",
+];
+
+/// Page chrome that surrounds the actual content
+const CHROME_ELEMENTS: &[&str] = &["nav", "footer", "aside", "form", "dialog"];
+
+/// ARIA landmark roles used for navigation and page chrome
+const CHROME_ROLES: &[&str] = &[
+    "navigation",
+    "banner",
+    "contentinfo",
+    "complementary",
+    "search",
+];
+
+/// Elements that start and end a paragraph-like block
+const BLOCK_ELEMENTS: &[&str] = &[
+    "p",
+    "div",
+    "section",
+    "article",
+    "main",
+    "blockquote",
+    "figure",
+    "figcaption",
+    "dl",
+    "dt",
+    "dd",
+    "address",
+    "details",
+    "summary",
+    "caption",
+    "center",
+];
+
+/// `<meta>` names that describe the browser rather than the document
+const IGNORED_META: &[&str] = &["viewport", "theme-color", "format-detection", "referrer"];
+
+/// How far into the file browsers look for a `<meta charset>` declaration
+const CHARSET_PRESCAN_LEN: usize = 1024;
+
+/// HTML Extractor (saved web pages)
+pub struct HtmlExtractor;
+
+#[async_trait]
+impl Extractor for HtmlExtractor {
+    /// The readable text as one section, with the page's title and `<meta>` tags as
+    /// metadata
+    async fn extract(
+        &self,
+        file_path: &str,
+        options: &ExtractOptions,
+    ) -> Result<ExtractedDocument> {
+        let html = read_html(file_path, options.encoding.as_deref()).await?;
+        let document = HtmlDocument::parse(&html);
+        Ok(ExtractedDocument {
+            metadata: document.metadata,
+            sections: vec![Section::new(document.text)],
+        })
+    }
+
+    fn supports(&self, file_path: &str) -> bool {
+        let lower = file_path.to_lowercase();
+        lower.ends_with(".html") || lower.ends_with(".htm") || lower.ends_with(".xhtml")
+    }
+
+    fn supports_mime_type(&self, mime_type: &str) -> bool {
+        matches!(mime_type, "text/html" | "application/xhtml+xml")
+    }
+}
+
+/// Decode a page with the requested encoding, else the one its `<meta charset>`
+/// declares, else the one [`decode_text`] detects
+async fn read_html(file_path: &str, encoding: Option<&str>) -> Result<String> {
+    let bytes = tokio::fs::read(file_path)
+        .await
+        .context("Failed to read HTML file")?;
+    let declared = encoding
+        .map(str::to_string)
+        .or_else(|| declared_charset(&bytes));
+    Ok(decode_text(&bytes, declared.as_deref())?.0)
+}
+
+/// The charset named by a `<meta charset>` or `<meta http-equiv="Content-Type">` tag
+/// near the start of the page, if it is one we know. A byte order mark overrides it.
+fn declared_charset(bytes: &[u8]) -> Option<String> {
+    static META_CHARSET: OnceLock<Regex> = OnceLock::new();
+    if Encoding::for_bom(bytes).is_some() {
+        return None;
+    }
+    let pattern = META_CHARSET.get_or_init(|| {
+        Regex::new(r#"(?i)<meta\s[^>]*charset\s*=\s*["']?\s*([a-z0-9_:.-]+)"#)
+            .expect("Failed to compile regex")
+    });
+    let head = &bytes[..bytes.len().min(CHARSET_PRESCAN_LEN)];
+    let label = std::str::from_utf8(pattern.captures(head)?.get(1)?.as_bytes()).ok()?;
+    Encoding::for_label(label.as_bytes())?;
+    Some(label.to_lowercase())
+}
+
+/// Readable text and metadata of an HTML page
+pub struct HtmlDocument {
+    pub text: String,
+    /// `title`, `language` and the `<meta name|property>` tags of the page
+    pub metadata: Map<String, Value>,
+}
+
+impl HtmlDocument {
+    /// Parse a page, keeping the main content and dropping scripts, styles and chrome.
+    ///
+    /// Headings become `#`-prefixed blocks, list items `- ` lines and table rows
+    /// `a | b` lines; blocks are separated by blank lines.
+    pub fn parse(html: &str) -> Self {
+        Self::parse_with(html, true)
+    }
+
+    /// Parse a document that has no site chrome, such as an e-book chapter.
+    ///
+    /// Headers, asides (often footnotes) and navigation are kept as content.
+    pub fn parse_content(html: &str) -> Self {
+        Self::parse_with(html, false)
+    }
+
+    fn parse_with(html: &str, strip_chrome: bool) -> Self {
+        let document = Html::parse_document(html);
+        let root = document.root_element();
+
+        let mut metadata = Map::new();
+        if let Some(title) = select_first(&document, "title") {
+            let title = collapse_whitespace(&title.text().collect::<String>());
+            if !title.is_empty() {
+                metadata.insert("title".to_string(), Value::String(title));
+            }
+        }
+        if let Some(lang) = root.attr("lang").filter(|lang| !lang.is_empty()) {
+            metadata.insert("language".to_string(), Value::String(lang.to_string()));
+        }
+        if let Ok(selector) = Selector::parse("meta[content]") {
+            for meta in document.select(&selector) {
+                let Some(name) = meta.attr("name").or_else(|| meta.attr("property")) else {
+                    continue;
+                };
+                let name = name.trim().to_lowercase();
+                let content = meta.attr("content").unwrap_or_default().trim();
+                if name.is_empty() || content.is_empty() || IGNORED_META.contains(&name.as_str()) {
+                    continue;
+                }
+                metadata
+                    .entry(name)
+                    .or_insert_with(|| Value::String(content.to_string()));
+            }
+        }
+
+        // Prefer the page's main content when it marks some: every outermost `main`
+        // and `article` outside of page chrome, in document order
+        let mut content: Vec<ElementRef> = Selector::parse("main, article")
+            .map(|selector| {
+                document
+                    .select(&selector)
+                    .filter(|element| {
+                        !element
+                            .ancestors()
+                            .filter_map(ElementRef::wrap)
+                            .any(|ancestor| {
+                                let name = ancestor.value().name();
+                                matches!(name, "main" | "article")
+                                    || is_hidden(ancestor, name, strip_chrome)
+                            })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        if content.is_empty() {
+            content.push(select_first(&document, "body").unwrap_or(root));
+        }
+
+        let mut builder = TextBuilder {
+            strip_chrome,
+            ..TextBuilder::default()
+        };
+        for element in content {
+            builder.walk(element, false);
+        }
+
+        Self {
+            text: builder.finish(),
+            metadata,
+        }
+    }
+}
+
+fn select_first<'a>(document: &'a Html, selector: &str) -> Option<ElementRef<'a>> {
+    let selector = Selector::parse(selector).ok()?;
+    document.select(&selector).next()
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Accumulates text blocks while walking the DOM
+#[derive(Default)]
+struct TextBuilder {
+    blocks: Vec<String>,
+    current: String,
+    pending_space: bool,
+    strip_chrome: bool,
+}
+
+impl TextBuilder {
+    fn walk(&mut self, element: ElementRef<'_>, in_table_row: bool) {
+        let name = element.value().name();
+        if is_hidden(element, name, self.strip_chrome) {
+            return;
+        }
+
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = usize::from(name.as_bytes()[1] - b'0');
+                self.block();
+                self.current.push_str(&"#".repeat(level));
+                self.current.push(' ');
+                self.children(element, false);
+                self.block();
+            }
+            "ul" | "ol" | "table" => {
+                self.block();
+                self.children(element, false);
+                self.block();
+            }
+            "li" => {
+                self.line();
+                self.current.push_str("- ");
+                self.children(element, false);
+            }
+            "tr" => {
+                self.line();
+                self.children(element, true);
+            }
+            "td" | "th" if in_table_row => {
+                if !self.current.is_empty() && !self.current.ends_with('\n') {
+                    self.current.push_str(" | ");
+                }
+                self.pending_space = false;
+                self.children(element, false);
+            }
+            "br" => self.line(),
+            "hr" => self.block(),
+            "pre" => {
+                self.block();
+                let text: String = element.text().collect();
+                self.current.push_str(text.trim_matches('\n'));
+                self.block();
+            }
+            _ if BLOCK_ELEMENTS.contains(&name) => {
+                self.block();
+                self.children(element, false);
+                self.block();
+            }
+            _ => self.children(element, in_table_row),
+        }
+    }
+
+    fn children(&mut self, element: ElementRef<'_>, in_table_row: bool) {
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => self.text(text),
+                Node::Element(_) => {
+                    if let Some(child) = ElementRef::wrap(child) {
+                        self.walk(child, in_table_row);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Append inline text, collapsing runs of whitespace into single spaces
+    fn text(&mut self, text: &str) {
+        if text.starts_with(char::is_whitespace) {
+            self.pending_space = true;
+        }
+        for (idx, word) in text.split_whitespace().enumerate() {
+            if (idx > 0 || self.pending_space)
+                && !self.current.is_empty()
+                && !self.current.ends_with([' ', '\n'])
+            {
+                self.current.push(' ');
+            }
+            self.current.push_str(word);
+            self.pending_space = false;
+        }
+        if text.ends_with(char::is_whitespace) {
+            self.pending_space = true;
+        }
+    }
+
+    fn line(&mut self) {
+        let trimmed = self.current.trim_end_matches(' ').len();
+        self.current.truncate(trimmed);
+        if !self.current.is_empty() && !self.current.ends_with('\n') {
+            self.current.push('\n');
+        }
+        self.pending_space = false;
+    }
+
+    fn block(&mut self) {
+        let block = self
+            .current
+            .lines()
+            .map(str::trim_end)
+            .filter(|line| {
+                !line.trim().is_empty()
+                    && line.trim() != "-"
+                    && !line.trim().chars().all(|c| c == '#')
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        if !block.trim().is_empty() {
+            self.blocks.push(block.trim().to_string());
+        }
+        self.current.clear();
+        self.pending_space = false;
+    }
+
+    fn finish(mut self) -> String {
+        self.block();
+        self.blocks.join("\n\n")
+    }
+}
+
+fn is_hidden(element: ElementRef<'_>, name: &str, strip_chrome: bool) -> bool {
+    if SKIPPED_ELEMENTS.contains(&name)
+        || element.attr("hidden").is_some()
+        || element.attr("aria-hidden") == Some("true")
+    {
+        return true;
+    }
+    if !strip_chrome {
+        return false;
+    }
+
+    // A page-level header is site chrome, but an article's header holds its title
+    let page_header = name == "header"
+        && element
+            .parent()
+            .and_then(ElementRef::wrap)
+            .is_some_and(|parent| parent.value().name() == "body");
+
+    page_header
+        || CHROME_ELEMENTS.contains(&name)
+        || element
+            .attr("role")
+            .is_some_and(|role| CHROME_ROLES.contains(&role))
+}
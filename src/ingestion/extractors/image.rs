@@ -0,0 +1,54 @@
+use super::ocr_fallback;
+use crate::ingestion::{ExtractOptions, ExtractedDocument, Extractor, Section};
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// Raster formats Tesseract (via Leptonica) can read directly
+const IMAGE_EXTENSIONS: &[&str] = &[
+    ".png", ".jpg", ".jpeg", ".tif", ".tiff", ".bmp", ".gif", ".webp", ".pnm",
+];
+
+/// Image Extractor (scans and photos, OCRed with Tesseract)
+pub struct ImageExtractor;
+
+#[async_trait]
+impl Extractor for ImageExtractor {
+    /// One section per image page; multi-page TIFFs are tagged with their `page` number
+    async fn extract(
+        &self,
+        file_path: &str,
+        options: &ExtractOptions,
+    ) -> Result<ExtractedDocument> {
+        let text = ocr_fallback(file_path, &options.ocr_languages).await?;
+        Ok(ExtractedDocument::new(ocr_sections(&text)))
+    }
+
+    fn supports(&self, file_path: &str) -> bool {
+        let lower = file_path.to_lowercase();
+        IMAGE_EXTENSIONS.iter().any(|ext| lower.ends_with(ext))
+    }
+
+    fn supports_mime_type(&self, mime_type: &str) -> bool {
+        mime_type.starts_with("image/")
+    }
+}
+
+/// Sections of Tesseract's output: one per page, tagged with its `page` number, when
+/// the image has several pages, which Tesseract separates with a form feed
+pub fn ocr_sections(text: &str) -> Vec<Section> {
+    let mut pages: Vec<&str> = text.split('\u{0C}').map(str::trim).collect();
+    while pages.last().is_some_and(|page| page.is_empty()) {
+        pages.pop();
+    }
+
+    if pages.len() <= 1 {
+        let text = pages.first().copied().unwrap_or_default();
+        return vec![Section::new(text).with("ocr", true)];
+    }
+
+    pages
+        .into_iter()
+        .enumerate()
+        .map(|(idx, page)| Section::new(page).with("page", idx + 1).with("ocr", true))
+        .collect()
+}
@@ -0,0 +1,190 @@
+use super::decode_text;
+use crate::ingestion::{ExtractOptions, ExtractedDocument, Extractor, Section};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// JSON Extractor (JSON arrays or objects, and JSON Lines)
+pub struct JsonExtractor;
+
+#[async_trait]
+impl Extractor for JsonExtractor {
+    /// One section per record, mapped with the request's `text_field`, `metadata_fields`,
+    /// `id_field` and `id_namespace`
+    async fn extract(
+        &self,
+        file_path: &str,
+        options: &ExtractOptions,
+    ) -> Result<ExtractedDocument> {
+        let bytes = tokio::fs::read(file_path)
+            .await
+            .context("Failed to read JSON file")?;
+        let options = options.clone();
+        // Ids are only unique within their source: by default, this file's `id_field`
+        let id_namespace = options.id_namespace.clone().or_else(|| {
+            let file_name = std::path::Path::new(file_path)
+                .file_name()?
+                .to_string_lossy();
+            Some(format!("{}:{}", file_name, options.id_field.as_deref()?))
+        });
+        tokio::task::spawn_blocking(move || {
+            let (json, _) = decode_text(&bytes, options.encoding.as_deref())?;
+            let mapping = FieldMapping {
+                text_field: options.text_field.as_deref(),
+                metadata_fields: &options.metadata_fields,
+                id_field: options.id_field.as_deref(),
+                id_namespace: id_namespace.as_deref(),
+            };
+            Ok(ExtractedDocument::new(record_sections(
+                &parse_records(&json)?,
+                &mapping,
+            )))
+        })
+        .await
+        .context("JSON extraction task panicked")?
+    }
+
+    fn supports(&self, file_path: &str) -> bool {
+        let path = file_path.to_lowercase();
+        [".json", ".jsonl", ".ndjson"]
+            .iter()
+            .any(|ext| path.ends_with(ext))
+    }
+
+    fn supports_mime_type(&self, mime_type: &str) -> bool {
+        matches!(
+            mime_type,
+            "application/json"
+                | "application/jsonl"
+                | "application/x-jsonlines"
+                | "application/x-ndjson"
+                | "application/ndjson"
+        )
+    }
+}
+
+/// Which fields of a record hold its text, its metadata and its stable id.
+///
+/// Fields are named by their path, with nested fields separated by dots
+/// (e.g. `author.name`).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FieldMapping<'a> {
+    /// Field holding the text; without one every field is rendered as `key: value`
+    pub text_field: Option<&'a str>,
+    /// Fields copied into the section metadata under their path
+    pub metadata_fields: &'a [String],
+    /// Field stored as `external_id`
+    pub id_field: Option<&'a str>,
+    /// Source the ids are unique within, stored as `id_namespace` next to them
+    pub id_namespace: Option<&'a str>,
+}
+
+/// Parse a JSON document or JSON Lines.
+///
+/// A JSON array yields its elements and any other JSON value yields itself. Anything
+/// that is not a single JSON value is read as one value per line, skipping blank lines
+/// and lines that are not valid JSON.
+pub fn parse_records(json: &str) -> Result<Vec<Value>> {
+    match serde_json::from_str::<Value>(json) {
+        Ok(Value::Array(records)) => return Ok(records),
+        Ok(record) => return Ok(vec![record]),
+        Err(e) if json.lines().filter(|l| !l.trim().is_empty()).count() <= 1 => {
+            return Err(e).context("Malformed JSON");
+        }
+        Err(_) => {}
+    }
+
+    let mut records = Vec::new();
+    for (index, line) in json.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match serde_json::from_str(line) {
+            Ok(record) => records.push(record),
+            Err(e) => tracing::warn!("Skipping malformed JSON on line {}: {}", index + 1, e),
+        }
+    }
+    if records.is_empty() {
+        anyhow::bail!("No JSON records found");
+    }
+    Ok(records)
+}
+
+/// Turn each record into a section of its own, tagged with `record_index`, its
+/// `external_id` and `id_namespace` and its metadata fields, and carrying the record
+/// for graph stores. Records without text are skipped.
+pub fn record_sections(records: &[Value], mapping: &FieldMapping) -> Vec<Section> {
+    let mut sections = Vec::new();
+
+    for (index, record) in records.iter().enumerate() {
+        let text = match (mapping.text_field, record) {
+            (Some(field), _) => field_value(record, field).map(value_text),
+            (None, Value::Object(fields)) => Some(
+                fields
+                    .iter()
+                    .filter(|(_, value)| !value.is_null())
+                    .map(|(key, value)| format!("{}: {}", key, value_text(value)))
+                    .collect::<Vec<_>>()
+                    .join(" | "),
+            ),
+            (None, value) => Some(value_text(value)),
+        };
+        let Some(text) = text.map(|t| t.trim().to_string()).filter(|t| !t.is_empty()) else {
+            tracing::debug!("Skipping JSON record {} without text", index);
+            continue;
+        };
+
+        let mut section = Section::new(text).with("record_index", index);
+        for field in mapping.metadata_fields {
+            if let Some(value) = field_value(record, field).filter(|v| !v.is_null()) {
+                section.metadata.insert(field.clone(), value.clone());
+            }
+        }
+        if let Some(id) = mapping
+            .id_field
+            .and_then(|field| field_value(record, field))
+            .map(value_text)
+            .filter(|id| !id.is_empty())
+        {
+            section
+                .metadata
+                .insert("external_id".to_string(), Value::String(id));
+            if let Some(namespace) = mapping.id_namespace {
+                section.metadata.insert(
+                    "id_namespace".to_string(),
+                    Value::String(namespace.to_string()),
+                );
+            }
+        }
+        if let Value::Object(fields) = record {
+            section.records = vec![fields.clone()];
+        }
+        sections.push(section);
+    }
+
+    sections
+}
+
+/// The value at a dotted path such as `author.name`; array elements are addressed by
+/// their index (`tags.0`)
+fn field_value<'a>(record: &'a Value, path: &str) -> Option<&'a Value> {
+    // A key that itself contains dots wins over the nested lookup
+    if let Some(value) = record.get(path) {
+        return Some(value);
+    }
+    path.split('.').try_fold(record, |value, key| match value {
+        Value::Object(fields) => fields.get(key),
+        Value::Array(items) => items.get(key.parse::<usize>().ok()?),
+        _ => None,
+    })
+}
+
+/// Strings as they are, other values as JSON
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
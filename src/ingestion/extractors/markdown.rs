@@ -0,0 +1,275 @@
+use super::{decode_text, HtmlDocument};
+use crate::ingestion::{ExtractOptions, ExtractedDocument, Extractor, Section};
+use ::markdown::mdast::{List, Node};
+use ::markdown::{Constructs, ParseOptions};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde_json::{Map, Value};
+
+/// Markdown Extractor
+pub struct MarkdownExtractor;
+
+#[async_trait]
+impl Extractor for MarkdownExtractor {
+    /// One section per heading, tagged with its `heading_path` (e.g. `Setup > Install`),
+    /// with the top-level keys of the YAML (`---`) or TOML (`+++`) front matter as metadata
+    async fn extract(
+        &self,
+        file_path: &str,
+        options: &ExtractOptions,
+    ) -> Result<ExtractedDocument> {
+        let markdown = read_markdown(file_path, options.encoding.as_deref()).await?;
+        let document = MarkdownDocument::parse(&markdown, !options.drop_code_blocks)?;
+        Ok(ExtractedDocument {
+            metadata: document.metadata,
+            sections: document.sections,
+        })
+    }
+
+    fn supports(&self, file_path: &str) -> bool {
+        let lower = file_path.to_lowercase();
+        lower.ends_with(".md") || lower.ends_with(".markdown")
+    }
+
+    fn supports_mime_type(&self, mime_type: &str) -> bool {
+        matches!(mime_type, "text/markdown" | "text/x-markdown")
+    }
+}
+
+async fn read_markdown(file_path: &str, encoding: Option<&str>) -> Result<String> {
+    let bytes = tokio::fs::read(file_path)
+        .await
+        .context("Failed to read markdown file")?;
+    let (markdown, _) = decode_text(&bytes, encoding)?;
+    Ok(markdown)
+}
+
+/// Readable text of a Markdown document, split at its headings
+pub struct MarkdownDocument {
+    pub sections: Vec<Section>,
+    /// Front matter of the document
+    pub metadata: Map<String, Value>,
+}
+
+impl MarkdownDocument {
+    /// Parse a document into plain-text sections.
+    ///
+    /// Markup is dropped: links keep their text, images their alt text, list items
+    /// become `- ` lines and table rows `a | b` lines. Code blocks are kept verbatim
+    /// without their fences unless `keep_code` is false.
+    pub fn parse(markdown: &str, keep_code: bool) -> Result<Self> {
+        let options = ParseOptions {
+            constructs: Constructs {
+                frontmatter: true,
+                ..Constructs::gfm()
+            },
+            ..ParseOptions::gfm()
+        };
+        let tree = ::markdown::to_mdast(markdown, &options)
+            .map_err(|e| anyhow::anyhow!("Failed to parse markdown: {}", e))?;
+
+        let mut builder = SectionBuilder {
+            keep_code,
+            ..SectionBuilder::default()
+        };
+        builder.block(&tree);
+        builder.flush();
+
+        Ok(Self {
+            sections: builder.sections,
+            metadata: builder.metadata,
+        })
+    }
+}
+
+#[derive(Default)]
+struct SectionBuilder {
+    sections: Vec<Section>,
+    metadata: Map<String, Value>,
+    blocks: Vec<String>,
+    /// Depth and title of the headings enclosing the current block
+    headings: Vec<(u8, String)>,
+    keep_code: bool,
+}
+
+impl SectionBuilder {
+    fn block(&mut self, node: &Node) {
+        match node {
+            Node::Root(root) => root.children.iter().for_each(|child| self.block(child)),
+            Node::Blockquote(quote) => quote.children.iter().for_each(|child| self.block(child)),
+            Node::FootnoteDefinition(note) => {
+                note.children.iter().for_each(|child| self.block(child))
+            }
+            Node::Yaml(yaml) => self.metadata.extend(yaml_front_matter(&yaml.value)),
+            Node::Toml(toml) => self.metadata.extend(toml_front_matter(&toml.value)),
+            Node::Heading(heading) => {
+                self.flush();
+                let title = inline_text(&heading.children);
+                self.headings.retain(|(depth, _)| *depth < heading.depth);
+                if !title.is_empty() {
+                    self.blocks
+                        .push(format!("{} {}", "#".repeat(heading.depth.into()), title));
+                    self.headings.push((heading.depth, title));
+                }
+            }
+            Node::List(list) => {
+                let mut lines = Vec::new();
+                self.list_lines(list, 0, &mut lines);
+                self.push(lines.join("\n"));
+            }
+            Node::Table(table) => {
+                let rows: Vec<String> = table
+                    .children
+                    .iter()
+                    .filter_map(Node::children)
+                    .map(|cells| {
+                        cells
+                            .iter()
+                            .map(|cell| inline_text(cell.children().map_or(&[][..], Vec::as_slice)))
+                            .collect::<Vec<_>>()
+                            .join(" | ")
+                    })
+                    .collect();
+                self.push(rows.join("\n"));
+            }
+            other => {
+                if let Some(text) = self.block_text(other) {
+                    self.push(text);
+                }
+            }
+        }
+    }
+
+    /// Text of a leaf block, or `None` for blocks that carry no readable content
+    fn block_text(&self, node: &Node) -> Option<String> {
+        match node {
+            Node::Paragraph(paragraph) => Some(inline_text(&paragraph.children)),
+            Node::Code(code) if self.keep_code => Some(code.value.trim_end().to_string()),
+            Node::Math(math) => Some(math.value.trim().to_string()),
+            Node::Html(html) => Some(HtmlDocument::parse_content(&html.value).text),
+            _ => None,
+        }
+    }
+
+    fn list_lines(&self, list: &List, depth: usize, lines: &mut Vec<String>) {
+        let indent = "  ".repeat(depth);
+        for (idx, item) in list.children.iter().enumerate() {
+            let Node::ListItem(item) = item else {
+                continue;
+            };
+            let mut marker = if list.ordered {
+                format!("{}.", list.start.unwrap_or(1) as usize + idx)
+            } else {
+                "-".to_string()
+            };
+            if let Some(checked) = item.checked {
+                marker.push_str(if checked { " [x]" } else { " [ ]" });
+            }
+
+            let mut first = true;
+            for child in &item.children {
+                if let Node::List(nested) = child {
+                    self.list_lines(nested, depth + 1, lines);
+                    continue;
+                }
+                let Some(text) = self.block_text(child).filter(|t| !t.is_empty()) else {
+                    continue;
+                };
+                for line in text.lines() {
+                    if first {
+                        lines.push(format!("{indent}{marker} {line}"));
+                        first = false;
+                    } else {
+                        lines.push(format!("{indent}  {line}"));
+                    }
+                }
+            }
+        }
+    }
+
+    fn push(&mut self, text: String) {
+        if !text.trim().is_empty() {
+            self.blocks.push(text);
+        }
+    }
+
+    /// Close the current section
+    fn flush(&mut self) {
+        if self.blocks.is_empty() {
+            return;
+        }
+
+        let mut section = Section::new(self.blocks.join("\n\n"));
+        if !self.headings.is_empty() {
+            let path: Vec<&str> = self.headings.iter().map(|(_, t)| t.as_str()).collect();
+            section = section.with("heading_path", path.join(" > "));
+        }
+        self.sections.push(section);
+        self.blocks.clear();
+    }
+}
+
+/// Text of phrasing content with whitespace collapsed
+fn inline_text(nodes: &[Node]) -> String {
+    fn collect(nodes: &[Node], out: &mut String) {
+        for node in nodes {
+            match node {
+                Node::Text(text) => out.push_str(&text.value),
+                Node::InlineCode(code) => out.push_str(&code.value),
+                Node::InlineMath(math) => out.push_str(&math.value),
+                Node::Image(image) => out.push_str(&image.alt),
+                Node::ImageReference(image) => out.push_str(&image.alt),
+                Node::Break(_) => out.push(' '),
+                // Inline tags such as `<br>` or `<kbd>` are markup, not content
+                Node::Html(_) | Node::FootnoteReference(_) => {}
+                other => collect(other.children().map_or(&[][..], Vec::as_slice), out),
+            }
+        }
+    }
+
+    let mut out = String::new();
+    collect(nodes, &mut out);
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn yaml_front_matter(source: &str) -> Map<String, Value> {
+    match serde_yaml::from_str::<Value>(source) {
+        Ok(Value::Object(map)) => map,
+        Ok(_) => Map::new(),
+        Err(e) => {
+            tracing::warn!("Ignoring invalid YAML front matter: {}", e);
+            Map::new()
+        }
+    }
+}
+
+fn toml_front_matter(source: &str) -> Map<String, Value> {
+    match source.parse::<toml::Table>() {
+        Ok(table) => table
+            .into_iter()
+            .map(|(key, value)| (key, toml_to_json(value)))
+            .collect(),
+        Err(e) => {
+            tracing::warn!("Ignoring invalid TOML front matter: {}", e);
+            Map::new()
+        }
+    }
+}
+
+/// Convert a TOML value, rendering dates as their RFC 3339 strings
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::from(i),
+        toml::Value::Float(f) => Value::from(f),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(dt) => Value::String(dt.to_string()),
+        toml::Value::Array(items) => items.into_iter().map(toml_to_json).collect(),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, toml_to_json(value)))
+                .collect(),
+        ),
+    }
+}
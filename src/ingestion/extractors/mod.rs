@@ -0,0 +1,207 @@
+mod archive;
+mod code;
+mod csv;
+mod doc;
+mod docx;
+mod email;
+mod epub;
+mod external;
+mod html;
+mod image;
+mod json;
+mod markdown;
+mod notebook;
+mod odp;
+mod ods;
+mod odt;
+mod pdf;
+mod pptx;
+mod presentation;
+mod registry;
+mod rtf;
+mod sniff;
+mod spreadsheet;
+mod text;
+mod xlsx;
+
+pub use archive::{
+    unpack_archive, ArchiveExtractor, MAX_ARCHIVE_BYTES, MAX_ARCHIVE_ENTRIES, MAX_ENTRY_BYTES,
+};
+pub use code::{split_code, CodeExtractor, CodeLanguage};
+pub use csv::CsvExtractor;
+pub use doc::DocExtractor;
+pub use docx::DocxExtractor;
+pub use email::{EmlExtractor, MboxExtractor};
+pub use epub::EpubExtractor;
+pub use external::{CommandTemplate, ExternalCommandConfig, ExternalCommandExtractor};
+pub use html::{HtmlDocument, HtmlExtractor};
+pub use image::{ocr_sections, ImageExtractor};
+pub use json::{parse_records, record_sections, FieldMapping, JsonExtractor};
+pub use markdown::{MarkdownDocument, MarkdownExtractor};
+pub use notebook::{notebook_sections, NotebookExtractor};
+pub use odp::OdpExtractor;
+pub use ods::OdsExtractor;
+pub use odt::OdtExtractor;
+pub use pdf::PdfExtractor;
+pub use pptx::PptxExtractor;
+pub use registry::{ExtractorRegistry, ExtractorSettings, BUILTIN_PRIORITY};
+pub use rtf::{RtfDocument, RtfExtractor};
+pub use sniff::{normalize_mime_type, sniff_mime_type, TEXT_MIME};
+pub use text::{decode_text, TextExtractor};
+pub use xlsx::XlsxExtractor;
+
+use anyhow::{Context, Result};
+use quick_xml::events::{BytesRef, BytesStart, Event};
+use std::io::{Read, Seek};
+use std::path::{Path, PathBuf};
+
+/// OCR an image file using Tesseract, optionally restricted to the given languages
+async fn ocr_fallback(file_path: &str, languages: &[String]) -> Result<String> {
+    // Use tesseract CLI
+    let mut command = tokio::process::Command::new("tesseract");
+    command.arg(file_path).arg("stdout");
+    if !languages.is_empty() {
+        command.arg("-l").arg(languages.join("+"));
+    }
+
+    let output = command
+        .output()
+        .await
+        .context("Failed to run tesseract. Make sure it's installed.")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Tesseract failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    String::from_utf8(output.stdout).context("Invalid UTF-8 from tesseract")
+}
+
+/// Path in `dir` for the `index`th file unpacked from a container, named after `filename`.
+///
+/// Only the last component of `filename` is kept, so names taken from archives or email
+/// headers cannot point outside `dir`, and the extension still selects an extractor.
+fn attachment_path(dir: &Path, index: usize, filename: &str) -> PathBuf {
+    let name = filename
+        .rsplit(['/', '\\'])
+        .next()
+        .map(str::trim)
+        .filter(|name| !name.is_empty() && *name != "." && *name != "..")
+        .unwrap_or("attachment");
+    dir.join(format!("{}-{}", index + 1, name))
+}
+
+/// Read a zip entry as UTF-8 text, returning `None` when the entry does not exist
+fn read_zip_entry<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    name: &str,
+) -> Result<Option<String>> {
+    let mut entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("Failed to open {} in archive", name)),
+    };
+
+    let mut content = String::new();
+    entry
+        .read_to_string(&mut content)
+        .with_context(|| format!("Failed to read {} from archive", name))?;
+    Ok(Some(content))
+}
+
+/// Resolve a character or predefined entity reference reported by quick-xml
+fn resolve_xml_ref(reference: &BytesRef<'_>) -> Result<String> {
+    if let Some(ch) = reference.resolve_char_ref().context("Invalid character reference")? {
+        return Ok(ch.to_string());
+    }
+
+    let name = reference.decode().context("Invalid entity reference")?;
+    Ok(quick_xml::escape::resolve_predefined_entity(&name)
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("&{};", name)))
+}
+
+/// Unescaped value of an XML attribute, looked up by its qualified name (e.g. `r:id`)
+fn xml_attr(element: &BytesStart<'_>, name: &str) -> Option<String> {
+    element
+        .try_get_attribute(name)
+        .ok()
+        .flatten()
+        .and_then(|attr| attr.unescape_value().ok())
+        .map(|value| value.into_owned())
+}
+
+/// A relationship of a part in an Open Packaging Conventions (OOXML) package
+struct Relationship {
+    id: String,
+    /// Relationship type URI, e.g. `.../relationships/notesSlide`
+    kind: String,
+    /// Path of the target part inside the package
+    part: String,
+}
+
+/// Read the relationships of `part` from its `_rels/<name>.rels` sibling.
+///
+/// A part without relationships yields an empty list. External targets (hyperlinks)
+/// are skipped and relative targets are resolved against the part's directory.
+fn read_relationships<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    part: &str,
+) -> Result<Vec<Relationship>> {
+    let (dir, name) = part.rsplit_once('/').unwrap_or(("", part));
+    let rels_name = if dir.is_empty() {
+        format!("_rels/{}.rels", name)
+    } else {
+        format!("{}/_rels/{}.rels", dir, name)
+    };
+    let Some(xml) = read_zip_entry(archive, &rels_name)? else {
+        return Ok(Vec::new());
+    };
+
+    let mut reader = quick_xml::Reader::from_str(&xml);
+    let mut relationships = Vec::new();
+    loop {
+        match reader
+            .read_event()
+            .with_context(|| format!("Malformed relationships in {}", rels_name))?
+        {
+            Event::Start(ref e) | Event::Empty(ref e)
+                if e.local_name().as_ref() == b"Relationship" =>
+            {
+                if xml_attr(e, "TargetMode").as_deref() == Some("External") {
+                    continue;
+                }
+                if let (Some(id), Some(target)) = (xml_attr(e, "Id"), xml_attr(e, "Target")) {
+                    relationships.push(Relationship {
+                        id,
+                        kind: xml_attr(e, "Type").unwrap_or_default(),
+                        part: resolve_part(dir, &target),
+                    });
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(relationships)
+}
+
+/// Resolve a relationship target such as `../media/a.png` or `/xl/a.xml` to a zip path
+fn resolve_part(dir: &str, target: &str) -> String {
+    let mut segments: Vec<&str> = match target.strip_prefix('/') {
+        Some(_) => Vec::new(),
+        None => dir.split('/').filter(|s| !s.is_empty()).collect(),
+    };
+    for segment in target.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            _ => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
@@ -0,0 +1,150 @@
+use super::{decode_text, MarkdownDocument};
+use crate::ingestion::{join_sections, ExtractOptions, ExtractedDocument, Extractor, Section};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// Jupyter Notebook Extractor (.ipynb)
+pub struct NotebookExtractor;
+
+#[async_trait]
+impl Extractor for NotebookExtractor {
+    /// One section per non-empty cell, tagged with `cell_index` and `cell_type`, with
+    /// `language`, `kernel` and `title` from the notebook metadata
+    async fn extract(
+        &self,
+        file_path: &str,
+        options: &ExtractOptions,
+    ) -> Result<ExtractedDocument> {
+        let notebook = read_notebook(file_path, options.encoding.as_deref()).await?;
+        let mut document =
+            ExtractedDocument::new(notebook_sections(&notebook, options.include_outputs)?);
+        if let Some(language) = notebook_language(&notebook) {
+            document = document.with("language", language);
+        }
+        let kernel = notebook["metadata"]["kernelspec"]["display_name"].as_str();
+        let title = notebook["metadata"]["title"].as_str();
+        for (key, value) in [("kernel", kernel), ("title", title)] {
+            if let Some(value) = value.map(str::trim).filter(|v| !v.is_empty()) {
+                document = document.with(key, value);
+            }
+        }
+        Ok(document)
+    }
+
+    fn supports(&self, file_path: &str) -> bool {
+        file_path.to_lowercase().ends_with(".ipynb")
+    }
+
+    fn supports_mime_type(&self, mime_type: &str) -> bool {
+        mime_type == "application/x-ipynb+json"
+    }
+}
+
+async fn read_notebook(file_path: &str, encoding: Option<&str>) -> Result<Value> {
+    let bytes = tokio::fs::read(file_path)
+        .await
+        .context("Failed to read notebook file")?;
+    let (json, _) = decode_text(&bytes, encoding)?;
+    serde_json::from_str(&json).context("Malformed notebook JSON")
+}
+
+/// Render the cells of a notebook in order.
+///
+/// Markdown cells are converted to plain text like Markdown files, code cells become
+/// fenced code blocks in the notebook's language and raw cells are kept as they are.
+/// With `include_outputs`, the text outputs of a code cell (streams, plain-text results
+/// and errors) follow it in a fenced block of their own.
+pub fn notebook_sections(notebook: &Value, include_outputs: bool) -> Result<Vec<Section>> {
+    // nbformat 3 kept its cells in worksheets
+    let cells = notebook["cells"]
+        .as_array()
+        .or_else(|| notebook["worksheets"][0]["cells"].as_array())
+        .context("Notebook has no cells")?;
+    let language = notebook_language(notebook).unwrap_or_default();
+
+    let mut sections = Vec::new();
+    for (index, cell) in cells.iter().enumerate() {
+        let cell_type = cell["cell_type"].as_str().unwrap_or("raw");
+        let source = multiline_text(&cell["source"])
+            .or_else(|| multiline_text(&cell["input"]))
+            .unwrap_or_default();
+        let source = source.trim_matches('\n').trim_end();
+
+        let mut blocks = Vec::new();
+        match cell_type {
+            "markdown" | "heading" => {
+                let document = MarkdownDocument::parse(source, true)?;
+                blocks.push(join_sections(&document.sections));
+            }
+            "code" => {
+                if !source.trim().is_empty() {
+                    blocks.push(format!("```{}\n{}\n```", language, source));
+                }
+                let outputs = cell_outputs(cell);
+                if include_outputs && !outputs.is_empty() {
+                    blocks.push(format!("```\n{}\n```", outputs));
+                }
+            }
+            _ => blocks.push(source.to_string()),
+        }
+
+        let text = blocks
+            .into_iter()
+            .filter(|block| !block.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        if !text.is_empty() {
+            sections.push(
+                Section::new(text)
+                    .with("cell_index", index)
+                    .with("cell_type", cell_type),
+            );
+        }
+    }
+    Ok(sections)
+}
+
+/// The kernel's language, e.g. `python`
+fn notebook_language(notebook: &Value) -> Option<&str> {
+    let metadata = &notebook["metadata"];
+    metadata["language_info"]["name"]
+        .as_str()
+        .or_else(|| metadata["kernelspec"]["language"].as_str())
+        .or_else(|| notebook["worksheets"][0]["cells"][0]["language"].as_str())
+}
+
+/// Text of the outputs of a code cell; images and other rich outputs are skipped
+fn cell_outputs(cell: &Value) -> String {
+    let Some(outputs) = cell["outputs"].as_array() else {
+        return String::new();
+    };
+
+    outputs
+        .iter()
+        .filter_map(|output| match output["output_type"].as_str()? {
+            "stream" => multiline_text(&output["text"]),
+            "execute_result" | "display_data" => multiline_text(&output["data"]["text/plain"])
+                // nbformat 3 put the data next to the output type
+                .or_else(|| multiline_text(&output["text"])),
+            "pyerr" | "error" => Some(format!(
+                "{}: {}",
+                output["ename"].as_str().unwrap_or("Error"),
+                output["evalue"].as_str().unwrap_or_default()
+            )),
+            _ => None,
+        })
+        .map(|text| text.trim_end().to_string())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Notebook text fields are either a string or a list of lines
+fn multiline_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Array(lines) => Some(lines.iter().filter_map(Value::as_str).collect()),
+        _ => None,
+    }
+}
@@ -0,0 +1,170 @@
+use super::presentation::{clean_block, slide_sections, Slide};
+use super::{read_zip_entry, resolve_xml_ref, xml_attr};
+use crate::ingestion::{ExtractOptions, ExtractedDocument, Extractor};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::fs::File;
+use zip::ZipArchive;
+
+/// Presentation classes of frames that repeat on every slide
+const CHROME_CLASSES: &[&str] = &["page-number", "footer", "header", "date-time"];
+
+/// ODP Extractor (OpenDocument presentations)
+pub struct OdpExtractor;
+
+#[async_trait]
+impl Extractor for OdpExtractor {
+    /// One section per slide and one per set of speaker notes, tagged with `slide_number`
+    async fn extract(
+        &self,
+        file_path: &str,
+        _options: &ExtractOptions,
+    ) -> Result<ExtractedDocument> {
+        let path = file_path.to_string();
+        tokio::task::spawn_blocking(move || {
+            Ok(ExtractedDocument::new(slide_sections(&read_odp(&path)?)))
+        })
+        .await
+        .context("ODP extraction task panicked")?
+    }
+
+    fn supports(&self, file_path: &str) -> bool {
+        file_path.to_lowercase().ends_with(".odp")
+    }
+
+    fn supports_mime_type(&self, mime_type: &str) -> bool {
+        mime_type == "application/vnd.oasis.opendocument.presentation"
+    }
+}
+
+/// Read the slides (`draw:page`) of an OpenDocument presentation
+pub fn read_odp(file_path: &str) -> Result<Vec<Slide>> {
+    let file = File::open(file_path).context("Failed to open ODP file")?;
+    let mut archive = ZipArchive::new(file).context("Failed to read ODP package")?;
+    let content =
+        read_zip_entry(&mut archive, "content.xml")?.context("ODP package has no content.xml")?;
+    parse_pages(&content)
+}
+
+/// A frame or drawing shape being read, with its `presentation:class`
+#[derive(Default)]
+struct Frame {
+    class: Option<String>,
+    paragraphs: Vec<String>,
+    rows: Vec<String>,
+}
+
+fn parse_pages(xml: &str) -> Result<Vec<Slide>> {
+    let mut reader = Reader::from_str(xml);
+    let mut slides: Vec<Slide> = Vec::new();
+    let mut frames: Vec<Frame> = Vec::new();
+    let mut paragraph: Option<String> = None;
+    let mut row: Vec<String> = Vec::new();
+    let mut cell: Option<String> = None;
+    let mut in_notes = false;
+    let mut in_annotation = false;
+
+    loop {
+        match reader.read_event().context("Malformed ODP content")? {
+            Event::Start(ref e) => match e.local_name().as_ref() {
+                b"page" => slides.push(Slide {
+                    number: slides.len() + 1,
+                    ..Slide::default()
+                }),
+                b"notes" => in_notes = true,
+                b"annotation" => in_annotation = true,
+                b"frame" | b"custom-shape" => frames.push(Frame {
+                    class: xml_attr(e, "presentation:class"),
+                    ..Frame::default()
+                }),
+                b"p" | b"h" if !in_annotation => paragraph = Some(String::new()),
+                b"table-row" => row.clear(),
+                b"table-cell" => cell = Some(String::new()),
+                _ => {}
+            },
+            // An empty page still takes its place in the slide order
+            Event::Empty(ref e) if e.local_name().as_ref() == b"page" => slides.push(Slide {
+                number: slides.len() + 1,
+                ..Slide::default()
+            }),
+            Event::Empty(ref e) if paragraph.is_some() => {
+                let text = match e.local_name().as_ref() {
+                    b"s" => " ".repeat(
+                        xml_attr(e, "text:c")
+                            .and_then(|c| c.parse().ok())
+                            .unwrap_or(1),
+                    ),
+                    b"tab" => "\t".to_string(),
+                    b"line-break" => "\n".to_string(),
+                    _ => continue,
+                };
+                paragraph.get_or_insert_default().push_str(&text);
+            }
+            Event::Text(ref e) if paragraph.is_some() => {
+                let text = e.xml10_content().context("Invalid text in ODP")?;
+                paragraph.get_or_insert_default().push_str(&text);
+            }
+            Event::GeneralRef(ref e) if paragraph.is_some() => {
+                let text = resolve_xml_ref(e)?;
+                paragraph.get_or_insert_default().push_str(&text);
+            }
+            Event::End(ref e) => match e.local_name().as_ref() {
+                b"notes" => in_notes = false,
+                b"annotation" => in_annotation = false,
+                b"p" | b"h" => {
+                    let Some(text) = paragraph.take().map(|p| clean_block(&p)) else {
+                        continue;
+                    };
+                    if text.is_empty() {
+                        continue;
+                    }
+                    if let Some(cell) = cell.as_mut() {
+                        if !cell.is_empty() {
+                            cell.push(' ');
+                        }
+                        cell.push_str(&text.replace('\n', " "));
+                    } else if let Some(frame) = frames.last_mut() {
+                        frame.paragraphs.push(text);
+                    }
+                }
+                b"table-cell" => row.extend(cell.take()),
+                b"table-row" if row.iter().any(|cell| !cell.is_empty()) => {
+                    if let Some(frame) = frames.last_mut() {
+                        frame.rows.push(row.join(" | "));
+                    }
+                }
+                b"frame" | b"custom-shape" => {
+                    let (Some(frame), Some(slide)) = (frames.pop(), slides.last_mut()) else {
+                        continue;
+                    };
+                    let class = frame.class.as_deref();
+                    if class.is_some_and(|c| CHROME_CLASSES.contains(&c)) {
+                        continue;
+                    }
+                    if in_notes {
+                        slide.notes.extend(frame.paragraphs);
+                    } else if class == Some("title") && slide.title.is_none() {
+                        let text = frame.paragraphs.join(" ").replace('\n', " ");
+                        slide.title = Some(text).filter(|t| !t.is_empty());
+                    } else {
+                        let text = if frame.rows.is_empty() {
+                            frame.paragraphs.join("\n")
+                        } else {
+                            frame.rows.join("\n")
+                        };
+                        if !text.is_empty() {
+                            slide.body.push(text);
+                        }
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(slides)
+}
@@ -0,0 +1,170 @@
+use super::spreadsheet::{sheet_sections, Sheet, MAX_COLUMNS};
+use super::{read_zip_entry, resolve_xml_ref, xml_attr};
+use crate::ingestion::{ExtractOptions, ExtractedDocument, Extractor};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::fs::File;
+use zip::ZipArchive;
+
+/// Cap on how often a non-empty repeated row or cell is copied out
+const MAX_REPEAT: usize = 1000;
+
+/// ODS Extractor (OpenDocument spreadsheets)
+pub struct OdsExtractor;
+
+#[async_trait]
+impl Extractor for OdsExtractor {
+    /// Sections of up to 25 rows, tagged with their `sheet` and row range
+    async fn extract(
+        &self,
+        file_path: &str,
+        _options: &ExtractOptions,
+    ) -> Result<ExtractedDocument> {
+        let path = file_path.to_string();
+        tokio::task::spawn_blocking(move || {
+            Ok(ExtractedDocument::new(sheet_sections(&read_ods(&path)?)))
+        })
+        .await
+        .context("ODS extraction task panicked")?
+    }
+
+    fn supports(&self, file_path: &str) -> bool {
+        file_path.to_lowercase().ends_with(".ods")
+    }
+
+    fn supports_mime_type(&self, mime_type: &str) -> bool {
+        mime_type == "application/vnd.oasis.opendocument.spreadsheet"
+    }
+}
+
+/// Read every table of an OpenDocument spreadsheet, showing each cell's displayed text
+pub fn read_ods(file_path: &str) -> Result<Vec<Sheet>> {
+    let file = File::open(file_path).context("Failed to open ODS file")?;
+    let mut archive = ZipArchive::new(file).context("Failed to read ODS package")?;
+    let content =
+        read_zip_entry(&mut archive, "content.xml")?.context("ODS package has no content.xml")?;
+    parse_tables(&content)
+}
+
+fn parse_tables(xml: &str) -> Result<Vec<Sheet>> {
+    let mut reader = Reader::from_str(xml);
+    let mut sheets: Vec<Sheet> = Vec::new();
+    let mut row_number = 0usize;
+    let mut row: Vec<String> = Vec::new();
+    // Empty cells are only written out once a non-empty cell follows them
+    let mut pending_empty = 0usize;
+    let mut row_repeat = 1usize;
+    let mut cell: Option<(String, usize)> = None;
+    let mut paragraphs = 0usize;
+    let mut in_annotation = false;
+
+    loop {
+        let event = reader.read_event().context("Malformed ODS content")?;
+        match event {
+            Event::Start(ref e) => match e.local_name().as_ref() {
+                b"table" => {
+                    sheets.push(Sheet {
+                        name: xml_attr(e, "table:name").unwrap_or_default(),
+                        rows: Vec::new(),
+                    });
+                    row_number = 0;
+                }
+                b"table-row" => {
+                    row.clear();
+                    pending_empty = 0;
+                    row_repeat = repeat(e, "table:number-rows-repeated");
+                }
+                b"table-cell" | b"covered-table-cell" => {
+                    cell = Some((String::new(), repeat(e, "table:number-columns-repeated")));
+                    paragraphs = 0;
+                }
+                b"annotation" => in_annotation = true,
+                b"p" | b"h" if !in_annotation => {
+                    if let Some((text, _)) = cell.as_mut() {
+                        if paragraphs > 0 {
+                            text.push('\n');
+                        }
+                        paragraphs += 1;
+                    }
+                }
+                _ => {}
+            },
+            Event::Empty(ref e) => match e.local_name().as_ref() {
+                b"table-row" => {
+                    row_number = row_number.saturating_add(repeat(e, "table:number-rows-repeated"))
+                }
+                b"table-cell" | b"covered-table-cell" => {
+                    pending_empty = (pending_empty + repeat(e, "table:number-columns-repeated"))
+                        .min(MAX_COLUMNS);
+                }
+                b"s" if !in_annotation => {
+                    if let Some((text, _)) = cell.as_mut() {
+                        text.push_str(&" ".repeat(repeat(e, "text:c").min(MAX_REPEAT)));
+                    }
+                }
+                b"tab" if !in_annotation => push_text(&mut cell, "\t"),
+                b"line-break" if !in_annotation => push_text(&mut cell, "\n"),
+                _ => {}
+            },
+            Event::Text(ref e) if !in_annotation => {
+                let text = e.xml10_content().context("Invalid text in ODS")?;
+                push_text(&mut cell, &text);
+            }
+            Event::GeneralRef(ref e) if !in_annotation => {
+                let text = resolve_xml_ref(e)?;
+                push_text(&mut cell, &text);
+            }
+            Event::End(ref e) => match e.local_name().as_ref() {
+                b"annotation" => in_annotation = false,
+                b"table-cell" | b"covered-table-cell" => {
+                    let Some((text, count)) = cell.take() else {
+                        continue;
+                    };
+                    if text.trim().is_empty() {
+                        pending_empty = (pending_empty + count).min(MAX_COLUMNS);
+                        continue;
+                    }
+                    // Rows never grow past the last column, however much is repeated
+                    let room = MAX_COLUMNS.saturating_sub(row.len());
+                    row.extend(std::iter::repeat_n(String::new(), pending_empty.min(room)));
+                    let room = MAX_COLUMNS.saturating_sub(row.len());
+                    pending_empty = 0;
+                    row.extend(std::iter::repeat_n(text, count.min(MAX_REPEAT).min(room)));
+                }
+                b"table-row" => {
+                    let first = row_number.saturating_add(1);
+                    row_number = row_number.saturating_add(row_repeat);
+                    if row.is_empty() {
+                        continue;
+                    }
+                    if let Some(sheet) = sheets.last_mut() {
+                        for number in first..first.saturating_add(row_repeat.min(MAX_REPEAT)) {
+                            sheet.rows.push((number, row.clone()));
+                        }
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(sheets)
+}
+
+fn push_text(cell: &mut Option<(String, usize)>, text: &str) {
+    if let Some((cell, _)) = cell.as_mut() {
+        cell.push_str(text);
+    }
+}
+
+/// A repeat count attribute, defaulting to 1
+fn repeat(element: &BytesStart<'_>, name: &str) -> usize {
+    xml_attr(element, name)
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(1)
+        .max(1)
+}
@@ -0,0 +1,252 @@
+use super::docx::Table;
+use super::{read_zip_entry, resolve_xml_ref, xml_attr};
+use crate::ingestion::{ExtractOptions, ExtractedDocument, Extractor, Section};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde_json::{Map, Value};
+use std::fs::File;
+use zip::ZipArchive;
+
+/// Elements whose text is not part of the document flow: comments, footnote
+/// markers, deleted text kept for change tracking and generated indexes
+const SKIPPED_ELEMENTS: &[&[u8]] = &[
+    b"annotation",
+    b"note-citation",
+    b"tracked-changes",
+    b"table-of-content",
+];
+
+/// ODT Extractor (OpenDocument text documents)
+pub struct OdtExtractor;
+
+#[async_trait]
+impl Extractor for OdtExtractor {
+    /// The text as one section, with title, author and language from `meta.xml`
+    async fn extract(
+        &self,
+        file_path: &str,
+        _options: &ExtractOptions,
+    ) -> Result<ExtractedDocument> {
+        let path = file_path.to_string();
+        tokio::task::spawn_blocking(move || read_odt(&path))
+            .await
+            .context("ODT extraction task panicked")?
+    }
+
+    fn supports(&self, file_path: &str) -> bool {
+        file_path.to_lowercase().ends_with(".odt")
+    }
+
+    fn supports_mime_type(&self, mime_type: &str) -> bool {
+        mime_type == "application/vnd.oasis.opendocument.text"
+    }
+}
+
+fn open_odt(file_path: &str) -> Result<ZipArchive<File>> {
+    let file = File::open(file_path).context("Failed to open ODT file")?;
+    ZipArchive::new(file).context("Failed to read ODT package")
+}
+
+/// Extract the text and metadata of an ODT package.
+///
+/// Paragraphs, headings and tables are separated by blank lines like DOCX output;
+/// footnotes and endnotes follow the body.
+fn read_odt(file_path: &str) -> Result<ExtractedDocument> {
+    let mut archive = open_odt(file_path)?;
+    let content =
+        read_zip_entry(&mut archive, "content.xml")?.context("ODT package has no content.xml")?;
+    let metadata = match read_zip_entry(&mut archive, "meta.xml")? {
+        Some(xml) => parse_meta(&xml)?,
+        None => Map::new(),
+    };
+    Ok(ExtractedDocument {
+        metadata,
+        sections: vec![Section::new(parse_blocks(&content)?.join("\n\n"))],
+    })
+}
+
+/// Walk `content.xml` and return its paragraphs and tables as text blocks, notes last.
+///
+/// Headings are prefixed with `#` markers matching their `text:outline-level`.
+fn parse_blocks(xml: &str) -> Result<Vec<String>> {
+    let mut reader = Reader::from_str(xml);
+    let mut blocks = Vec::new();
+    let mut notes = Vec::new();
+    // Notes and frames nest paragraphs inside paragraphs, so keep a stack
+    let mut paragraphs: Vec<(String, usize)> = Vec::new();
+    let mut tables: Vec<Table> = Vec::new();
+    let mut note_depth = 0usize;
+    let mut skip_depth = 0usize;
+
+    loop {
+        let event = reader.read_event().context("Malformed ODT content")?;
+
+        if skip_depth > 0 {
+            match event {
+                Event::Start(ref e) if SKIPPED_ELEMENTS.contains(&e.local_name().as_ref()) => {
+                    skip_depth += 1;
+                }
+                Event::End(ref e) if SKIPPED_ELEMENTS.contains(&e.local_name().as_ref()) => {
+                    skip_depth -= 1;
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+            continue;
+        }
+
+        match event {
+            Event::Start(ref e) => match e.local_name().as_ref() {
+                name if SKIPPED_ELEMENTS.contains(&name) => skip_depth = 1,
+                b"p" => paragraphs.push((String::new(), 0)),
+                b"h" => {
+                    let level = xml_attr(e, "text:outline-level")
+                        .and_then(|level| level.parse::<usize>().ok())
+                        .unwrap_or(1);
+                    paragraphs.push((String::new(), level.clamp(1, 6)));
+                }
+                b"note-body" => note_depth += 1,
+                b"table" => tables.push(Table::default()),
+                b"table-row" => {
+                    if let Some(table) = tables.last_mut() {
+                        table.rows.push(Vec::new());
+                    }
+                }
+                b"table-cell" | b"covered-table-cell" => {
+                    if let Some(row) = tables.last_mut().and_then(|t| t.rows.last_mut()) {
+                        row.push(String::new());
+                    }
+                }
+                _ => {}
+            },
+            Event::Empty(ref e) => match e.local_name().as_ref() {
+                b"s" => {
+                    let count = xml_attr(e, "text:c")
+                        .and_then(|c| c.parse().ok())
+                        .unwrap_or(1);
+                    push_text(&mut paragraphs, &" ".repeat(count));
+                }
+                b"tab" => push_text(&mut paragraphs, "\t"),
+                b"line-break" => push_text(&mut paragraphs, "\n"),
+                b"table-cell" | b"covered-table-cell" => {
+                    if let Some(row) = tables.last_mut().and_then(|t| t.rows.last_mut()) {
+                        row.push(String::new());
+                    }
+                }
+                _ => {}
+            },
+            Event::Text(ref e) => {
+                let text = e.xml10_content().context("Invalid text in ODT")?;
+                push_text(&mut paragraphs, &text);
+            }
+            Event::GeneralRef(ref e) => {
+                let text = resolve_xml_ref(e)?;
+                push_text(&mut paragraphs, &text);
+            }
+            Event::End(ref e) => match e.local_name().as_ref() {
+                b"note-body" => note_depth = note_depth.saturating_sub(1),
+                b"p" | b"h" => {
+                    if let Some((text, level)) = paragraphs.pop() {
+                        let text = text.trim();
+                        if text.is_empty() {
+                            continue;
+                        }
+                        if note_depth > 0 {
+                            notes.push(text.to_string());
+                        } else if let Some(cell) = tables.last_mut().and_then(Table::current_cell) {
+                            if !cell.is_empty() {
+                                cell.push('\n');
+                            }
+                            cell.push_str(text);
+                        } else if level > 0 {
+                            blocks.push(format!("{} {}", "#".repeat(level), text));
+                        } else {
+                            blocks.push(text.to_string());
+                        }
+                    }
+                }
+                b"table" => {
+                    if let Some(table) = tables.pop() {
+                        let rendered = table.render();
+                        if rendered.is_empty() {
+                            continue;
+                        }
+                        // Nested tables are flattened into the enclosing cell
+                        if let Some(cell) = tables.last_mut().and_then(Table::current_cell) {
+                            if !cell.is_empty() {
+                                cell.push('\n');
+                            }
+                            cell.push_str(&rendered);
+                        } else {
+                            blocks.push(rendered);
+                        }
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    blocks.extend(notes);
+    Ok(blocks)
+}
+
+fn push_text(paragraphs: &mut [(String, usize)], text: &str) {
+    if let Some((paragraph, _)) = paragraphs.last_mut() {
+        paragraph.push_str(text);
+    }
+}
+
+/// Read the document title, author and language from `meta.xml`.
+///
+/// The initial creator is the author; `dc:creator` (the last editor) is only used
+/// when it is missing.
+fn parse_meta(xml: &str) -> Result<Map<String, Value>> {
+    let mut reader = Reader::from_str(xml);
+    let mut field: Option<&'static str> = None;
+    let mut value = String::new();
+    let mut metadata = Map::new();
+    let mut last_editor: Option<String> = None;
+
+    loop {
+        match reader.read_event().context("Malformed ODT metadata")? {
+            Event::Start(ref e) => {
+                field = match e.name().as_ref() {
+                    b"dc:title" => Some("title"),
+                    b"meta:initial-creator" => Some("author"),
+                    b"dc:creator" => Some("creator"),
+                    b"dc:language" => Some("language"),
+                    _ => None,
+                };
+                value.clear();
+            }
+            Event::Text(ref e) if field.is_some() => {
+                value.push_str(&e.xml10_content().context("Invalid text in ODT metadata")?);
+            }
+            Event::GeneralRef(ref e) if field.is_some() => value.push_str(&resolve_xml_ref(e)?),
+            Event::End(_) => {
+                let text = value.trim();
+                match field.take() {
+                    Some("creator") if !text.is_empty() => last_editor = Some(text.to_string()),
+                    Some(key) if !text.is_empty() => {
+                        metadata.insert(key.to_string(), Value::String(text.to_string()));
+                    }
+                    _ => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if let Some(editor) = last_editor {
+        metadata
+            .entry("author")
+            .or_insert_with(|| Value::String(editor));
+    }
+    Ok(metadata)
+}
@@ -0,0 +1,366 @@
+use super::ocr_fallback;
+use crate::ingestion::{ExtractOptions, ExtractedDocument, Extractor, Section};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use pdf_extract::PlainTextOutput;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Default resolution used when rendering pages for OCR
+const DEFAULT_RASTER_DPI: &str = "300";
+
+/// PDF Extractor
+pub struct PdfExtractor;
+
+#[async_trait]
+impl Extractor for PdfExtractor {
+    /// One section per page, tagged with its 1-based `page` number.
+    ///
+    /// Pages without a text layer are rendered to images and OCRed; those sections
+    /// are tagged with `ocr: true`.
+    async fn extract(
+        &self,
+        file_path: &str,
+        options: &ExtractOptions,
+    ) -> Result<ExtractedDocument> {
+        // Try native PDF extraction first
+        let path = file_path.to_string();
+        let extracted = tokio::task::spawn_blocking(move || extract_pages(&path))
+            .await
+            .context("PDF extraction task panicked")?;
+        match extracted {
+            Ok(pages) => {
+                let missing: Vec<u32> = pages
+                    .iter()
+                    .filter(|(_, text)| text.is_empty())
+                    .map(|(number, _)| *number)
+                    .collect();
+
+                let mut recognised = Vec::new();
+                if !missing.is_empty() {
+                    tracing::info!(
+                        "{} of {} pages in {} have no text layer, running OCR",
+                        missing.len(),
+                        pages.len(),
+                        file_path
+                    );
+                    recognised = ocr_pages(file_path, &missing, &options.ocr_languages).await?;
+                }
+
+                Ok(ExtractedDocument::new(
+                    pages
+                        .into_iter()
+                        .map(|(number, text)| {
+                            match recognised.iter().find(|(page, _)| *page == number) {
+                                Some((_, ocr_text)) => Section::new(ocr_text.clone())
+                                    .with("page", number)
+                                    .with("ocr", true),
+                                None => Section::new(text).with("page", number),
+                            }
+                        })
+                        .collect(),
+                ))
+            }
+            Err(e) => {
+                // Fallback to OCR of the whole document
+                tracing::warn!(
+                    "PDF extraction failed ({}), falling back to OCR for {}",
+                    e,
+                    file_path
+                );
+                let pages = ocr_document(file_path, &options.ocr_languages).await?;
+                Ok(ExtractedDocument::new(
+                    pages
+                        .into_iter()
+                        .map(|(number, text)| {
+                            Section::new(text).with("page", number).with("ocr", true)
+                        })
+                        .collect(),
+                ))
+            }
+        }
+    }
+
+    fn supports(&self, file_path: &str) -> bool {
+        file_path.to_lowercase().ends_with(".pdf")
+    }
+
+    fn supports_mime_type(&self, mime_type: &str) -> bool {
+        mime_type == "application/pdf"
+    }
+}
+
+/// Extract the text layer of every page, keyed by page number.
+///
+/// A page whose content cannot be interpreted yields an empty string rather than
+/// failing the whole document.
+fn extract_pages(file_path: &str) -> Result<Vec<(u32, String)>> {
+    let doc = load_document(file_path)?;
+
+    let mut pages = Vec::new();
+    for number in doc.get_pages().into_keys() {
+        let mut text = String::new();
+        let result = {
+            let mut output = PlainTextOutput::new(&mut text);
+            pdf_extract::output_doc_page(&doc, &mut output, number)
+        };
+        if let Err(e) = result {
+            tracing::warn!(
+                "Failed to extract text from page {} of {}: {}",
+                number,
+                file_path,
+                e
+            );
+            text.clear();
+        }
+        pages.push((number, text.trim().to_string()));
+    }
+
+    Ok(pages)
+}
+
+fn load_document(file_path: &str) -> Result<lopdf::Document> {
+    let mut doc = lopdf::Document::load(file_path).context("Failed to parse PDF")?;
+    if doc.is_encrypted() {
+        doc.decrypt("").context("PDF is password protected")?;
+    }
+    Ok(doc)
+}
+
+/// OCR the given pages, returning the text of every page that produced any.
+///
+/// Pages are rendered with the configured rasterizer; when that is unavailable the
+/// page's embedded images are OCRed instead. Failures are logged per page so one bad
+/// page does not lose the rest of the document.
+async fn ocr_pages(
+    file_path: &str,
+    pages: &[u32],
+    languages: &[String],
+) -> Result<Vec<(u32, String)>> {
+    let dir = tempfile::tempdir().context("Failed to create OCR work directory")?;
+    let mut document: Option<Arc<lopdf::Document>> = None;
+    let mut results = Vec::new();
+
+    for &page in pages {
+        let images = match render_page(file_path, page, dir.path()).await {
+            Ok(image) => vec![image],
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to render page {} of {} ({}), using its embedded images",
+                    page,
+                    file_path,
+                    e
+                );
+                if document.is_none() {
+                    let path = file_path.to_string();
+                    let loaded = tokio::task::spawn_blocking(move || load_document(&path))
+                        .await
+                        .context("PDF parsing task panicked")??;
+                    document = Some(Arc::new(loaded));
+                }
+                let Some(doc) = document.clone() else {
+                    continue;
+                };
+                let images_dir = dir.path().to_path_buf();
+                let images =
+                    tokio::task::spawn_blocking(move || embedded_images(&doc, page, &images_dir))
+                        .await
+                        .context("PDF image extraction task panicked")?;
+                match images {
+                    Ok(images) => images,
+                    Err(e) => {
+                        tracing::warn!("No usable images on page {} of {}: {}", page, file_path, e);
+                        continue;
+                    }
+                }
+            }
+        };
+
+        if let Some(text) = ocr_images(&images, languages).await {
+            results.push((page, text));
+        }
+    }
+
+    Ok(results)
+}
+
+/// OCR every page of a PDF that could not be parsed, relying on the rasterizer alone
+async fn ocr_document(file_path: &str, languages: &[String]) -> Result<Vec<(u32, String)>> {
+    let dir = tempfile::tempdir().context("Failed to create OCR work directory")?;
+    let images = render_all(file_path, dir.path())
+        .await
+        .context("PDF could not be parsed or rendered for OCR")?;
+
+    let mut results = Vec::new();
+    for (page, image) in images {
+        if let Some(text) = ocr_images(std::slice::from_ref(&image), languages).await {
+            results.push((page, text));
+        }
+    }
+
+    if results.is_empty() {
+        anyhow::bail!("OCR produced no text for {}", file_path);
+    }
+    Ok(results)
+}
+
+async fn ocr_images(images: &[PathBuf], languages: &[String]) -> Option<String> {
+    let mut texts = Vec::new();
+    for image in images {
+        match ocr_fallback(&image.to_string_lossy(), languages).await {
+            Ok(text) if !text.trim().is_empty() => texts.push(text.trim().to_string()),
+            Ok(_) => {}
+            Err(e) => tracing::warn!("OCR failed for {}: {}", image.display(), e),
+        }
+    }
+    (!texts.is_empty()).then(|| texts.join("\n\n"))
+}
+
+/// External command used to render pages, from `PDF_RASTERIZER` (`pdftoppm` or `mutool`)
+fn rasterizer() -> (String, bool) {
+    let program = std::env::var("PDF_RASTERIZER").unwrap_or_else(|_| "pdftoppm".to_string());
+    let is_mutool = Path::new(&program)
+        .file_stem()
+        .is_some_and(|stem| stem.to_string_lossy().starts_with("mutool"));
+    (program, is_mutool)
+}
+
+fn raster_dpi() -> String {
+    std::env::var("PDF_RASTER_DPI").unwrap_or_else(|_| DEFAULT_RASTER_DPI.to_string())
+}
+
+/// Render a single page to a PNG in `dir`
+async fn render_page(file_path: &str, page: u32, dir: &Path) -> Result<PathBuf> {
+    let (program, is_mutool) = rasterizer();
+    let dpi = raster_dpi();
+    let page_arg = page.to_string();
+    let prefix = dir.join(format!("page-{}", page));
+    let image = prefix.with_extension("png");
+
+    let mut command = tokio::process::Command::new(&program);
+    if is_mutool {
+        command
+            .args(["draw", "-r", &dpi, "-o"])
+            .arg(&image)
+            .arg(file_path)
+            .arg(&page_arg);
+    } else {
+        command
+            .args([
+                "-r",
+                &dpi,
+                "-f",
+                &page_arg,
+                "-l",
+                &page_arg,
+                "-singlefile",
+                "-png",
+            ])
+            .arg(file_path)
+            .arg(&prefix);
+    }
+
+    run_rasterizer(&program, command).await?;
+    Ok(image)
+}
+
+/// Render every page to PNGs in `dir`, returned in page order
+async fn render_all(file_path: &str, dir: &Path) -> Result<Vec<(u32, PathBuf)>> {
+    let (program, is_mutool) = rasterizer();
+    let dpi = raster_dpi();
+
+    let mut command = tokio::process::Command::new(&program);
+    if is_mutool {
+        command
+            .args(["draw", "-r", &dpi, "-o"])
+            .arg(dir.join("page-%d.png"))
+            .arg(file_path);
+    } else {
+        command
+            .args(["-r", &dpi, "-png"])
+            .arg(file_path)
+            .arg(dir.join("page"));
+    }
+    run_rasterizer(&program, command).await?;
+
+    // pdftoppm zero-pads page numbers depending on the page count, so parse them back
+    let mut images: Vec<(u32, PathBuf)> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter_map(|path| {
+            let stem = path.file_stem()?.to_string_lossy().to_string();
+            let page = stem.rsplit('-').next()?.parse().ok()?;
+            Some((page, path))
+        })
+        .collect();
+    images.sort();
+    Ok(images)
+}
+
+async fn run_rasterizer(program: &str, mut command: tokio::process::Command) -> Result<()> {
+    let output = command
+        .output()
+        .await
+        .with_context(|| format!("Failed to run {}. Make sure it's installed.", program))?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(())
+}
+
+/// Write the image XObjects of a page to `dir` in formats Tesseract can read.
+///
+/// JPEG streams are written as-is; 8-bit grayscale and RGB streams are decoded and
+/// wrapped in a PNM header. Other encodings are skipped.
+fn embedded_images(doc: &lopdf::Document, page: u32, dir: &Path) -> Result<Vec<PathBuf>> {
+    let page_id = *doc
+        .get_pages()
+        .get(&page)
+        .with_context(|| format!("Page {} not found", page))?;
+
+    let mut paths = Vec::new();
+    for (idx, image) in doc.get_page_images(page_id)?.into_iter().enumerate() {
+        let filters = image.filters.clone().unwrap_or_default();
+        let base = dir.join(format!("page-{}-image-{}", page, idx));
+
+        if filters.iter().any(|f| f == "DCTDecode") {
+            let path = base.with_extension("jpg");
+            std::fs::write(&path, image.content)?;
+            paths.push(path);
+            continue;
+        }
+
+        let magic = match (image.color_space.as_deref(), image.bits_per_component) {
+            (Some("DeviceGray"), Some(8)) => "P5",
+            (Some("DeviceRGB"), Some(8)) => "P6",
+            _ => continue,
+        };
+        let pixels = if filters.is_empty() {
+            image.content.to_vec()
+        } else {
+            match doc
+                .get_object(image.id)
+                .and_then(lopdf::Object::as_stream)
+                .and_then(lopdf::Stream::decompressed_content)
+            {
+                Ok(pixels) => pixels,
+                Err(_) => continue,
+            }
+        };
+
+        let path = base.with_extension("pnm");
+        let mut data = format!("{}\n{} {}\n255\n", magic, image.width, image.height).into_bytes();
+        data.extend_from_slice(&pixels);
+        std::fs::write(&path, data)?;
+        paths.push(path);
+    }
+
+    if paths.is_empty() {
+        anyhow::bail!("no embedded images in a supported format");
+    }
+    Ok(paths)
+}
@@ -0,0 +1,219 @@
+use super::presentation::{clean_block, slide_sections, Slide};
+use super::{read_relationships, read_zip_entry, resolve_xml_ref, xml_attr};
+use crate::ingestion::{ExtractOptions, ExtractedDocument, Extractor};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::fs::File;
+use zip::ZipArchive;
+
+/// Placeholders that repeat on every slide (slide number, date, footer) or hold no text
+const CHROME_PLACEHOLDERS: &[&str] = &["sldNum", "dt", "ftr", "hdr", "sldImg"];
+
+/// PPTX Extractor (Office Open XML presentations)
+pub struct PptxExtractor;
+
+#[async_trait]
+impl Extractor for PptxExtractor {
+    /// One section per slide and one per set of speaker notes, tagged with `slide_number`
+    async fn extract(
+        &self,
+        file_path: &str,
+        _options: &ExtractOptions,
+    ) -> Result<ExtractedDocument> {
+        let path = file_path.to_string();
+        tokio::task::spawn_blocking(move || {
+            Ok(ExtractedDocument::new(slide_sections(&read_pptx(&path)?)))
+        })
+        .await
+        .context("PPTX extraction task panicked")?
+    }
+
+    fn supports(&self, file_path: &str) -> bool {
+        file_path.to_lowercase().ends_with(".pptx")
+    }
+
+    fn supports_mime_type(&self, mime_type: &str) -> bool {
+        mime_type == "application/vnd.openxmlformats-officedocument.presentationml.presentation"
+    }
+}
+
+/// Read the slides of a presentation in the order they are shown
+pub fn read_pptx(file_path: &str) -> Result<Vec<Slide>> {
+    let file = File::open(file_path).context("Failed to open PPTX file")?;
+    let mut archive = ZipArchive::new(file).context("Failed to read PPTX package")?;
+
+    let presentation = read_zip_entry(&mut archive, "ppt/presentation.xml")?
+        .context("PPTX package has no ppt/presentation.xml")?;
+    let relationships = read_relationships(&mut archive, "ppt/presentation.xml")?;
+
+    let mut slides = Vec::new();
+    for (idx, id) in slide_ids(&presentation)?.into_iter().enumerate() {
+        let Some(part) = relationships
+            .iter()
+            .find(|r| r.id == id)
+            .map(|r| r.part.clone())
+        else {
+            continue;
+        };
+        let Some(xml) = read_zip_entry(&mut archive, &part)? else {
+            tracing::warn!("PPTX slide part {} is missing", part);
+            continue;
+        };
+        let (title, body) = parse_shapes(&xml)?;
+
+        let notes_part = read_relationships(&mut archive, &part)?
+            .into_iter()
+            .find(|r| r.kind.ends_with("/notesSlide"))
+            .map(|r| r.part);
+        let notes = match notes_part {
+            Some(notes_part) => match read_zip_entry(&mut archive, &notes_part)? {
+                Some(xml) => parse_shapes(&xml)?.1,
+                None => Vec::new(),
+            },
+            None => Vec::new(),
+        };
+
+        slides.push(Slide {
+            number: idx + 1,
+            title,
+            body,
+            notes,
+        });
+    }
+
+    Ok(slides)
+}
+
+/// Relationship ids of the slides listed in `presentation.xml`
+fn slide_ids(xml: &str) -> Result<Vec<String>> {
+    let mut reader = Reader::from_str(xml);
+    let mut ids = Vec::new();
+    loop {
+        match reader.read_event().context("Malformed PPTX presentation")? {
+            Event::Start(ref e) | Event::Empty(ref e) if e.local_name().as_ref() == b"sldId" => {
+                ids.extend(xml_attr(e, "r:id"));
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(ids)
+}
+
+/// A shape (text box, placeholder or table frame) being read
+#[derive(Default)]
+struct Shape {
+    placeholder: Option<String>,
+    paragraphs: Vec<String>,
+    rows: Vec<String>,
+}
+
+/// The title and the text blocks of the other shapes of a slide or notes page.
+///
+/// Each shape becomes one block with a line per paragraph; tables get a
+/// `a | b` line per row.
+fn parse_shapes(xml: &str) -> Result<(Option<String>, Vec<String>)> {
+    let mut reader = Reader::from_str(xml);
+    let mut title: Option<String> = None;
+    let mut blocks = Vec::new();
+    // Group shapes nest, so keep a stack
+    let mut shapes: Vec<Shape> = Vec::new();
+    let mut paragraph: Option<String> = None;
+    let mut row: Vec<String> = Vec::new();
+    let mut cell: Option<String> = None;
+    let mut in_text = false;
+
+    loop {
+        match reader.read_event().context("Malformed PPTX slide")? {
+            Event::Start(ref e) => match e.local_name().as_ref() {
+                b"sp" | b"graphicFrame" => shapes.push(Shape::default()),
+                b"ph" => set_placeholder(&mut shapes, xml_attr(e, "type")),
+                b"p" => paragraph = Some(String::new()),
+                b"t" => in_text = true,
+                b"tr" => row.clear(),
+                b"tc" => cell = Some(String::new()),
+                _ => {}
+            },
+            Event::Empty(ref e) => match e.local_name().as_ref() {
+                b"ph" => set_placeholder(&mut shapes, xml_attr(e, "type")),
+                b"br" => {
+                    if let Some(paragraph) = paragraph.as_mut() {
+                        paragraph.push('\n');
+                    }
+                }
+                _ => {}
+            },
+            Event::Text(ref e) if in_text => {
+                if let Some(paragraph) = paragraph.as_mut() {
+                    paragraph.push_str(&e.xml10_content().context("Invalid text in PPTX")?);
+                }
+            }
+            Event::GeneralRef(ref e) if in_text => {
+                if let Some(paragraph) = paragraph.as_mut() {
+                    paragraph.push_str(&resolve_xml_ref(e)?);
+                }
+            }
+            Event::End(ref e) => match e.local_name().as_ref() {
+                b"t" => in_text = false,
+                b"p" => {
+                    let Some(text) = paragraph.take().map(|p| clean_block(&p)) else {
+                        continue;
+                    };
+                    if text.is_empty() {
+                        continue;
+                    }
+                    if let Some(cell) = cell.as_mut() {
+                        if !cell.is_empty() {
+                            cell.push(' ');
+                        }
+                        cell.push_str(&text.replace('\n', " "));
+                    } else if let Some(shape) = shapes.last_mut() {
+                        shape.paragraphs.push(text);
+                    }
+                }
+                b"tc" => row.extend(cell.take()),
+                b"tr" if row.iter().any(|cell| !cell.is_empty()) => {
+                    if let Some(shape) = shapes.last_mut() {
+                        shape.rows.push(row.join(" | "));
+                    }
+                }
+                b"sp" | b"graphicFrame" => {
+                    let Some(shape) = shapes.pop() else {
+                        continue;
+                    };
+                    match shape.placeholder.as_deref() {
+                        Some(kind) if CHROME_PLACEHOLDERS.contains(&kind) => {}
+                        Some("title" | "ctrTitle") if title.is_none() => {
+                            let text = shape.paragraphs.join(" ").replace('\n', " ");
+                            title = Some(text).filter(|t| !t.is_empty());
+                        }
+                        _ => {
+                            let text = if shape.rows.is_empty() {
+                                shape.paragraphs.join("\n")
+                            } else {
+                                shape.rows.join("\n")
+                            };
+                            if !text.is_empty() {
+                                blocks.push(text);
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok((title, blocks))
+}
+
+/// Record the placeholder type of the current shape; untyped placeholders hold body text
+fn set_placeholder(shapes: &mut [Shape], kind: Option<String>) {
+    if let Some(shape) = shapes.last_mut() {
+        shape.placeholder = Some(kind.unwrap_or_else(|| "body".to_string()));
+    }
+}
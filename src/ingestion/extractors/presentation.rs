@@ -0,0 +1,56 @@
+use crate::ingestion::Section;
+
+/// Text of one slide of a deck
+#[derive(Default)]
+pub struct Slide {
+    /// 1-based position in the presentation's slide list, counting slides whose
+    /// content could not be read
+    pub number: usize,
+    pub title: Option<String>,
+    /// Text blocks of the slide's other shapes, in document order
+    pub body: Vec<String>,
+    /// Paragraphs of the speaker notes
+    pub notes: Vec<String>,
+}
+
+/// Render slides in order as sections tagged with `slide_number` and `slide_title`.
+///
+/// The title becomes a `#` heading above the body. Speaker notes get a section of
+/// their own, tagged `speaker_notes`, so they can be filtered separately.
+pub fn slide_sections(slides: &[Slide]) -> Vec<Section> {
+    let mut sections = Vec::new();
+
+    for slide in slides {
+        let tag = |section: Section| {
+            let section = section.with("slide_number", slide.number);
+            match &slide.title {
+                Some(title) => section.with("slide_title", title.as_str()),
+                None => section,
+            }
+        };
+
+        let mut blocks: Vec<String> = Vec::new();
+        if let Some(title) = &slide.title {
+            blocks.push(format!("# {}", title));
+        }
+        blocks.extend(slide.body.iter().cloned());
+        if !blocks.is_empty() {
+            sections.push(tag(Section::new(blocks.join("\n\n"))));
+        }
+
+        if !slide.notes.is_empty() {
+            sections.push(tag(Section::new(slide.notes.join("\n"))).with("speaker_notes", true));
+        }
+    }
+
+    sections
+}
+
+/// Collapse runs of whitespace within each line and drop empty lines
+pub fn clean_block(text: &str) -> String {
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
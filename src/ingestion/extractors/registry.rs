@@ -0,0 +1,171 @@
+use super::{
+    normalize_mime_type, sniff_mime_type, ArchiveExtractor, CodeExtractor, CsvExtractor,
+    DocExtractor, DocxExtractor, EmlExtractor, EpubExtractor, ExternalCommandConfig,
+    ExternalCommandExtractor, HtmlExtractor, ImageExtractor, JsonExtractor, MarkdownExtractor,
+    MboxExtractor, NotebookExtractor, OdpExtractor, OdsExtractor, OdtExtractor, PdfExtractor,
+    PptxExtractor, RtfExtractor, TextExtractor, XlsxExtractor, TEXT_MIME,
+};
+use crate::ingestion::Extractor;
+use anyhow::Result;
+use serde::Deserialize;
+use std::sync::{Arc, OnceLock};
+
+/// Priority of the built-in extractors; register above it to take formats over
+pub const BUILTIN_PRIORITY: i32 = 0;
+
+/// Extractors configured under `settings.extractors` in the app's YAML config
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ExtractorSettings {
+    /// Command-line tools to extract formats with
+    #[serde(default)]
+    pub external: Vec<ExternalCommandConfig>,
+}
+
+struct Registered {
+    name: String,
+    priority: i32,
+    extractor: Arc<dyn Extractor>,
+}
+
+/// The extractors available to the pipeline, in priority order.
+///
+/// Built-ins are registered at [`BUILTIN_PRIORITY`]. An extractor registered with a
+/// higher priority wins over every lower one that claims the same file, whichever way
+/// it claims it; among equal priorities the usual detection order decides (see
+/// [`ExtractorRegistry::get`]), then registration order.
+#[derive(Default)]
+pub struct ExtractorRegistry {
+    extractors: Vec<Registered>,
+}
+
+impl ExtractorRegistry {
+    /// An empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry of the built-in extractors
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        let builtins: Vec<(&str, Arc<dyn Extractor>)> = vec![
+            ("pdf", Arc::new(PdfExtractor)),
+            ("markdown", Arc::new(MarkdownExtractor)),
+            ("text", Arc::new(TextExtractor)),
+            ("epub", Arc::new(EpubExtractor)),
+            ("docx", Arc::new(DocxExtractor)),
+            ("doc", Arc::new(DocExtractor)),
+            ("odt", Arc::new(OdtExtractor)),
+            ("rtf", Arc::new(RtfExtractor)),
+            ("html", Arc::new(HtmlExtractor)),
+            ("image", Arc::new(ImageExtractor)),
+            ("csv", Arc::new(CsvExtractor)),
+            ("xlsx", Arc::new(XlsxExtractor)),
+            ("ods", Arc::new(OdsExtractor)),
+            ("pptx", Arc::new(PptxExtractor)),
+            ("odp", Arc::new(OdpExtractor)),
+            ("eml", Arc::new(EmlExtractor)),
+            ("mbox", Arc::new(MboxExtractor)),
+            ("archive", Arc::new(ArchiveExtractor)),
+            ("code", Arc::new(CodeExtractor)),
+            ("notebook", Arc::new(NotebookExtractor)),
+            ("json", Arc::new(JsonExtractor)),
+        ];
+        for (name, extractor) in builtins {
+            registry.register_arc(name, BUILTIN_PRIORITY, extractor);
+        }
+        registry
+    }
+
+    /// The built-ins plus the external commands of `settings`
+    pub fn from_settings(settings: &ExtractorSettings) -> Result<Self> {
+        let mut registry = Self::with_builtins();
+        for config in &settings.external {
+            let extractor = ExternalCommandExtractor::new(config.clone())?;
+            registry.register(&config.name, config.priority, extractor);
+        }
+        Ok(registry)
+    }
+
+    /// The shared registry of built-in extractors, used when the app context has none
+    pub fn builtin() -> Arc<Self> {
+        static BUILTIN: OnceLock<Arc<ExtractorRegistry>> = OnceLock::new();
+        BUILTIN
+            .get_or_init(|| Arc::new(Self::with_builtins()))
+            .clone()
+    }
+
+    /// Add an extractor under `name`
+    pub fn register(&mut self, name: &str, priority: i32, extractor: impl Extractor + 'static) {
+        self.register_arc(name, priority, Arc::new(extractor));
+    }
+
+    /// Add an extractor that is already shared under `name`
+    pub fn register_arc(&mut self, name: &str, priority: i32, extractor: Arc<dyn Extractor>) {
+        self.extractors.push(Registered {
+            name: name.to_string(),
+            priority,
+            extractor,
+        });
+        // Stable, so equal priorities keep their registration order
+        self.extractors
+            .sort_by_key(|e| std::cmp::Reverse(e.priority));
+    }
+
+    /// Names of the registered extractors, highest priority first
+    pub fn names(&self) -> Vec<&str> {
+        self.extractors.iter().map(|e| e.name.as_str()).collect()
+    }
+
+    /// Pick the extractor for a file.
+    ///
+    /// The format is detected from the file's content first, so misnamed files and
+    /// files without an extension still reach the right extractor. When sniffing is
+    /// inconclusive (plain text, or a format nothing here reads) the extension decides,
+    /// then the `Content-Type` the file was uploaded with. A higher priority extractor
+    /// matching at any of these steps wins over lower priority ones.
+    pub fn get(&self, file_path: &str, content_type: Option<&str>) -> Option<Arc<dyn Extractor>> {
+        self.find(file_path, content_type)
+            .map(|registered| registered.extractor.clone())
+    }
+
+    /// Name of the extractor [`Self::get`] picks
+    pub fn get_name(&self, file_path: &str, content_type: Option<&str>) -> Option<&str> {
+        self.find(file_path, content_type)
+            .map(|registered| registered.name.as_str())
+    }
+
+    fn find(&self, file_path: &str, content_type: Option<&str>) -> Option<&Registered> {
+        let sniffed = sniff_mime_type(file_path);
+        let declared = content_type.and_then(normalize_mime_type);
+        let binary = sniffed
+            .as_deref()
+            .filter(|mime_type| *mime_type != TEXT_MIME);
+
+        // The earliest detection step each extractor matches at
+        let step = |extractor: &dyn Extractor| {
+            if binary.is_some_and(|mime_type| extractor.supports_mime_type(mime_type)) {
+                Some(0)
+            } else if extractor.supports(file_path) {
+                Some(1)
+            } else if declared
+                .as_deref()
+                .is_some_and(|mime_type| extractor.supports_mime_type(mime_type))
+            {
+                Some(2)
+            } else if sniffed
+                .as_deref()
+                .is_some_and(|mime_type| extractor.supports_mime_type(mime_type))
+            {
+                Some(3)
+            } else {
+                None
+            }
+        };
+
+        self.extractors
+            .iter()
+            .filter_map(|registered| Some((registered, step(registered.extractor.as_ref())?)))
+            .min_by_key(|(registered, step)| (std::cmp::Reverse(registered.priority), *step))
+            .map(|(registered, _)| registered)
+    }
+}
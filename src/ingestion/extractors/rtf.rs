@@ -0,0 +1,526 @@
+use crate::ingestion::{ExtractOptions, ExtractedDocument, Extractor, Section};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use encoding_rs::Encoding;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Destinations that hold formatting tables, pictures, embedded objects or page
+/// chrome rather than document text
+const SKIPPED_DESTINATIONS: &[&str] = &[
+    "colortbl",
+    "stylesheet",
+    "listtable",
+    "listoverridetable",
+    "revtbl",
+    "rsidtbl",
+    "latentstyles",
+    "pict",
+    "object",
+    "fldinst",
+    "header",
+    "headerl",
+    "headerr",
+    "headerf",
+    "footer",
+    "footerl",
+    "footerr",
+    "footerf",
+    "nonshppict",
+];
+
+/// RTF Extractor (Rich Text Format)
+pub struct RtfExtractor;
+
+#[async_trait]
+impl Extractor for RtfExtractor {
+    /// The text as one section, with title and author from the `\info` group
+    async fn extract(
+        &self,
+        file_path: &str,
+        _options: &ExtractOptions,
+    ) -> Result<ExtractedDocument> {
+        let path = file_path.to_string();
+        tokio::task::spawn_blocking(move || {
+            let document = read_rtf(&path)?;
+            Ok(ExtractedDocument {
+                metadata: document.metadata,
+                sections: vec![Section::new(document.text)],
+            })
+        })
+        .await
+        .context("RTF extraction task panicked")?
+    }
+
+    fn supports(&self, file_path: &str) -> bool {
+        file_path.to_lowercase().ends_with(".rtf")
+    }
+
+    fn supports_mime_type(&self, mime_type: &str) -> bool {
+        matches!(mime_type, "application/rtf" | "text/rtf")
+    }
+}
+
+/// Text and `\info` metadata of an RTF document
+pub struct RtfDocument {
+    pub text: String,
+    pub metadata: Map<String, Value>,
+}
+
+fn read_rtf(file_path: &str) -> Result<RtfDocument> {
+    let bytes = std::fs::read(file_path).context("Failed to read RTF file")?;
+    RtfDocument::parse(&bytes)
+}
+
+impl RtfDocument {
+    /// Parse an RTF document.
+    ///
+    /// Paragraphs are separated by blank lines, table rows become `a | b` lines and
+    /// paragraphs with an outline level become `#` headings. Footnotes follow the
+    /// body. 8-bit text is decoded with the font's charset or the `\ansicpg` code
+    /// page; `\uN` escapes replace their fallback characters.
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        if !bytes.starts_with(b"{\\rtf") {
+            anyhow::bail!("File is not an RTF document");
+        }
+        let mut parser = Parser::default();
+        parser.run(bytes);
+        Ok(parser.finish())
+    }
+}
+
+/// Where the text of the current group goes
+#[derive(Clone, Copy, PartialEq)]
+enum Destination {
+    Body,
+    Footnote,
+    FontTable,
+    Info,
+    InfoField(&'static str),
+    Skip,
+}
+
+/// State scoped to a `{...}` group
+#[derive(Clone, Copy)]
+struct Group {
+    destination: Destination,
+    /// Number of fallback characters following a `\uN` escape
+    unicode_skip: usize,
+    encoding: &'static Encoding,
+}
+
+struct Parser {
+    groups: Vec<Group>,
+    codepage: &'static Encoding,
+    fonts: HashMap<i32, &'static Encoding>,
+    /// Font being defined in the font table
+    font: Option<i32>,
+    /// Undecoded 8-bit text of the current run
+    pending: Vec<u8>,
+    /// Fallback characters still to drop after a `\uN` escape
+    skip_chars: usize,
+    high_surrogate: Option<u16>,
+    paragraph: String,
+    in_table: bool,
+    outline_level: Option<usize>,
+    row: Vec<String>,
+    table: Vec<String>,
+    blocks: Vec<String>,
+    note: String,
+    notes: Vec<String>,
+    metadata: Map<String, Value>,
+    field: String,
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        let group = Group {
+            destination: Destination::Body,
+            unicode_skip: 1,
+            encoding: encoding_rs::WINDOWS_1252,
+        };
+        Self {
+            groups: vec![group],
+            codepage: encoding_rs::WINDOWS_1252,
+            fonts: HashMap::new(),
+            font: None,
+            pending: Vec::new(),
+            skip_chars: 0,
+            high_surrogate: None,
+            paragraph: String::new(),
+            in_table: false,
+            outline_level: None,
+            row: Vec::new(),
+            table: Vec::new(),
+            blocks: Vec::new(),
+            note: String::new(),
+            notes: Vec::new(),
+            metadata: Map::new(),
+            field: String::new(),
+        }
+    }
+}
+
+impl Parser {
+    fn group(&mut self) -> &mut Group {
+        self.groups
+            .last_mut()
+            .expect("the document group is never popped")
+    }
+
+    fn destination(&self) -> Destination {
+        self.groups
+            .last()
+            .map_or(Destination::Body, |g| g.destination)
+    }
+
+    fn run(&mut self, bytes: &[u8]) {
+        let mut pos = 0;
+        while pos < bytes.len() {
+            let byte = bytes[pos];
+            pos += 1;
+            match byte {
+                b'{' => {
+                    self.flush();
+                    self.skip_chars = 0;
+                    let group = *self.group();
+                    self.groups.push(group);
+                }
+                b'}' => {
+                    self.flush();
+                    self.skip_chars = 0;
+                    self.end_group();
+                }
+                b'\\' => pos = self.control(bytes, pos),
+                b'\r' | b'\n' => {}
+                b'\t' => self.text_char('\t'),
+                _ => self.text_byte(byte),
+            }
+        }
+        self.flush();
+    }
+
+    fn end_group(&mut self) {
+        if self.groups.len() == 1 {
+            return;
+        }
+        let group = self.groups.pop().expect("checked above");
+        match group.destination {
+            Destination::InfoField(key) if self.destination() != group.destination => {
+                let value = std::mem::take(&mut self.field).trim().to_string();
+                if !value.is_empty() {
+                    self.metadata.insert(key.to_string(), Value::String(value));
+                }
+            }
+            Destination::Footnote if self.destination() != Destination::Footnote => {
+                self.end_note();
+            }
+            Destination::FontTable => self.font = None,
+            _ => {}
+        }
+    }
+
+    /// Handle the control word or symbol starting after a backslash at `pos`,
+    /// returning the position after it
+    fn control(&mut self, bytes: &[u8], mut pos: usize) -> usize {
+        let Some(&first) = bytes.get(pos) else {
+            return pos;
+        };
+        // Hex escapes stay buffered so multi-byte code pages decode as a whole
+        if first != b'\'' {
+            self.flush();
+        }
+
+        if !first.is_ascii_alphabetic() {
+            pos += 1;
+            match first {
+                b'\'' => {
+                    let byte = bytes
+                        .get(pos..pos + 2)
+                        .and_then(|hex| std::str::from_utf8(hex).ok())
+                        .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                    if let Some(byte) = byte {
+                        pos += 2;
+                        self.text_byte(byte);
+                    }
+                }
+                b'*' => self.group().destination = Destination::Skip,
+                b'\\' | b'{' | b'}' => self.text_char(first as char),
+                b'~' => self.text_char('\u{a0}'),
+                b'_' => self.text_char('-'),
+                b'\r' | b'\n' => self.end_paragraph(),
+                _ => {}
+            }
+            return pos;
+        }
+
+        let start = pos;
+        while bytes.get(pos).is_some_and(u8::is_ascii_alphabetic) {
+            pos += 1;
+        }
+        let word = std::str::from_utf8(&bytes[start..pos]).unwrap_or_default();
+        let digits_start = pos;
+        if bytes.get(pos) == Some(&b'-') {
+            pos += 1;
+        }
+        while bytes.get(pos).is_some_and(u8::is_ascii_digit) {
+            pos += 1;
+        }
+        let param: Option<i32> = std::str::from_utf8(&bytes[digits_start..pos])
+            .ok()
+            .and_then(|digits| digits.parse().ok());
+        // A single space delimits the control word and is not text
+        if bytes.get(pos) == Some(&b' ') {
+            pos += 1;
+        }
+
+        if word == "bin" {
+            let length = param.unwrap_or(0).max(0) as usize;
+            return (pos + length).min(bytes.len());
+        }
+        if word != "u" {
+            self.skip_chars = 0;
+        }
+        self.control_word(word, param);
+        pos
+    }
+
+    fn control_word(&mut self, word: &str, param: Option<i32>) {
+        let destination = self.destination();
+        match word {
+            "par" | "sect" | "page" => self.end_paragraph(),
+            "line" => self.text_char('\n'),
+            "tab" => self.text_char('\t'),
+            "emdash" => self.text_char('\u{2014}'),
+            "endash" => self.text_char('\u{2013}'),
+            "bullet" => self.text_char('\u{2022}'),
+            "lquote" => self.text_char('\u{2018}'),
+            "rquote" => self.text_char('\u{2019}'),
+            "ldblquote" => self.text_char('\u{201c}'),
+            "rdblquote" => self.text_char('\u{201d}'),
+            "emspace" | "enspace" | "qmspace" => self.text_char(' '),
+            "u" => {
+                if let Some(code) = param {
+                    // Values above 32767 are written as negative numbers
+                    let code = if code < 0 { code + 65536 } else { code };
+                    self.unicode(code as u16);
+                    self.skip_chars = self.group().unicode_skip;
+                }
+            }
+            "uc" => self.group().unicode_skip = param.unwrap_or(1).max(0) as usize,
+            "ansicpg" => {
+                self.codepage = param.map_or(encoding_rs::WINDOWS_1252, codepage_encoding);
+                self.group().encoding = self.codepage;
+            }
+            "mac" => {
+                self.codepage = encoding_rs::MACINTOSH;
+                self.group().encoding = self.codepage;
+            }
+            "f" => match destination {
+                Destination::FontTable => self.font = param,
+                _ => {
+                    let encoding = param
+                        .and_then(|font| self.fonts.get(&font).copied())
+                        .unwrap_or(self.codepage);
+                    self.group().encoding = encoding;
+                }
+            },
+            "fcharset" if destination == Destination::FontTable => {
+                if let (Some(font), Some(encoding)) = (self.font, param.and_then(charset_encoding))
+                {
+                    self.fonts.insert(font, encoding);
+                }
+            }
+            "pard" => {
+                self.in_table = false;
+                self.outline_level = None;
+            }
+            "intbl" => self.in_table = true,
+            "outlinelevel" => self.outline_level = param.map(|level| level.clamp(0, 5) as usize),
+            "cell" | "nestcell" => {
+                let cell = clean_text(&std::mem::take(&mut self.paragraph));
+                self.row.push(cell.replace('\n', " "));
+            }
+            "row" | "nestrow" => {
+                let row = std::mem::take(&mut self.row);
+                if row.iter().any(|cell| !cell.is_empty()) {
+                    self.table.push(row.join(" | "));
+                }
+            }
+            "fonttbl" => self.group().destination = Destination::FontTable,
+            "info" => self.group().destination = Destination::Info,
+            "title" if destination == Destination::Info => {
+                self.field.clear();
+                self.group().destination = Destination::InfoField("title");
+            }
+            "author" if destination == Destination::Info => {
+                self.field.clear();
+                self.group().destination = Destination::InfoField("author");
+            }
+            "footnote" => {
+                self.group().destination = Destination::Footnote;
+            }
+            _ if SKIPPED_DESTINATIONS.contains(&word) => {
+                self.group().destination = Destination::Skip;
+            }
+            _ => {}
+        }
+    }
+
+    fn text_byte(&mut self, byte: u8) {
+        if self.skip_chars > 0 {
+            self.skip_chars -= 1;
+            return;
+        }
+        if self.is_text_destination() {
+            self.pending.push(byte);
+        }
+    }
+
+    fn text_char(&mut self, c: char) {
+        if self.skip_chars > 0 {
+            self.skip_chars -= 1;
+            return;
+        }
+        self.flush();
+        self.push_str(c.encode_utf8(&mut [0; 4]));
+    }
+
+    fn unicode(&mut self, unit: u16) {
+        let decoded = match (self.high_surrogate.take(), unit) {
+            (_, 0xD800..=0xDBFF) => {
+                self.high_surrogate = Some(unit);
+                return;
+            }
+            (Some(high), 0xDC00..=0xDFFF) => char::decode_utf16([high, unit]).next(),
+            (_, unit) => char::decode_utf16([unit]).next(),
+        };
+        let c = decoded.and_then(|c| c.ok()).unwrap_or('\u{fffd}');
+        self.push_str(c.encode_utf8(&mut [0; 4]));
+    }
+
+    fn is_text_destination(&self) -> bool {
+        matches!(
+            self.destination(),
+            Destination::Body | Destination::Footnote | Destination::InfoField(_)
+        )
+    }
+
+    /// Decode buffered 8-bit text with the current group's encoding
+    fn flush(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let bytes = std::mem::take(&mut self.pending);
+        let encoding = self.group().encoding;
+        let (text, _) = encoding.decode_without_bom_handling(&bytes);
+        self.push_str(&text);
+    }
+
+    fn push_str(&mut self, text: &str) {
+        match self.destination() {
+            Destination::Body => self.paragraph.push_str(text),
+            Destination::Footnote => self.note.push_str(text),
+            Destination::InfoField(_) => self.field.push_str(text),
+            _ => {}
+        }
+    }
+
+    fn end_paragraph(&mut self) {
+        match self.destination() {
+            Destination::Body if self.in_table => self.paragraph.push('\n'),
+            Destination::Body => {
+                self.end_table();
+                let text = clean_text(&std::mem::take(&mut self.paragraph));
+                if text.is_empty() {
+                    return;
+                }
+                match self.outline_level {
+                    Some(level) => self
+                        .blocks
+                        .push(format!("{} {}", "#".repeat(level + 1), text)),
+                    None => self.blocks.push(text),
+                }
+            }
+            Destination::Footnote => self.end_note(),
+            Destination::InfoField(_) => self.field.push(' '),
+            _ => {}
+        }
+    }
+
+    fn end_note(&mut self) {
+        let text = clean_text(&std::mem::take(&mut self.note));
+        if !text.is_empty() {
+            self.notes.push(text);
+        }
+    }
+
+    fn end_table(&mut self) {
+        if !self.table.is_empty() {
+            self.blocks.push(std::mem::take(&mut self.table).join("\n"));
+        }
+    }
+
+    fn finish(mut self) -> RtfDocument {
+        self.groups.truncate(1);
+        self.in_table = false;
+        self.end_paragraph();
+        self.end_table();
+        let mut blocks = self.blocks;
+        blocks.extend(self.notes);
+        RtfDocument {
+            text: blocks.join("\n\n"),
+            metadata: self.metadata,
+        }
+    }
+}
+
+/// Trim each line and drop empty ones
+fn clean_text(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Map a Windows code page number (`\ansicpgN`) to an encoding
+fn codepage_encoding(codepage: i32) -> &'static Encoding {
+    match codepage {
+        437 | 850 | 1252 => encoding_rs::WINDOWS_1252,
+        866 => encoding_rs::IBM866,
+        874 => encoding_rs::WINDOWS_874,
+        932 => encoding_rs::SHIFT_JIS,
+        936 => encoding_rs::GBK,
+        949 => encoding_rs::EUC_KR,
+        950 => encoding_rs::BIG5,
+        1250..=1258 => Encoding::for_label(format!("windows-{codepage}").as_bytes())
+            .unwrap_or(encoding_rs::WINDOWS_1252),
+        10000 => encoding_rs::MACINTOSH,
+        65001 => encoding_rs::UTF_8,
+        _ => encoding_rs::WINDOWS_1252,
+    }
+}
+
+/// Map a font's `\fcharsetN` to an encoding; ANSI, default and symbol charsets
+/// use the document code page
+fn charset_encoding(charset: i32) -> Option<&'static Encoding> {
+    let codepage = match charset {
+        77 => 10000,
+        128 => 932,
+        129 => 949,
+        134 => 936,
+        136 => 950,
+        161 => 1253,
+        162 => 1254,
+        163 => 1258,
+        177 => 1255,
+        178 => 1256,
+        186 => 1257,
+        204 => 1251,
+        222 => 874,
+        238 => 1250,
+        _ => return None,
+    };
+    Some(codepage_encoding(codepage))
+}
@@ -0,0 +1,165 @@
+use super::read_zip_entry;
+use super::text::detect_encoding;
+use std::fs::File;
+use std::io::Read;
+
+/// Bytes read from the start of a file to recognise its format
+const SNIFF_LEN: usize = 8192;
+
+/// MIME type reported for text files; too generic to override a file's extension
+pub const TEXT_MIME: &str = "text/plain";
+
+/// Signatures found at the very start of a file
+const MAGIC_NUMBERS: &[(&[u8], &str)] = &[
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xFF\xD8\xFF", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"II*\x00", "image/tiff"),
+    (b"MM\x00*", "image/tiff"),
+    (b"{\\rtf", "application/rtf"),
+    (b"\x1F\x8B", "application/gzip"),
+];
+
+/// Sizes of the DIB headers following a BMP file header, from `BITMAPCOREHEADER` (12)
+/// to `BITMAPV5HEADER` (124)
+const BMP_DIB_HEADER_SIZES: &[u32] = &[12, 40, 52, 56, 64, 108, 124];
+
+/// Zip parts that identify an Office Open XML package
+const OOXML_PARTS: &[(&str, &str)] = &[
+    (
+        "word/document.xml",
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    ),
+    (
+        "xl/workbook.xml",
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    ),
+    (
+        "ppt/presentation.xml",
+        "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+    ),
+];
+
+/// Detect the MIME type of a file from its content.
+///
+/// Binary formats are recognised by their magic number, zip packages (DOCX, EPUB,
+/// OpenDocument, ...) by the parts they contain and Compound Files by their streams.
+/// Anything else that decodes as text is reported as [`TEXT_MIME`], and `None` is
+/// returned when the file cannot be read or is unrecognised binary data.
+pub fn sniff_mime_type(file_path: &str) -> Option<String> {
+    let file = File::open(file_path).ok()?;
+    let file_len = file.metadata().ok()?.len();
+    let mut head = Vec::with_capacity(SNIFF_LEN);
+    file.take(SNIFF_LEN as u64).read_to_end(&mut head).ok()?;
+
+    if let Some(&(_, mime)) = MAGIC_NUMBERS
+        .iter()
+        .find(|(magic, _)| head.starts_with(magic))
+    {
+        return Some(mime.to_string());
+    }
+    if head.len() >= 12 && head.starts_with(b"RIFF") && &head[8..12] == b"WEBP" {
+        return Some("image/webp".to_string());
+    }
+    if is_bmp(&head, file_len) {
+        return Some("image/bmp".to_string());
+    }
+    // PDF readers accept junk before the header, so search the first kilobyte
+    if head[..head.len().min(1024)]
+        .windows(5)
+        .any(|window| window == b"%PDF-")
+    {
+        return Some("application/pdf".to_string());
+    }
+    if head.starts_with(b"PK\x03\x04") {
+        return Some(sniff_zip(file_path));
+    }
+    if head.starts_with(b"\xD0\xCF\x11\xE0\xA1\xB1\x1A\xE1") {
+        return Some(sniff_compound_file(file_path));
+    }
+    // tar has no leading magic; POSIX and GNU headers carry "ustar" at offset 257
+    if head.len() >= 262 && &head[257..262] == b"ustar" {
+        return Some("application/x-tar".to_string());
+    }
+
+    sniff_text(&head)
+}
+
+/// Whether `head` starts a BMP file: "BM" alone also starts plenty of text, so the
+/// file size recorded in the header and the size of the DIB header must check out
+fn is_bmp(head: &[u8], file_len: u64) -> bool {
+    if head.len() < 18 || !head.starts_with(b"BM") {
+        return false;
+    }
+    let declared_len = u32::from_le_bytes([head[2], head[3], head[4], head[5]]);
+    let dib_header_size = u32::from_le_bytes([head[14], head[15], head[16], head[17]]);
+    u64::from(declared_len) == file_len && BMP_DIB_HEADER_SIZES.contains(&dib_header_size)
+}
+
+/// The `mimetype` entry of EPUB and OpenDocument packages, else the OOXML main part
+fn sniff_zip(file_path: &str) -> String {
+    let archive = File::open(file_path)
+        .ok()
+        .and_then(|file| zip::ZipArchive::new(file).ok());
+    let Some(mut archive) = archive else {
+        return "application/zip".to_string();
+    };
+
+    if let Ok(Some(mimetype)) = read_zip_entry(&mut archive, "mimetype") {
+        let mimetype = mimetype.trim();
+        if !mimetype.is_empty() && !mimetype.contains(char::is_whitespace) {
+            return mimetype.to_lowercase();
+        }
+    }
+    if archive.index_for_name("META-INF/container.xml").is_some() {
+        return "application/epub+zip".to_string();
+    }
+
+    OOXML_PARTS
+        .iter()
+        .find(|(part, _)| archive.index_for_name(part).is_some())
+        .map_or("application/zip", |(_, mime)| mime)
+        .to_string()
+}
+
+fn sniff_compound_file(file_path: &str) -> String {
+    let is_word = cfb::open(file_path)
+        .map(|compound| compound.is_stream("/WordDocument"))
+        .unwrap_or(false);
+    if is_word {
+        "application/msword".to_string()
+    } else {
+        "application/x-cfb".to_string()
+    }
+}
+
+/// HTML by its opening tag, otherwise text when the bytes decode without control
+/// characters in any encoding the text extractor detects
+fn sniff_text(head: &[u8]) -> Option<String> {
+    // The read may have cut a multi-byte character in half
+    let head = match std::str::from_utf8(head) {
+        Err(e) if e.error_len().is_none() => &head[..e.valid_up_to()],
+        _ => head,
+    };
+
+    let (text, _) = detect_encoding(head).decode_with_bom_removal(head);
+    let is_binary = text
+        .chars()
+        .any(|c| c.is_ascii_control() && !matches!(c, '\t' | '\n' | '\r' | '\u{0C}' | '\u{1B}'));
+    if is_binary {
+        return None;
+    }
+
+    let start = text.trim_start().to_lowercase();
+    let is_html = start.starts_with("<!doctype html")
+        || start.starts_with("<html")
+        || (start.starts_with("<?xml") && start.contains("<html"));
+    Some(if is_html { "text/html" } else { TEXT_MIME }.to_string())
+}
+
+/// The lowercased type of a `Content-Type` header value, without its parameters
+pub fn normalize_mime_type(content_type: &str) -> Option<String> {
+    let essence = content_type.split(';').next()?.trim().to_lowercase();
+    (!essence.is_empty()).then_some(essence)
+}
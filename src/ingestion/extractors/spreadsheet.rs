@@ -0,0 +1,146 @@
+use crate::ingestion::Section;
+use serde_json::{Map, Value};
+
+/// Columns in a sheet: `XFD`, the last column of Excel and LibreOffice, is the
+/// 16384th. Cells beyond it are dropped rather than padded out.
+pub const MAX_COLUMNS: usize = 16_384;
+
+/// Rows rendered into one section, so every chunk covers a contiguous row range
+const ROWS_PER_SECTION: usize = 25;
+
+/// A worksheet as rows of cell text
+pub struct Sheet {
+    pub name: String,
+    /// Rows with their 1-based row number in the source file
+    pub rows: Vec<(usize, Vec<String>)>,
+}
+
+/// Render sheets as sections of `Header: value` lines, one line per row.
+///
+/// The first non-empty row of a sheet is its header; columns without a header are
+/// named after their letter. Sections are tagged with `sheet`, `row_start` and
+/// `row_end` and carry their rows as records keyed by header.
+pub fn sheet_sections(sheets: &[Sheet]) -> Vec<Section> {
+    let mut sections = Vec::new();
+
+    for sheet in sheets {
+        let mut rows = sheet
+            .rows
+            .iter()
+            .map(|(number, cells)| (*number, clean_row(cells)))
+            .filter(|(_, cells)| !cells.is_empty());
+        let Some((header_number, header)) = rows.next() else {
+            continue;
+        };
+        let data: Vec<(usize, Vec<String>)> = rows.collect();
+
+        if data.is_empty() {
+            sections.push(
+                Section::new(header.join(" | "))
+                    .with("sheet", sheet.name.as_str())
+                    .with("row_start", header_number)
+                    .with("row_end", header_number),
+            );
+            continue;
+        }
+
+        let width = data
+            .iter()
+            .map(|(_, cells)| cells.len())
+            .fold(header.len(), usize::max);
+        let headers = header_names(&header, width);
+
+        for group in data.chunks(ROWS_PER_SECTION) {
+            let mut lines = Vec::new();
+            let mut records = Vec::new();
+            for (_, cells) in group {
+                let mut pairs = Vec::new();
+                let mut record = Map::new();
+                for (name, value) in headers.iter().zip(cells) {
+                    if value.is_empty() {
+                        continue;
+                    }
+                    pairs.push(format!("{}: {}", name, value));
+                    record.insert(name.clone(), Value::String(value.clone()));
+                }
+                lines.push(pairs.join(" | "));
+                records.push(record);
+            }
+
+            let (first, last) = (group[0].0, group[group.len() - 1].0);
+            let mut section = Section::new(lines.join("\n"))
+                .with("sheet", sheet.name.as_str())
+                .with("row_start", first)
+                .with("row_end", last);
+            section.records = records;
+            sections.push(section);
+        }
+    }
+
+    sections
+}
+
+/// Trim cells onto one line and drop trailing empty cells; an empty row becomes empty
+fn clean_row(cells: &[String]) -> Vec<String> {
+    let mut cleaned: Vec<String> = cells
+        .iter()
+        .map(|cell| cell.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect();
+    while cleaned.last().is_some_and(String::is_empty) {
+        cleaned.pop();
+    }
+    cleaned
+}
+
+/// Unique column names, falling back to `Column B` for cells without a header
+fn header_names(header: &[String], width: usize) -> Vec<String> {
+    let mut names: Vec<String> = Vec::with_capacity(width);
+    for idx in 0..width {
+        let base = header
+            .get(idx)
+            .filter(|name| !name.is_empty())
+            .cloned()
+            .unwrap_or_else(|| format!("Column {}", column_letters(idx)));
+
+        let mut name = base.clone();
+        let mut copy = 1;
+        while names.contains(&name) {
+            copy += 1;
+            name = format!("{} ({})", base, copy);
+        }
+        names.push(name);
+    }
+    names
+}
+
+/// Spreadsheet column letters for a 0-based index: 0 is `A`, 26 is `AA`
+pub fn column_letters(mut idx: usize) -> String {
+    let mut letters = Vec::new();
+    loop {
+        letters.push(b'A' + (idx % 26) as u8);
+        if idx < 26 {
+            break;
+        }
+        idx = idx / 26 - 1;
+    }
+    letters.reverse();
+    String::from_utf8(letters).unwrap_or_default()
+}
+
+/// 0-based column index of a cell reference such as `AB12`
+pub fn column_index(reference: &str) -> Option<usize> {
+    let letters: Vec<u8> = reference
+        .bytes()
+        .take_while(u8::is_ascii_alphabetic)
+        .map(|b| b.to_ascii_uppercase())
+        .collect();
+    if letters.is_empty() {
+        return None;
+    }
+    letters
+        .iter()
+        .try_fold(0usize, |acc, b| {
+            acc.checked_mul(26)?.checked_add((b - b'A' + 1) as usize)
+        })
+        .map(|n| n - 1)
+}
@@ -0,0 +1,89 @@
+use super::sniff::TEXT_MIME;
+use crate::ingestion::{ExtractOptions, ExtractedDocument, Extractor, Section};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+
+/// Bytes inspected when guessing whether a file without a BOM is UTF-16
+const UTF16_SAMPLE_LEN: usize = 4096;
+
+/// Text Extractor
+pub struct TextExtractor;
+
+#[async_trait]
+impl Extractor for TextExtractor {
+    /// One section of the decoded text, with the `encoding` it was decoded from
+    async fn extract(
+        &self,
+        file_path: &str,
+        options: &ExtractOptions,
+    ) -> Result<ExtractedDocument> {
+        let bytes = tokio::fs::read(file_path)
+            .await
+            .context("Failed to read text file")?;
+        let (text, encoding) = decode_text(&bytes, options.encoding.as_deref())?;
+        Ok(ExtractedDocument::new(vec![Section::new(text)])
+            .with("encoding", encoding.name().to_lowercase()))
+    }
+
+    fn supports(&self, file_path: &str) -> bool {
+        file_path.to_lowercase().ends_with(".txt")
+    }
+
+    fn supports_mime_type(&self, mime_type: &str) -> bool {
+        mime_type == TEXT_MIME
+    }
+}
+
+/// Decode text to UTF-8, returning the encoding it was read as.
+///
+/// `declared` is an encoding label such as `latin1` or `shift_jis` and takes
+/// precedence. Otherwise a byte order mark decides, then the NUL pattern of BOM-less
+/// UTF-16, then valid UTF-8; anything else is read as Windows-1252, the superset
+/// of Latin-1 that legacy Windows tools wrote. Undecodable bytes become U+FFFD.
+pub fn decode_text(bytes: &[u8], declared: Option<&str>) -> Result<(String, &'static Encoding)> {
+    let encoding = match declared.map(str::trim).filter(|label| !label.is_empty()) {
+        Some(label) => Encoding::for_label(label.as_bytes())
+            .with_context(|| format!("Unknown text encoding: {}", label))?,
+        None => detect_encoding(bytes),
+    };
+
+    let (text, had_errors) = encoding.decode_with_bom_removal(bytes);
+    if had_errors {
+        tracing::warn!(
+            "Text is not valid {}; undecodable bytes were replaced",
+            encoding.name()
+        );
+    }
+    Ok((text.into_owned(), encoding))
+}
+
+pub(super) fn detect_encoding(bytes: &[u8]) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return encoding;
+    }
+    // ASCII-heavy UTF-16 has a NUL in every other byte: the high byte of each unit
+    let sample = &bytes[..bytes.len().min(UTF16_SAMPLE_LEN) & !1];
+    if !sample.is_empty() {
+        let units = sample.len() / 2;
+        let even_nuls = sample.iter().step_by(2).filter(|b| **b == 0).count();
+        let odd_nuls = sample
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .filter(|b| **b == 0)
+            .count();
+        if odd_nuls * 3 > units && even_nuls * 10 < units {
+            return UTF_16LE;
+        }
+        if even_nuls * 3 > units && odd_nuls * 10 < units {
+            return UTF_16BE;
+        }
+    }
+
+    if std::str::from_utf8(bytes).is_ok() {
+        UTF_8
+    } else {
+        WINDOWS_1252
+    }
+}
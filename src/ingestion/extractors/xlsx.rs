@@ -0,0 +1,328 @@
+use super::spreadsheet::{column_index, sheet_sections, Sheet, MAX_COLUMNS};
+use super::{read_relationships, read_zip_entry, resolve_xml_ref, xml_attr};
+use crate::ingestion::{ExtractOptions, ExtractedDocument, Extractor};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::collections::HashSet;
+use std::fs::File;
+use zip::ZipArchive;
+
+/// Built-in number formats that display dates and times
+const BUILTIN_DATE_FORMATS: &[u32] = &[14, 15, 16, 17, 18, 19, 20, 21, 22, 45, 46, 47];
+
+/// XLSX Extractor (Office Open XML workbooks)
+pub struct XlsxExtractor;
+
+#[async_trait]
+impl Extractor for XlsxExtractor {
+    /// Sections of up to 25 rows, tagged with their `sheet` and row range
+    async fn extract(
+        &self,
+        file_path: &str,
+        _options: &ExtractOptions,
+    ) -> Result<ExtractedDocument> {
+        let path = file_path.to_string();
+        tokio::task::spawn_blocking(move || {
+            Ok(ExtractedDocument::new(sheet_sections(&read_xlsx(&path)?)))
+        })
+        .await
+        .context("XLSX extraction task panicked")?
+    }
+
+    fn supports(&self, file_path: &str) -> bool {
+        let lower = file_path.to_lowercase();
+        lower.ends_with(".xlsx") || lower.ends_with(".xlsm")
+    }
+
+    fn supports_mime_type(&self, mime_type: &str) -> bool {
+        matches!(
+            mime_type,
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+                | "application/vnd.ms-excel.sheet.macroenabled.12"
+        )
+    }
+}
+
+/// Read every worksheet of a workbook, in tab order.
+///
+/// Cells show their cached values: shared and inline strings, booleans as
+/// `TRUE`/`FALSE`, and numbers formatted as dates rendered as ISO dates.
+pub fn read_xlsx(file_path: &str) -> Result<Vec<Sheet>> {
+    let file = File::open(file_path).context("Failed to open XLSX file")?;
+    let mut archive = ZipArchive::new(file).context("Failed to read XLSX package")?;
+
+    let workbook = read_zip_entry(&mut archive, "xl/workbook.xml")?
+        .context("XLSX package has no xl/workbook.xml")?;
+    let relationships = read_relationships(&mut archive, "xl/workbook.xml")?;
+    let shared_strings = match read_zip_entry(&mut archive, "xl/sharedStrings.xml")? {
+        Some(xml) => parse_shared_strings(&xml)?,
+        None => Vec::new(),
+    };
+    let date_styles = match read_zip_entry(&mut archive, "xl/styles.xml")? {
+        Some(xml) => parse_date_styles(&xml)?,
+        None => HashSet::new(),
+    };
+
+    let mut sheets = Vec::new();
+    for (name, relationship) in parse_sheet_list(&workbook)? {
+        let Some(part) = relationships
+            .iter()
+            .find(|r| r.id == relationship)
+            .map(|r| r.part.clone())
+        else {
+            continue;
+        };
+        let Some(xml) = read_zip_entry(&mut archive, &part)? else {
+            tracing::warn!("XLSX sheet {} is missing its part {}", name, part);
+            continue;
+        };
+        let rows = parse_sheet(&xml, &shared_strings, &date_styles)?;
+        sheets.push(Sheet { name, rows });
+    }
+
+    Ok(sheets)
+}
+
+/// Sheet names and relationship ids from `workbook.xml`
+fn parse_sheet_list(xml: &str) -> Result<Vec<(String, String)>> {
+    let mut reader = Reader::from_str(xml);
+    let mut sheets = Vec::new();
+    loop {
+        match reader.read_event().context("Malformed XLSX workbook")? {
+            Event::Start(ref e) | Event::Empty(ref e) if e.local_name().as_ref() == b"sheet" => {
+                if let (Some(name), Some(id)) = (xml_attr(e, "name"), xml_attr(e, "r:id")) {
+                    sheets.push((name, id));
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(sheets)
+}
+
+/// The shared string table; phonetic (`rPh`) runs are left out
+fn parse_shared_strings(xml: &str) -> Result<Vec<String>> {
+    let mut reader = Reader::from_str(xml);
+    let mut strings = Vec::new();
+    let mut current = String::new();
+    let mut in_text = false;
+    let mut in_phonetic = false;
+
+    loop {
+        match reader
+            .read_event()
+            .context("Malformed XLSX shared strings")?
+        {
+            Event::Start(ref e) => match e.local_name().as_ref() {
+                b"si" => current.clear(),
+                b"t" => in_text = true,
+                b"rPh" => in_phonetic = true,
+                _ => {}
+            },
+            Event::Empty(ref e) if e.local_name().as_ref() == b"si" => strings.push(String::new()),
+            Event::Text(ref e) if in_text && !in_phonetic => {
+                current.push_str(&e.xml10_content().context("Invalid text in XLSX")?);
+            }
+            Event::GeneralRef(ref e) if in_text && !in_phonetic => {
+                current.push_str(&resolve_xml_ref(e)?);
+            }
+            Event::End(ref e) => match e.local_name().as_ref() {
+                b"si" => strings.push(std::mem::take(&mut current)),
+                b"t" => in_text = false,
+                b"rPh" => in_phonetic = false,
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(strings)
+}
+
+/// Indexes of the cell formats (`cellXfs`) whose number format shows a date
+fn parse_date_styles(xml: &str) -> Result<HashSet<usize>> {
+    let mut reader = Reader::from_str(xml);
+    let mut custom_dates = HashSet::new();
+    let mut styles = HashSet::new();
+    let mut in_cell_formats = false;
+    let mut index = 0usize;
+
+    loop {
+        match reader.read_event().context("Malformed XLSX styles")? {
+            Event::Empty(ref e) if e.local_name().as_ref() == b"cellXfs" => {}
+            Event::Start(ref e) | Event::Empty(ref e) => match e.local_name().as_ref() {
+                b"numFmt" => {
+                    let id = xml_attr(e, "numFmtId").and_then(|id| id.parse::<u32>().ok());
+                    let code = xml_attr(e, "formatCode").unwrap_or_default();
+                    if let Some(id) = id.filter(|_| is_date_format(&code)) {
+                        custom_dates.insert(id);
+                    }
+                }
+                b"cellXfs" => in_cell_formats = true,
+                b"xf" if in_cell_formats => {
+                    let id = xml_attr(e, "numFmtId")
+                        .and_then(|id| id.parse::<u32>().ok())
+                        .unwrap_or(0);
+                    if BUILTIN_DATE_FORMATS.contains(&id) || custom_dates.contains(&id) {
+                        styles.insert(index);
+                    }
+                    index += 1;
+                }
+                _ => {}
+            },
+            Event::End(ref e) if e.local_name().as_ref() == b"cellXfs" => {
+                in_cell_formats = false;
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(styles)
+}
+
+/// Whether a custom number format displays a date or time, ignoring quoted literals,
+/// escaped characters and bracketed colors or conditions
+fn is_date_format(code: &str) -> bool {
+    let mut chars = code.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                chars.by_ref().find(|c| *c == '"');
+            }
+            '\\' | '_' | '*' => {
+                chars.next();
+            }
+            '[' => {
+                chars.by_ref().find(|c| *c == ']');
+            }
+            _ if matches!(c.to_ascii_lowercase(), 'd' | 'm' | 'y' | 'h' | 's') => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Rows of a worksheet part, placing each cell at the column of its reference
+fn parse_sheet(
+    xml: &str,
+    shared_strings: &[String],
+    date_styles: &HashSet<usize>,
+) -> Result<Vec<(usize, Vec<String>)>> {
+    let mut reader = Reader::from_str(xml);
+    let mut rows = Vec::new();
+    let mut row: Option<(usize, Vec<String>)> = None;
+    let mut last_row = 0usize;
+    // Type, style and column of the cell being read, and its value
+    let mut cell: Option<(String, Option<usize>, usize)> = None;
+    let mut value = String::new();
+    let mut in_value = false;
+
+    loop {
+        match reader.read_event().context("Malformed XLSX worksheet")? {
+            Event::Start(ref e) => match e.local_name().as_ref() {
+                b"row" => {
+                    let number = xml_attr(e, "r")
+                        .and_then(|r| r.parse().ok())
+                        .unwrap_or(last_row + 1);
+                    last_row = number;
+                    row = Some((number, Vec::new()));
+                }
+                b"c" => {
+                    let next_column = row.as_ref().map_or(0, |(_, cells)| cells.len());
+                    let column = xml_attr(e, "r")
+                        .and_then(|r| column_index(&r))
+                        .unwrap_or(next_column);
+                    let kind = xml_attr(e, "t").unwrap_or_default();
+                    let style = xml_attr(e, "s").and_then(|s| s.parse().ok());
+                    cell = Some((kind, style, column));
+                    value.clear();
+                }
+                // `v` holds the cached value, `t` the text of an inline string
+                b"v" | b"t" if cell.is_some() => in_value = true,
+                _ => {}
+            },
+            Event::Text(ref e) if in_value => {
+                value.push_str(&e.xml10_content().context("Invalid text in XLSX")?);
+            }
+            Event::GeneralRef(ref e) if in_value => value.push_str(&resolve_xml_ref(e)?),
+            Event::End(ref e) => match e.local_name().as_ref() {
+                b"v" | b"t" => in_value = false,
+                b"c" => {
+                    let (Some((kind, style, column)), Some((_, cells))) = (cell.take(), &mut row)
+                    else {
+                        continue;
+                    };
+                    let is_date = style.is_some_and(|s| date_styles.contains(&s));
+                    let text = cell_text(&kind, &value, is_date, shared_strings);
+                    if text.is_empty() {
+                        continue;
+                    }
+                    if column >= MAX_COLUMNS {
+                        tracing::debug!("Skipping XLSX cell beyond the last column");
+                        continue;
+                    }
+                    if cells.len() <= column {
+                        cells.resize(column + 1, String::new());
+                    }
+                    cells[column] = text;
+                }
+                b"row" => {
+                    if let Some(row) = row.take().filter(|(_, cells)| !cells.is_empty()) {
+                        rows.push(row);
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(rows)
+}
+
+fn cell_text(kind: &str, value: &str, is_date: bool, shared_strings: &[String]) -> String {
+    match kind {
+        "s" => value
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .and_then(|idx| shared_strings.get(idx))
+            .cloned()
+            .unwrap_or_default(),
+        "b" => match value.trim() {
+            "1" => "TRUE".to_string(),
+            "0" => "FALSE".to_string(),
+            other => other.to_string(),
+        },
+        "inlineStr" | "str" | "e" => value.to_string(),
+        _ if is_date => value
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .and_then(serial_to_datetime)
+            .unwrap_or_else(|| value.to_string()),
+        _ => value.trim().to_string(),
+    }
+}
+
+/// Format an Excel serial date (days since 1899-12-30) as an ISO date or date-time
+fn serial_to_datetime(serial: f64) -> Option<String> {
+    if !(0.0..2_958_466.0).contains(&serial) {
+        return None;
+    }
+    let days = serial.trunc();
+    let seconds = ((serial - days) * 86_400.0).round() as i64;
+    let epoch = chrono::NaiveDate::from_ymd_opt(1899, 12, 30)?.and_hms_opt(0, 0, 0)?;
+    let datetime = epoch + chrono::Duration::days(days as i64) + chrono::Duration::seconds(seconds);
+
+    Some(if seconds == 0 {
+        datetime.format("%Y-%m-%d").to_string()
+    } else if days == 0.0 {
+        datetime.format("%H:%M:%S").to_string()
+    } else {
+        datetime.format("%Y-%m-%d %H:%M:%S").to_string()
+    })
+}
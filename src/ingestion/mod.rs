@@ -1,27 +1,237 @@
+pub mod chunking;
+pub mod embeddings;
 pub mod extractors;
 pub mod stores;
 pub mod pipeline;
 
 use async_trait::async_trait;
-use anyhow::Result;
+use anyhow::{Context, Result};
+
+/// A span of extracted text with metadata describing where it came from (e.g. its page)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Section {
+    pub text: String,
+    pub metadata: serde_json::Map<String, serde_json::Value>,
+    /// Structured rows (e.g. of a spreadsheet) that graph stores keep as individual records
+    pub records: Vec<serde_json::Map<String, serde_json::Value>>,
+    /// Embedding of the text, once the pipeline has computed it
+    pub embedding: Option<Vec<f32>>,
+}
+
+impl Section {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn with(mut self, key: &str, value: impl Into<serde_json::Value>) -> Self {
+        self.metadata.insert(key.to_string(), value.into());
+        self
+    }
+}
+
+/// Everything extracted from a file: its document-level metadata (title, author,
+/// language, ...) and its text as ordered sections carrying their own metadata
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExtractedDocument {
+    pub metadata: serde_json::Map<String, serde_json::Value>,
+    pub sections: Vec<Section>,
+}
+
+impl ExtractedDocument {
+    pub fn new(sections: Vec<Section>) -> Self {
+        Self {
+            sections,
+            ..Self::default()
+        }
+    }
+
+    pub fn with(mut self, key: &str, value: impl Into<serde_json::Value>) -> Self {
+        self.metadata.insert(key.to_string(), value.into());
+        self
+    }
+
+    /// The whole text, sections separated by blank lines
+    pub fn text(&self) -> String {
+        join_sections(&self.sections)
+    }
+
+    /// Whether there is no text to ingest (e.g. an archive, whose entries are ingested
+    /// as documents of their own)
+    pub fn is_empty(&self) -> bool {
+        self.sections.iter().all(|s| s.text.trim().is_empty())
+    }
+
+    /// Document-level metadata as a JSON object, or `None` when there is none
+    pub fn metadata_value(&self) -> Option<serde_json::Value> {
+        if self.metadata.is_empty() {
+            None
+        } else {
+            Some(serde_json::Value::Object(self.metadata.clone()))
+        }
+    }
+}
+
+/// A file embedded in another one (an email attachment, an archive entry), unpacked to
+/// disk to be ingested as a child document
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Attachment {
+    /// Name of the file inside its container
+    pub filename: String,
+    /// Where the file was written
+    pub path: std::path::PathBuf,
+    /// MIME type the container declares for the file, if any
+    pub content_type: Option<String>,
+    /// Where the file came from (e.g. the email it was attached to), stored on the child document
+    pub metadata: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Join section texts into a single blob, keeping paragraph breaks between them
+pub fn join_sections(sections: &[Section]) -> String {
+    sections
+        .iter()
+        .map(|s| s.text.as_str())
+        .filter(|text| !text.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Per-request options that tune how files are extracted and chunked
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct ExtractOptions {
+    /// Tesseract languages to OCR with, e.g. `["eng", "deu"]`
+    #[serde(default)]
+    pub ocr_languages: Vec<String>,
+    /// Leave fenced and indented code blocks out of Markdown documents
+    #[serde(default)]
+    pub drop_code_blocks: bool,
+    /// Keep the text outputs of Jupyter notebook code cells
+    #[serde(default)]
+    pub include_outputs: bool,
+    /// Field holding the text of JSON/JSONL records, e.g. `body` or `content.text`
+    #[serde(default)]
+    pub text_field: Option<String>,
+    /// Fields of JSON/JSONL records stored as chunk metadata
+    #[serde(default)]
+    pub metadata_fields: Vec<String>,
+    /// Field holding the stable id of JSON/JSONL records, stored as `external_id`
+    #[serde(default)]
+    pub id_field: Option<String>,
+    /// Source the `id_field` values are unique within, stored as `id_namespace`;
+    /// defaults to the file name and `id_field`, e.g. `tickets.jsonl:id`
+    #[serde(default)]
+    pub id_namespace: Option<String>,
+    /// Encoding label of text files, e.g. `latin1`; detected when not given
+    #[serde(default)]
+    pub encoding: Option<String>,
+    /// `Content-Type` the file was uploaded with, used when its content is inconclusive
+    #[serde(default)]
+    pub content_type: Option<String>,
+    /// Chunking strategy, instead of the configured one
+    #[serde(default)]
+    pub chunk_strategy: Option<chunking::ChunkStrategy>,
+    /// Largest chunk, instead of the configured size
+    #[serde(default)]
+    pub chunk_size: Option<usize>,
+    /// Overlap between consecutive chunks, instead of the configured one
+    #[serde(default)]
+    pub chunk_overlap: Option<usize>,
+    /// Unit of `chunk_size` and `chunk_overlap`, instead of the configured one
+    #[serde(default)]
+    pub chunk_unit: Option<chunking::ChunkUnit>,
+}
+
+impl ExtractOptions {
+    /// Parse a language list such as `eng+deu` or `eng,deu`, dropping anything that is
+    /// not a plain Tesseract language code
+    pub fn parse_languages(value: &str) -> Vec<String> {
+        value
+            .split(['+', ',', ' '])
+            .map(str::trim)
+            .filter(|lang| !lang.is_empty())
+            .filter(|lang| lang.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Parse a comma-separated list of field names such as `author, tags.0`
+    pub fn parse_fields(value: &str) -> Vec<String> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|field| !field.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Parse a boolean form field such as `true`, `1`, `yes` or `on`
+    pub fn parse_flag(value: &str) -> bool {
+        matches!(
+            value.trim().to_lowercase().as_str(),
+            "true" | "1" | "yes" | "on"
+        )
+    }
+}
 
 /// Trait for extracting text from different file formats
 #[async_trait]
 pub trait Extractor: Send + Sync {
-    async fn extract(&self, file_path: &str) -> Result<String>;
+    /// Extract a file's text as ordered sections, each with its own metadata (page,
+    /// heading, ...), along with document-level metadata (title, author, ...)
+    async fn extract(&self, file_path: &str, options: &ExtractOptions) -> Result<ExtractedDocument>;
+
+    /// Write the files embedded in this one (email attachments, archive entries) into `dir`
+    /// so they can be ingested as child documents
+    async fn extract_attachments(&self, _file_path: &str, _dir: &std::path::Path) -> Result<Vec<Attachment>> {
+        Ok(Vec::new())
+    }
+
+    /// Extract a file and write its embedded files into `dir` in one go. Formats that
+    /// parse the whole file for either override this to parse it only once.
+    async fn extract_with_attachments(
+        &self,
+        file_path: &str,
+        options: &ExtractOptions,
+        dir: &std::path::Path,
+    ) -> Result<(ExtractedDocument, Vec<Attachment>)> {
+        let document = self.extract(file_path, options).await?;
+        let attachments = self.extract_attachments(file_path, dir).await
+            .context("Failed to extract embedded files")?;
+        Ok((document, attachments))
+    }
+
     fn supports(&self, file_path: &str) -> bool;
+
+    /// Whether this extractor reads files of the given MIME type (e.g. `application/pdf`)
+    fn supports_mime_type(&self, _mime_type: &str) -> bool {
+        false
+    }
 }
 
 /// Trait for vector database operations
 #[async_trait]
 pub trait VectorStore: Send + Sync {
     async fn ingest(&self, document_id: &str, text: &str, metadata: Option<serde_json::Value>) -> Result<()>;
+
+    /// Ingest an extracted document, whose section metadata should follow their text
+    /// into the store
+    async fn ingest_document(&self, document_id: &str, document: &ExtractedDocument) -> Result<()> {
+        self.ingest(document_id, &document.text(), document.metadata_value()).await
+    }
 }
 
 /// Trait for graph database operations
 #[async_trait]
 pub trait GraphStore: Send + Sync {
     async fn ingest(&self, document_id: &str, text: &str, metadata: Option<serde_json::Value>) -> Result<()>;
+
+    /// Ingest an extracted document, whose section metadata should follow their text
+    /// into the store
+    async fn ingest_document(&self, document_id: &str, document: &ExtractedDocument) -> Result<()> {
+        self.ingest(document_id, &document.text(), document.metadata_value()).await
+    }
 }
 
 /// Ingestion target type
@@ -1,26 +1,46 @@
-use super::{VectorStore, GraphStore, IngestionTarget, GraphDbType};
-use super::extractors::get_extractor;
-use super::stores::{ChromaDbStore, create_graph_store};
+use super::{VectorStore, GraphStore, IngestionTarget, GraphDbType, ExtractOptions, ExtractedDocument, Attachment, Section};
+use super::chunking::{ChunkingOptions, create_chunker};
+use super::embeddings::Embedder;
+use super::extractors::ExtractorRegistry;
+use super::stores::{ChromaConfig, ChromaDbStore, create_graph_store};
 use anyhow::{Context, Result};
 use sea_orm::DatabaseConnection;
 use crate::models::_entities::documents::{self, Entity as Documents};
-use sea_orm::{EntityTrait, Set, ActiveModelTrait};
+use sea_orm::{EntityTrait, Set, ActiveModelTrait, ColumnTrait, QueryFilter};
+use sea_orm::sea_query::Expr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Most levels of archives and attachments nested in one another that are unpacked,
+/// guarding against archives that contain themselves
+pub const MAX_NESTING_DEPTH: usize = 3;
+
+/// A document created for a file embedded in another, ready to be queued for ingestion
+#[derive(Debug, Clone)]
+pub struct ChildDocument {
+    pub document_id: i32,
+    pub file_path: String,
+    pub options: ExtractOptions,
+}
 
 pub struct IngestionPipeline {
     db: DatabaseConnection,
     vector_store: Option<Box<dyn VectorStore>>,
     graph_store: Option<Box<dyn GraphStore>>,
+    extractors: Arc<ExtractorRegistry>,
+    chunking: ChunkingOptions,
+    embedder: Option<Arc<dyn Embedder>>,
 }
 
 impl IngestionPipeline {
     pub async fn new(
         db: DatabaseConnection,
-        chroma_url: Option<&str>,
+        chroma: Option<ChromaConfig>,
         graph_db_type: Option<GraphDbType>,
         graph_config: Option<serde_json::Value>,
     ) -> Result<Self> {
-        let vector_store = if let Some(url) = chroma_url {
-            Some(Box::new(ChromaDbStore::new(url, "akashic").await?) as Box<dyn VectorStore>)
+        let vector_store = if let Some(config) = chroma {
+            Some(Box::new(ChromaDbStore::new(&config).await?) as Box<dyn VectorStore>)
         } else {
             None
         };
@@ -35,58 +55,203 @@ impl IngestionPipeline {
             db,
             vector_store,
             graph_store,
+            extractors: ExtractorRegistry::builtin(),
+            chunking: ChunkingOptions::default(),
+            embedder: None,
         })
     }
 
+    /// Pick extractors from `extractors` instead of the built-ins
+    pub fn with_extractors(mut self, extractors: Arc<ExtractorRegistry>) -> Self {
+        self.extractors = extractors;
+        self
+    }
+
+    /// Chunk documents with `chunking` unless a request overrides it
+    pub fn with_chunking(mut self, chunking: ChunkingOptions) -> Self {
+        self.chunking = chunking;
+        self
+    }
+
+    /// Embed chunks with `embedder` before handing them to the stores, instead of
+    /// leaving it to the vector store; semantic chunking uses it too
+    pub fn with_embedder(mut self, embedder: Arc<dyn Embedder>) -> Self {
+        self.embedder = Some(embedder);
+        self
+    }
+
+    /// Extract a file and ingest its text into the configured stores.
+    ///
+    /// Files embedded in it (archive entries, email attachments) are unpacked into
+    /// documents of their own, linked through `parent_id`, and returned for the caller
+    /// to queue. A document with children stays `processing` until they have all
+    /// finished; see [`Self::refresh_parent_status`]. Unpacked files are deleted as
+    /// soon as the job ingesting them finishes.
     pub async fn process_file(
         &self,
         document_id: i32,
         file_path: &str,
         target: IngestionTarget,
-    ) -> Result<()> {
+        options: &ExtractOptions,
+    ) -> Result<Vec<ChildDocument>> {
+        let result = self.extract_and_ingest(document_id, file_path, target, options).await;
+
+        // A file unpacked from another document is only needed by its own job
+        if Path::new(file_path).starts_with(attachments_root()) {
+            if let Err(e) = tokio::fs::remove_file(file_path).await {
+                tracing::warn!("Failed to remove unpacked file {}: {}", file_path, e);
+            }
+        }
+
+        result
+    }
+
+    async fn extract_and_ingest(
+        &self,
+        document_id: i32,
+        file_path: &str,
+        target: IngestionTarget,
+        options: &ExtractOptions,
+    ) -> Result<Vec<ChildDocument>> {
         // Update status to processing
         self.update_document_status(document_id, "processing", 0).await?;
 
         // Extract text
-        let extractor = get_extractor(file_path)
+        let extractor = self.extractors.get(file_path, options.content_type.as_deref())
             .context("No extractor found for this file type")?;
         
-        let text = extractor.extract(file_path).await
-            .context("Failed to extract text from file")?;
+        // Unpack embedded files before touching the stores, so an archive over its
+        // limits fails without ingesting anything
+        let (document, attachments) = if self.nesting_depth(document_id).await? < MAX_NESTING_DEPTH {
+            let dir = attachment_dir(document_id);
+            tokio::fs::create_dir_all(&dir)
+                .await
+                .context("Failed to create a directory for embedded files")?;
+            match extractor.extract_with_attachments(file_path, options, &dir).await {
+                Ok(extracted) => extracted,
+                Err(e) => {
+                    remove_attachment_dir(document_id).await;
+                    return Err(e.context("Failed to extract text from file"));
+                }
+            }
+        } else {
+            tracing::warn!(
+                "Not unpacking embedded files of document {}: nested more than {} levels deep",
+                document_id,
+                MAX_NESTING_DEPTH
+            );
+            let document = extractor.extract(file_path, options).await
+                .context("Failed to extract text from file")?;
+            (document, Vec::new())
+        };
+
+        let children = match self.ingest_extracted(document_id, &document, attachments, target, options).await {
+            Ok(children) => children,
+            Err(e) => {
+                remove_attachment_dir(document_id).await;
+                return Err(e);
+            }
+        };
+
+        if children.is_empty() {
+            // None of the unpacked files will be ingested
+            remove_attachment_dir(document_id).await;
+            // Mark as completed
+            self.update_document_status(document_id, "completed", 100).await?;
+            self.refresh_parent_status(document_id).await?;
+        } else {
+            self.update_document_status(document_id, "processing", 90).await?;
+        }
+
+        Ok(children)
+    }
+
+    /// Store an extracted document and create documents for its embedded files
+    async fn ingest_extracted(
+        &self,
+        document_id: i32,
+        document: &ExtractedDocument,
+        attachments: Vec<Attachment>,
+        target: IngestionTarget,
+        options: &ExtractOptions,
+    ) -> Result<Vec<ChildDocument>> {
+        self.merge_document_metadata(document_id, &document.metadata).await?;
 
         self.update_document_status(document_id, "processing", 30).await?;
 
-        // Ingest based on target
-        match target {
-            IngestionTarget::Vector => {
-                if let Some(ref store) = self.vector_store {
-                    store.ingest(&document_id.to_string(), &text, None).await?;
-                }
-                self.update_document_status(document_id, "processing", 80).await?;
-            }
-            IngestionTarget::Graph => {
-                if let Some(ref store) = self.graph_store {
-                    store.ingest(&document_id.to_string(), &text, None).await?;
-                }
-                self.update_document_status(document_id, "processing", 80).await?;
+        // Archives have no text of their own
+        if !document.is_empty() {
+            let chunks = self.chunk(document, options).await?;
+            self.ingest_chunks(document_id, &chunks, target).await?;
+        }
+
+        self.create_children(document_id, attachments, options).await
+    }
+
+    /// Create a `documents` row for each supported embedded file, inheriting the
+    /// parent's ingestion settings. Unsupported files are skipped.
+    async fn create_children(
+        &self,
+        parent_id: i32,
+        attachments: Vec<Attachment>,
+        options: &ExtractOptions,
+    ) -> Result<Vec<ChildDocument>> {
+        if attachments.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let parent = Documents::find_by_id(parent_id)
+            .one(&self.db)
+            .await?
+            .context("Document not found")?;
+
+        let mut children = Vec::new();
+        for attachment in attachments {
+            let file_path = attachment.path.to_string_lossy().to_string();
+            let Some(extractor) = self.extractors.get_name(&file_path, attachment.content_type.as_deref()) else {
+                tracing::debug!(
+                    "Skipping embedded file {} of document {}: unsupported format",
+                    attachment.filename,
+                    parent_id
+                );
+                continue;
+            };
+            // The archive limits only hold for one archive at a time, so archives packed
+            // in archives are not unpacked at all
+            if extractor == "archive" && attachment.metadata.contains_key("archive_path") {
+                tracing::warn!(
+                    "Skipping embedded file {} of document {}: archives nested in archives are not unpacked",
+                    attachment.filename,
+                    parent_id
+                );
+                continue;
             }
-            IngestionTarget::Both => {
-                if let Some(ref store) = self.vector_store {
-                    store.ingest(&document_id.to_string(), &text, None).await?;
-                }
-                self.update_document_status(document_id, "processing", 60).await?;
 
-                if let Some(ref store) = self.graph_store {
-                    store.ingest(&document_id.to_string(), &text, None).await?;
-                }
-                self.update_document_status(document_id, "processing", 80).await?;
+            let child = documents::ActiveModel {
+                filename: Set(Some(attachment.filename)),
+                status: Set(Some("queued".to_string())),
+                ingestion_type: Set(parent.ingestion_type.clone()),
+                graph_db: Set(parent.graph_db.clone()),
+                progress: Set(Some(0)),
+                metadata: Set(Some(serde_json::Value::Object(attachment.metadata).to_string())),
+                parent_id: Set(Some(parent_id)),
+                ..Default::default()
             }
-        }
+            .insert(&self.db)
+            .await?;
 
-        // Mark as completed
-        self.update_document_status(document_id, "completed", 100).await?;
+            children.push(ChildDocument {
+                document_id: child.id,
+                file_path,
+                options: ExtractOptions {
+                    content_type: attachment.content_type,
+                    encoding: None,
+                    ..options.clone()
+                },
+            });
+        }
 
-        Ok(())
+        Ok(children)
     }
 
     pub async fn process_text(
@@ -94,32 +259,78 @@ impl IngestionPipeline {
         document_id: i32,
         text: &str,
         target: IngestionTarget,
+        options: &ExtractOptions,
     ) -> Result<()> {
         self.update_document_status(document_id, "processing", 10).await?;
 
+        let document = ExtractedDocument::new(vec![Section::new(text)]);
+        let chunks = self.chunk(&document, options).await?;
+        self.ingest_chunks(document_id, &chunks, target).await?;
+
+        self.update_document_status(document_id, "completed", 100).await?;
+
+        Ok(())
+    }
+
+    /// Split a document into chunks with the configured strategy, or the one the
+    /// request asked for, so that every store receives the same chunks
+    async fn chunk(&self, document: &ExtractedDocument, options: &ExtractOptions) -> Result<ExtractedDocument> {
+        let chunker = create_chunker(&self.chunking.with_overrides(options), self.embedder.clone())?;
+        let mut sections = chunker.chunk(&document.sections).await
+            .context("Failed to chunk extracted text")?;
+
+        if let Some(embedder) = &self.embedder {
+            self.embed(embedder.as_ref(), &mut sections).await?;
+        }
+
+        Ok(ExtractedDocument {
+            metadata: document.metadata.clone(),
+            sections,
+        })
+    }
+
+    /// Embed each chunk once so that every store gets the same vectors, recording the
+    /// model and dimension in the chunk's metadata
+    async fn embed(&self, embedder: &dyn Embedder, chunks: &mut [Section]) -> Result<()> {
+        let texts: Vec<String> = chunks.iter().map(|chunk| chunk.text.clone()).collect();
+        let vectors = embedder.embed(&texts).await
+            .with_context(|| format!("Failed to embed chunks with {}", embedder.model()))?;
+
+        for (chunk, vector) in chunks.iter_mut().zip(vectors) {
+            chunk.metadata.insert("embedding_model".to_string(), embedder.model().into());
+            chunk.metadata.insert("embedding_dimension".to_string(), vector.len().into());
+            chunk.embedding = Some(vector);
+        }
+        Ok(())
+    }
+
+    /// Ingest chunks into the stores of the target
+    async fn ingest_chunks(&self, document_id: i32, chunks: &ExtractedDocument, target: IngestionTarget) -> Result<()> {
         match target {
             IngestionTarget::Vector => {
                 if let Some(ref store) = self.vector_store {
-                    store.ingest(&document_id.to_string(), text, None).await?;
+                    store.ingest_document(&document_id.to_string(), chunks).await?;
                 }
+                self.update_document_status(document_id, "processing", 80).await?;
             }
             IngestionTarget::Graph => {
                 if let Some(ref store) = self.graph_store {
-                    store.ingest(&document_id.to_string(), text, None).await?;
+                    store.ingest_document(&document_id.to_string(), chunks).await?;
                 }
+                self.update_document_status(document_id, "processing", 80).await?;
             }
             IngestionTarget::Both => {
                 if let Some(ref store) = self.vector_store {
-                    store.ingest(&document_id.to_string(), text, None).await?;
+                    store.ingest_document(&document_id.to_string(), chunks).await?;
                 }
+                self.update_document_status(document_id, "processing", 60).await?;
+
                 if let Some(ref store) = self.graph_store {
-                    store.ingest(&document_id.to_string(), text, None).await?;
+                    store.ingest_document(&document_id.to_string(), chunks).await?;
                 }
+                self.update_document_status(document_id, "processing", 80).await?;
             }
         }
-
-        self.update_document_status(document_id, "completed", 100).await?;
-
         Ok(())
     }
 
@@ -142,6 +353,43 @@ impl IngestionPipeline {
         Ok(())
     }
 
+    /// Store extracted metadata in the document's `metadata` column.
+    ///
+    /// Values supplied with the upload take precedence over extracted ones.
+    async fn merge_document_metadata(
+        &self,
+        document_id: i32,
+        extracted: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<()> {
+        if extracted.is_empty() {
+            return Ok(());
+        }
+
+        let doc = Documents::find_by_id(document_id)
+            .one(&self.db)
+            .await?
+            .context("Document not found")?;
+
+        let mut merged = doc
+            .metadata
+            .as_deref()
+            .and_then(|m| serde_json::from_str::<serde_json::Value>(m).ok())
+            .and_then(|m| match m {
+                serde_json::Value::Object(map) => Some(map),
+                _ => None,
+            })
+            .unwrap_or_default();
+        for (key, value) in extracted {
+            merged.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+
+        let mut active: documents::ActiveModel = doc.into();
+        active.metadata = Set(Some(serde_json::Value::Object(merged).to_string()));
+        active.update(&self.db).await?;
+
+        Ok(())
+    }
+
     pub async fn handle_error(&self, document_id: i32, error: &str) -> Result<()> {
         let doc = Documents::find_by_id(document_id)
             .one(&self.db)
@@ -153,6 +401,113 @@ impl IngestionPipeline {
         active.error_message = Set(Some(error.to_string()));
         active.update(&self.db).await?;
 
-        Ok(())
+        self.refresh_parent_status(document_id).await
+    }
+
+    /// Recompute the status of the documents a document was unpacked from, now that it
+    /// has finished.
+    ///
+    /// A parent stays `processing`, with the share of finished children as its progress,
+    /// until all of them have finished. It is then `completed`, or `failed` when every
+    /// child failed, with the failed children listed in its error message. Finishing a
+    /// parent in turn refreshes its own parent.
+    ///
+    /// Children finishing at the same time may each count the others as unfinished, so
+    /// a parent is only ever moved back to `processing` while it has not finished.
+    async fn refresh_parent_status(&self, document_id: i32) -> Result<()> {
+        let mut current = document_id;
+        loop {
+            let Some(parent_id) = Documents::find_by_id(current)
+                .one(&self.db)
+                .await?
+                .and_then(|doc| doc.parent_id)
+            else {
+                return Ok(());
+            };
+
+            let children = Documents::find_children(&self.db, parent_id).await?;
+            let failed: Vec<&str> = children
+                .iter()
+                .filter(|child| child.status.as_deref() == Some("failed"))
+                .map(|child| child.filename.as_deref().unwrap_or("unknown"))
+                .collect();
+            let finished = children
+                .iter()
+                .filter(|child| matches!(child.status.as_deref(), Some("completed" | "failed")))
+                .count();
+
+            if finished < children.len() {
+                let progress = (finished * 100 / children.len()).min(99) as i32;
+                Documents::update_many()
+                    .col_expr(documents::Column::Status, Expr::value("processing"))
+                    .col_expr(documents::Column::Progress, Expr::value(progress))
+                    .col_expr(documents::Column::UpdatedAt, Expr::value(chrono::Utc::now().fixed_offset()))
+                    .filter(documents::Column::Id.eq(parent_id))
+                    .filter(documents::Column::Status.is_not_in(["completed", "failed"]))
+                    .exec(&self.db)
+                    .await?;
+                return Ok(());
+            }
+
+            let parent = Documents::find_by_id(parent_id)
+                .one(&self.db)
+                .await?
+                .context("Document not found")?;
+            let mut active: documents::ActiveModel = parent.into();
+
+            // Every child has been ingested, so the files unpacked for them can go
+            remove_attachment_dir(parent_id).await;
+
+            let status = if failed.len() == children.len() { "failed" } else { "completed" };
+            active.status = Set(Some(status.to_string()));
+            active.progress = Set(Some(100));
+            if !failed.is_empty() {
+                active.error_message = Set(Some(format!(
+                    "Failed to ingest {} of {} embedded files: {}",
+                    failed.len(),
+                    children.len(),
+                    failed.join(", ")
+                )));
+            }
+            active.update(&self.db).await?;
+
+            current = parent_id;
+        }
+    }
+
+    /// How many ancestors a document has
+    async fn nesting_depth(&self, document_id: i32) -> Result<usize> {
+        let mut depth = 0;
+        let mut current = document_id;
+        while let Some(parent_id) = Documents::find_by_id(current)
+            .one(&self.db)
+            .await?
+            .and_then(|doc| doc.parent_id)
+        {
+            depth += 1;
+            current = parent_id;
+        }
+        Ok(depth)
+    }
+}
+
+/// Where embedded files are unpacked, in a directory per document
+fn attachments_root() -> PathBuf {
+    std::env::temp_dir().join("akashic-attachments")
+}
+
+/// Where the embedded files of a document are unpacked; they must outlive the
+/// document's own ingestion until its children have been processed
+fn attachment_dir(document_id: i32) -> PathBuf {
+    attachments_root().join(document_id.to_string())
+}
+
+/// Remove whatever is left of a document's unpacked files
+async fn remove_attachment_dir(document_id: i32) {
+    let dir = attachment_dir(document_id);
+    match tokio::fs::remove_dir_all(&dir).await {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => tracing::warn!("Failed to remove {}: {}", dir.display(), e),
     }
 }
@@ -1,84 +1,368 @@
-use super::{VectorStore, GraphStore, GraphDbType};
+use super::embeddings::content_hash;
+use super::{VectorStore, GraphStore, GraphDbType, Section, ExtractedDocument};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
+use serde::Deserialize;
 use serde_json::json;
+use std::collections::HashMap;
+
+/// Version of the ChromaDB HTTP API to talk
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChromaApiVersion {
+    /// Ask the server: `/api/v2` when it answers there, else `/api/v1`
+    #[default]
+    Auto,
+    /// Chroma before 0.6, addressing collections under `/api/v1`
+    V1,
+    /// Chroma 0.6 and later, addressing collections under
+    /// `/api/v2/tenants/{tenant}/databases/{database}`
+    V2,
+}
+
+/// Where the ChromaDB collection lives
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChromaConfig {
+    pub url: String,
+    pub tenant: String,
+    pub database: String,
+    pub collection: String,
+    pub api_version: ChromaApiVersion,
+}
+
+impl ChromaConfig {
+    /// The `akashic` collection of Chroma's default tenant and database at `url`
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            tenant: "default_tenant".to_string(),
+            database: "default_database".to_string(),
+            collection: "akashic".to_string(),
+            api_version: ChromaApiVersion::Auto,
+        }
+    }
+
+    /// Read `CHROMA_URL`, `CHROMA_TENANT`, `CHROMA_DATABASE`, `CHROMA_COLLECTION` and
+    /// `CHROMA_API_VERSION` (`auto`, `v1` or `v2`); `None` when `CHROMA_URL` is unset
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(url) = std::env::var("CHROMA_URL") else {
+            return Ok(None);
+        };
+        let mut config = Self::new(&url);
+        if let Ok(tenant) = std::env::var("CHROMA_TENANT") {
+            config.tenant = tenant;
+        }
+        if let Ok(database) = std::env::var("CHROMA_DATABASE") {
+            config.database = database;
+        }
+        if let Ok(collection) = std::env::var("CHROMA_COLLECTION") {
+            config.collection = collection;
+        }
+        if let Ok(version) = std::env::var("CHROMA_API_VERSION") {
+            config.api_version = serde_json::from_str(&format!("\"{}\"", version.to_lowercase()))
+                .with_context(|| format!("Invalid CHROMA_API_VERSION {:?}: expected auto, v1 or v2", version))?;
+        }
+        Ok(Some(config))
+    }
+}
+
+#[derive(Deserialize)]
+struct ChromaCollection {
+    id: String,
+}
 
 /// ChromaDB Vector Store (using HTTP API)
 pub struct ChromaDbStore {
-    base_url: String,
-    collection_name: String,
+    /// Base of the collection routes: `/api/v1` or `/api/v2/tenants/{t}/databases/{d}`
+    api_url: String,
+    api_version: ChromaApiVersion,
+    collection_id: String,
     client: reqwest::Client,
 }
 
 impl ChromaDbStore {
-    pub async fn new(url: &str, collection_name: &str) -> Result<Self> {
+    /// Connect to the server and get or create the configured collection
+    pub async fn new(config: &ChromaConfig) -> Result<Self> {
         let client = reqwest::Client::new();
-        let base_url = url.trim_end_matches('/').to_string();
-        
-        // Create collection if it doesn't exist
-        let _response = client
-            .post(format!("{}/api/v1/collections", base_url))
+        let base_url = config.url.trim_end_matches('/').to_string();
+
+        let api_version = match config.api_version {
+            ChromaApiVersion::Auto => Self::detect_api_version(&client, &base_url).await?,
+            version => version,
+        };
+        let api_url = match api_version {
+            ChromaApiVersion::V2 => {
+                // Tenant and database names become path segments, percent-encoded
+                let mut url = reqwest::Url::parse(&base_url)
+                    .with_context(|| format!("Invalid ChromaDB URL {}", base_url))?;
+                url.path_segments_mut()
+                    .map_err(|_| anyhow::anyhow!("Invalid ChromaDB URL {}", base_url))?
+                    .pop_if_empty()
+                    .extend(["api", "v2", "tenants", &config.tenant, "databases", &config.database]);
+                url.to_string()
+            }
+            _ => format!("{}/api/v1", base_url),
+        };
+
+        let mut request = client
+            .post(format!("{}/collections", api_url))
             .json(&json!({
-                "name": collection_name,
-                "metadata": {}
-            }))
+                "name": config.collection,
+                "get_or_create": true
+            }));
+        // v1 takes the tenant and database as query parameters
+        if api_version == ChromaApiVersion::V1 {
+            request = request.query(&[("tenant", &config.tenant), ("database", &config.database)]);
+        }
+        let response = request
             .send()
-            .await;
-        // Ignore error if collection already exists
-        
+            .await
+            .context("Failed to send request to ChromaDB")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!(
+                "Failed to create ChromaDB collection {} ({}): {}",
+                config.collection, status, error_text
+            );
+        }
+        let collection: ChromaCollection = response
+            .json()
+            .await
+            .context("Malformed ChromaDB collection response")?;
+
         Ok(Self {
-            base_url,
-            collection_name: collection_name.to_string(),
+            api_url,
+            api_version,
+            collection_id: collection.id,
             client,
         })
     }
+
+    /// The API version in use, after auto-detection
+    pub fn api_version(&self) -> ChromaApiVersion {
+        self.api_version
+    }
+
+    /// Id of the collection chunks are written to
+    pub fn collection_id(&self) -> &str {
+        &self.collection_id
+    }
+
+    /// Servers from 0.6 on answer `/api/v2/heartbeat` (and refuse `/api/v1`); older
+    /// ones only know `/api/v1/heartbeat`
+    async fn detect_api_version(client: &reqwest::Client, base_url: &str) -> Result<ChromaApiVersion> {
+        for (version, path) in [
+            (ChromaApiVersion::V2, "/api/v2/heartbeat"),
+            (ChromaApiVersion::V1, "/api/v1/heartbeat"),
+        ] {
+            let response = client
+                .get(format!("{}{}", base_url, path))
+                .send()
+                .await
+                .with_context(|| format!("Failed to reach ChromaDB at {}", base_url))?;
+            if response.status().is_success() {
+                return Ok(version);
+            }
+        }
+        anyhow::bail!("ChromaDB at {} answers neither the v1 nor the v2 API", base_url)
+    }
 }
 
 #[async_trait]
 impl VectorStore for ChromaDbStore {
     async fn ingest(&self, document_id: &str, text: &str, metadata: Option<serde_json::Value>) -> Result<()> {
-        // Chunk the text (simple implementation - split by paragraphs)
-        let chunks: Vec<&str> = text.split("\n\n").filter(|s| !s.trim().is_empty()).collect();
-        
+        let mut document = ExtractedDocument::new(vec![Section::new(text)]);
+        if let Some(serde_json::Value::Object(metadata)) = metadata {
+            document.metadata = metadata;
+        }
+        self.ingest_document(document_id, &document).await
+    }
+
+    async fn ingest_document(&self, document_id: &str, document: &ExtractedDocument) -> Result<()> {
+        // The pipeline has already chunked the document: one Chroma document per section
         let mut ids = Vec::new();
         let mut documents = Vec::new();
         let mut metadatas = Vec::new();
+        let mut embeddings = Vec::new();
+        let parts = external_parts(&document.sections);
         
-        for (idx, chunk) in chunks.iter().enumerate() {
-            let chunk_id = format!("{}_{}", document_id, idx);
-            let mut chunk_metadata = metadata.clone().unwrap_or(json!({}));
-            
-            if let Some(obj) = chunk_metadata.as_object_mut() {
-                obj.insert("chunk_index".to_string(), json!(idx));
-                obj.insert("document_id".to_string(), json!(document_id));
-            }
+        for (idx, (chunk, part)) in document.sections.iter().zip(&parts).enumerate() {
+            let mut chunk_metadata = document.metadata.clone();
+            chunk_metadata.extend(chunk.metadata.clone());
+            chunk_metadata.insert("chunk_index".to_string(), json!(idx));
+            chunk_metadata.insert("document_id".to_string(), json!(document_id));
+
+            // Records with a stable external id keep the same ids when ingested again,
+            // prefixed so that they never collide with `{document_id}_{idx}` and keyed by
+            // a fixed-length hash of their namespace so that sources never collide either
+            let chunk_id = match part {
+                Some(part) => {
+                    chunk_metadata.insert("id_namespace".to_string(), json!(part.namespace));
+                    chunk_metadata.insert("external_part".to_string(), json!(part.part));
+                    format!("ext:{}:{}#{}", &content_hash(part.namespace)[..16], part.external_id, part.part)
+                }
+                None => format!("{}_{}", document_id, idx),
+            };
 
             ids.push(chunk_id);
-            documents.push(chunk.to_string());
-            metadatas.push(chunk_metadata);
+            documents.push(chunk.text.as_str());
+            embeddings.extend(chunk.embedding.as_ref());
+            metadatas.push(serde_json::Value::Object(scalar_properties(chunk_metadata)));
         }
 
-        // Add documents to collection
+        let mut body = json!({
+            "ids": ids,
+            "documents": documents,
+            "metadatas": metadatas
+        });
+        // Without embeddings of our own, Chroma embeds the documents with its default model
+        if !embeddings.is_empty() {
+            if embeddings.len() != ids.len() {
+                anyhow::bail!("Only {} of {} chunks have an embedding", embeddings.len(), ids.len());
+            }
+            body["embeddings"] = json!(embeddings);
+        }
+
+        // Upsert so chunks with stable ids replace their earlier versions
+        self.post("upsert", &body).await?;
+
+        // A record that now has fewer chunks leaves its trailing parts behind; every
+        // part still current was just rewritten with this document's id
+        for (namespace, external_ids) in distinct_external_ids(&parts) {
+            self.post(
+                "delete",
+                &json!({
+                    "where": {
+                        "$and": [
+                            {"id_namespace": {"$eq": namespace}},
+                            {"external_id": {"$in": external_ids}},
+                            {"document_id": {"$ne": document_id}}
+                        ]
+                    }
+                }),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ChromaDbStore {
+    /// POST `body` to a route of the collection
+    async fn post(&self, route: &str, body: &serde_json::Value) -> Result<()> {
         let response = self.client
-            .post(format!("{}/api/v1/collections/{}/add", self.base_url, self.collection_name))
-            .json(&json!({
-                "ids": ids,
-                "documents": documents,
-                "metadatas": metadatas
-            }))
+            .post(format!("{}/collections/{}/{}", self.api_url, self.collection_id, route))
+            .json(body)
             .send()
             .await
             .context("Failed to send request to ChromaDB")?;
 
         if !response.status().is_success() {
+            let status = response.status();
             let error_text = response.text().await.unwrap_or_default();
-            anyhow::bail!("ChromaDB request failed: {}", error_text);
+            anyhow::bail!("ChromaDB request failed ({}): {}", status, error_text);
         }
 
         Ok(())
     }
 }
 
+/// A section of a record with a stable `external_id`
+struct ExternalPart<'a> {
+    /// Source the id is unique within, its `id_namespace` (empty without one)
+    namespace: &'a str,
+    external_id: &'a str,
+    /// Which of the record's chunks the section is, 0 for the first
+    part: usize,
+}
+
+/// The external part of each section that has one
+fn external_parts(sections: &[Section]) -> Vec<Option<ExternalPart<'_>>> {
+    let mut counts: HashMap<(&str, &str), usize> = HashMap::new();
+    sections
+        .iter()
+        .map(|section| {
+            let external_id = section.metadata.get("external_id")?.as_str()?;
+            let namespace = section.metadata.get("id_namespace").and_then(|n| n.as_str()).unwrap_or_default();
+            let count = counts.entry((namespace, external_id)).or_insert(0);
+            *count += 1;
+            Some(ExternalPart { namespace, external_id, part: *count - 1 })
+        })
+        .collect()
+}
+
+/// The external ids among `parts` grouped by namespace, each once and in order
+fn distinct_external_ids<'a>(parts: &[Option<ExternalPart<'a>>]) -> Vec<(&'a str, Vec<&'a str>)> {
+    let mut groups: Vec<(&str, Vec<&str>)> = Vec::new();
+    for part in parts.iter().flatten().filter(|p| p.part == 0) {
+        match groups.iter_mut().find(|(namespace, _)| *namespace == part.namespace) {
+            Some((_, ids)) => ids.push(part.external_id),
+            None => groups.push((part.namespace, vec![part.external_id])),
+        }
+    }
+    groups
+}
+
+/// Chroma metadata and graph properties only hold scalar values, so nested values are
+/// serialized and nulls dropped
+fn scalar_properties(properties: serde_json::Map<String, serde_json::Value>) -> serde_json::Map<String, serde_json::Value> {
+    properties
+        .into_iter()
+        .filter(|(_, value)| !value.is_null())
+        .map(|(key, value)| match value {
+            serde_json::Value::Array(_) | serde_json::Value::Object(_) => {
+                (key, serde_json::Value::String(value.to_string()))
+            }
+            scalar => (key, scalar),
+        })
+        .collect()
+}
+
+/// Rows of a section as `{index, data}` maps, `data` holding the row's fields as
+/// properties of its `Record` node
+fn record_rows(section: &Section) -> Vec<serde_json::Value> {
+    section
+        .records
+        .iter()
+        .enumerate()
+        .map(|(index, record)| json!({"index": index, "data": scalar_properties(record.clone())}))
+        .collect()
+}
+
+/// Attach a section to its document
+const CREATE_SECTION: &str = "MATCH (d:Document {id: $id}) \
+     CREATE (d)-[:HAS_SECTION]->(:Section {document_id: $id, index: $index, text: $text, metadata: $metadata})";
+
+/// Attach a part of a record with a stable external id to its document, reusing the
+/// node an earlier ingestion left and dropping its records beyond `$records`
+const MERGE_SECTION: &str = "MATCH (d:Document {id: $id}) \
+     MERGE (s:Section {id_namespace: $id_namespace, external_id: $external_id, part: $part}) \
+     SET s.document_id = $id, s.index = $index, s.text = $text, s.metadata = $metadata \
+     WITH d, s \
+     OPTIONAL MATCH (:Document)-[old:HAS_SECTION]->(s) \
+     DELETE old \
+     WITH DISTINCT d, s \
+     MERGE (d)-[:HAS_SECTION]->(s) \
+     WITH s \
+     OPTIONAL MATCH (s)-[:HAS_RECORD]->(r:Record) WHERE r.index >= $records \
+     DETACH DELETE r";
+
+/// Attach the rows of a section to it, all in one query
+const MERGE_RECORDS: &str = "MATCH (s:Section {document_id: $id, index: $index}) \
+     UNWIND $rows AS row \
+     MERGE (s)-[:HAS_RECORD]->(r:Record {index: row.index}) \
+     SET r = row.data, r.index = row.index, r.document_id = $id, r.section_index = $index";
+
+/// Remove the parts of re-ingested records beyond their current chunk count, which
+/// are the only ones not rewritten for this document
+const DELETE_STALE_SECTIONS: &str = "MATCH (s:Section) \
+     WHERE s.id_namespace = $id_namespace AND s.external_id IN $external_ids AND s.document_id <> $id \
+     OPTIONAL MATCH (s)-[:HAS_RECORD]->(r:Record) \
+     DETACH DELETE s, r";
+
 /// Neo4j Graph Store
 pub struct Neo4jStore {
     graph: neo4rs::Graph,
@@ -112,6 +396,75 @@ impl GraphStore for Neo4jStore {
 
         Ok(())
     }
+
+    async fn ingest_document(&self, document_id: &str, document: &ExtractedDocument) -> Result<()> {
+        self.ingest(document_id, &document.text(), document.metadata_value()).await?;
+
+        let sections = &document.sections;
+        if sections.iter().all(|s| s.metadata.is_empty()) {
+            return Ok(());
+        }
+
+        // Attach each section (e.g. a PDF page) to the document with its own metadata
+        let parts = external_parts(sections);
+        for (idx, (section, part)) in sections.iter().zip(&parts).enumerate() {
+            let query = neo4rs::query(if part.is_some() { MERGE_SECTION } else { CREATE_SECTION })
+                .param("id", document_id)
+                .param("index", idx as i64)
+                .param("text", section.text.as_str())
+                .param("metadata", serde_json::Value::Object(section.metadata.clone()).to_string());
+            let query = match part {
+                Some(part) => query
+                    .param("id_namespace", part.namespace)
+                    .param("external_id", part.external_id)
+                    .param("part", part.part as i64)
+                    .param("records", section.records.len() as i64),
+                None => query,
+            };
+
+            self.graph.run(query).await.context("Failed to insert section into Neo4j")?;
+
+            // Rows of tabular sections become records of their own
+            if !section.records.is_empty() {
+                let rows: Vec<neo4rs::BoltType> = record_rows(section).into_iter().map(bolt_value).collect();
+                let query = neo4rs::query(MERGE_RECORDS)
+                    .param("id", document_id)
+                    .param("index", idx as i64)
+                    .param("rows", rows);
+
+                self.graph.run(query).await.context("Failed to insert records into Neo4j")?;
+            }
+        }
+
+        for (namespace, external_ids) in distinct_external_ids(&parts) {
+            let query = neo4rs::query(DELETE_STALE_SECTIONS)
+                .param("id", document_id)
+                .param("id_namespace", namespace)
+                .param("external_ids", external_ids);
+            self.graph.run(query).await.context("Failed to remove stale sections from Neo4j")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A JSON value as a Bolt parameter
+fn bolt_value(value: serde_json::Value) -> neo4rs::BoltType {
+    match value {
+        serde_json::Value::Null => neo4rs::BoltType::Null(neo4rs::BoltNull),
+        serde_json::Value::Bool(b) => b.into(),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => i.into(),
+            None => n.as_f64().unwrap_or_default().into(),
+        },
+        serde_json::Value::String(s) => s.into(),
+        serde_json::Value::Array(items) => items.into_iter().map(bolt_value).collect::<Vec<_>>().into(),
+        serde_json::Value::Object(map) => map
+            .into_iter()
+            .map(|(key, value)| (key, bolt_value(value)))
+            .collect::<HashMap<_, _>>()
+            .into(),
+    }
 }
 
 /// FalkorDB Graph Store (using Redis protocol)
@@ -135,21 +488,21 @@ impl FalkorDbStore {
 #[async_trait]
 impl GraphStore for FalkorDbStore {
     async fn ingest(&self, document_id: &str, text: &str, metadata: Option<serde_json::Value>) -> Result<()> {
-        use redis::AsyncCommands;
-        
         let mut con = self.client.get_multiplexed_async_connection()
             .await
             .context("Failed to get Redis connection")?;
         
-        // Create a Document node using Cypher query
-        let query = format!(
-            "CREATE (d:Document {{id: '{}', text: '{}', metadata: '{}', created_at: timestamp()}})",
-            document_id.replace("'", "\\'"),
-            text.replace("'", "\\'").chars().take(1000).collect::<String>(), // Limit text size
-            metadata.unwrap_or(json!({})).to_string().replace("'", "\\'")
+        // Create a Document node; content only ever travels as query parameters
+        let query = falkor_query(
+            &[
+                ("id", json!(document_id)),
+                ("text", json!(truncate_chars(text, 1000))), // Limit text size
+                ("metadata", json!(metadata.unwrap_or(json!({})).to_string())),
+            ],
+            "CREATE (d:Document {id: $id, text: $text, metadata: $metadata, created_at: timestamp()})",
         );
 
-        let _: String = redis::cmd("GRAPH.QUERY")
+        let _: redis::Value = redis::cmd("GRAPH.QUERY")
             .arg(&self.graph_name)
             .arg(&query)
             .query_async(&mut con)
@@ -158,6 +511,126 @@ impl GraphStore for FalkorDbStore {
 
         Ok(())
     }
+
+    async fn ingest_document(&self, document_id: &str, document: &ExtractedDocument) -> Result<()> {
+        self.ingest(document_id, &document.text(), document.metadata_value()).await?;
+
+        let sections = &document.sections;
+        if sections.iter().all(|s| s.metadata.is_empty()) {
+            return Ok(());
+        }
+
+        let mut con = self.client.get_multiplexed_async_connection()
+            .await
+            .context("Failed to get Redis connection")?;
+
+        // Attach each section (e.g. a PDF page) to the document with its own metadata
+        let parts = external_parts(sections);
+        for (idx, (section, part)) in sections.iter().zip(&parts).enumerate() {
+            let mut params = vec![
+                ("id", json!(document_id)),
+                ("index", json!(idx)),
+                ("text", json!(truncate_chars(&section.text, 1000))), // Limit text size
+                ("metadata", json!(serde_json::Value::Object(section.metadata.clone()).to_string())),
+            ];
+            let query = match part {
+                Some(part) => {
+                    params.extend([
+                        ("id_namespace", json!(part.namespace)),
+                        ("external_id", json!(part.external_id)),
+                        ("part", json!(part.part)),
+                        ("records", json!(section.records.len())),
+                    ]);
+                    falkor_query(&params, MERGE_SECTION)
+                }
+                None => falkor_query(&params, CREATE_SECTION),
+            };
+
+            let _: redis::Value = redis::cmd("GRAPH.QUERY")
+                .arg(&self.graph_name)
+                .arg(&query)
+                .query_async(&mut con)
+                .await
+                .context("Failed to execute FalkorDB query")?;
+
+            // Rows of tabular sections become records of their own
+            if !section.records.is_empty() {
+                let query = falkor_query(
+                    &[
+                        ("id", json!(document_id)),
+                        ("index", json!(idx)),
+                        ("rows", json!(record_rows(section))),
+                    ],
+                    MERGE_RECORDS,
+                );
+
+                let _: redis::Value = redis::cmd("GRAPH.QUERY")
+                    .arg(&self.graph_name)
+                    .arg(&query)
+                    .query_async(&mut con)
+                    .await
+                    .context("Failed to execute FalkorDB query")?;
+            }
+        }
+
+        for (namespace, external_ids) in distinct_external_ids(&parts) {
+            let query = falkor_query(
+                &[
+                    ("id", json!(document_id)),
+                    ("id_namespace", json!(namespace)),
+                    ("external_ids", json!(external_ids)),
+                ],
+                DELETE_STALE_SECTIONS,
+            );
+            let _: redis::Value = redis::cmd("GRAPH.QUERY")
+                .arg(&self.graph_name)
+                .arg(&query)
+                .query_async(&mut con)
+                .await
+                .context("Failed to execute FalkorDB query")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A FalkorDB query with parameters, `CYPHER name=value ... <query>`, so that values
+/// are never spliced into the query itself
+pub fn falkor_query(params: &[(&str, serde_json::Value)], query: &str) -> String {
+    let mut header = String::from("CYPHER");
+    for (name, value) in params {
+        header.push(' ');
+        header.push_str(name);
+        header.push('=');
+        header.push_str(&cypher_literal(value));
+    }
+    format!("{} {}", header, query)
+}
+
+/// A JSON value written as a Cypher literal
+fn cypher_literal(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => "null".to_string(),
+        serde_json::Value::Bool(b) => b.to_string(),
+        serde_json::Value::Number(n) => n.to_string(),
+        // Backslashes are escaped first so that they cannot escape the closing quote
+        serde_json::Value::String(s) => format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")),
+        serde_json::Value::Array(items) => {
+            format!("[{}]", items.iter().map(cypher_literal).collect::<Vec<_>>().join(", "))
+        }
+        serde_json::Value::Object(map) => format!(
+            "{{{}}}",
+            map.iter()
+                .map(|(key, value)| format!("`{}`: {}", key.replace('`', "``"), cypher_literal(value)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// The first `max` characters of `text`
+fn truncate_chars(text: &str, max: usize) -> String {
+    text.chars().take(max).collect()
 }
 
 /// Graphiti Store (Python-based using PyO3)
@@ -3,4 +3,5 @@
 pub mod prelude;
 
 pub mod documents;
+pub mod embedding_caches;
 pub mod users;
@@ -1,4 +1,5 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.19
 
 pub use super::documents::Entity as Documents;
+pub use super::embedding_caches::Entity as EmbeddingCaches;
 pub use super::users::Entity as Users;
@@ -1,5 +1,6 @@
 use sea_orm::entity::prelude::*;
-pub use super::_entities::documents::{ActiveModel, Model, Entity};
+use sea_orm::QueryOrder;
+pub use super::_entities::documents::{ActiveModel, Column, Model, Entity};
 pub type Documents = Entity;
 
 #[async_trait::async_trait]
@@ -25,4 +26,17 @@ impl Model {}
 impl ActiveModel {}
 
 // implement your custom finders, selectors oriented logic here
-impl Entity {}
+impl Entity {
+    /// Documents created from the files embedded in a document (archive entries, email
+    /// attachments), oldest first
+    pub async fn find_children<C: ConnectionTrait>(
+        db: &C,
+        parent_id: i32,
+    ) -> Result<Vec<Model>, DbErr> {
+        Self::find()
+            .filter(Column::ParentId.eq(parent_id))
+            .order_by_asc(Column::Id)
+            .all(db)
+            .await
+    }
+}
@@ -0,0 +1,58 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::OnConflict;
+pub use super::_entities::embedding_caches::{ActiveModel, Column, Model, Entity};
+pub type EmbeddingCaches = Entity;
+
+/// Rows looked up or written per query, well under SQLite's bound parameter limit
+const BATCH_SIZE: usize = 200;
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {}
+
+// implement your read-oriented logic here
+impl Model {}
+
+// implement your write-oriented logic here
+impl ActiveModel {}
+
+// implement your custom finders, selectors oriented logic here
+impl Entity {
+    /// Cached embeddings of `model` for the given content hashes; hashes not in the
+    /// cache are left out
+    pub async fn find_by_hashes<C: ConnectionTrait>(
+        db: &C,
+        model: &str,
+        content_hashes: &[String],
+    ) -> Result<Vec<Model>, DbErr> {
+        let mut found = Vec::new();
+        for hashes in content_hashes.chunks(BATCH_SIZE) {
+            found.extend(
+                Self::find()
+                    .filter(Column::Model.eq(model))
+                    .filter(Column::ContentHash.is_in(hashes.iter().cloned()))
+                    .all(db)
+                    .await?,
+            );
+        }
+        Ok(found)
+    }
+
+    /// Insert embeddings, keeping the ones already cached for the same model and hash
+    pub async fn insert_missing<C: ConnectionTrait>(
+        db: &C,
+        rows: Vec<ActiveModel>,
+    ) -> Result<(), DbErr> {
+        for batch in rows.chunks(BATCH_SIZE) {
+            Self::insert_many(batch.to_vec())
+                .on_conflict(
+                    OnConflict::columns([Column::Model, Column::ContentHash])
+                        .do_nothing()
+                        .to_owned(),
+                )
+                .do_nothing()
+                .exec_without_returning(db)
+                .await?;
+        }
+        Ok(())
+    }
+}
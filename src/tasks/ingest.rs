@@ -1,8 +1,10 @@
 use loco_rs::prelude::*;
-use crate::ingestion::{IngestionTarget, GraphDbType, pipeline::IngestionPipeline};
+use crate::ingestion::{IngestionTarget, GraphDbType, ExtractOptions, chunking::ChunkingOptions, embeddings::Embedder, extractors::ExtractorRegistry, pipeline::IngestionPipeline, stores::ChromaConfig};
 use crate::models::_entities::documents;
+use crate::workers::ingest::{IngestWorker, IngestWorkerArgs};
 use sea_orm::{Set, ActiveModelTrait};
 use std::io::{self, Read};
+use std::sync::Arc;
 
 pub struct IngestTask;
 
@@ -15,7 +17,7 @@ impl Task for IngestTask {
         }
     }
 
-    async fn run(&self, app_context: &AppContext, vars: &task::Vars) -> Result<()> {
+    async fn run(&self, app_context: &AppContext, _vars: &task::Vars) -> Result<()> {
         // Parse arguments - Vars is a HashMap-like structure
         let cli_args: Vec<String> = std::env::args().collect();
         
@@ -24,37 +26,88 @@ impl Task for IngestTask {
         let mut stdin = false;
         let mut target = IngestionTarget::Both;
         let mut graph_db: Option<GraphDbType> = None;
+        let mut options = ExtractOptions::default();
         
         let mut i = 0;
         while i < cli_args.len() {
             match cli_args[i].as_str() {
-                "--file" | "-f" => {
-                    if i + 1 < cli_args.len() {
-                        file_path = Some(cli_args[i + 1].clone());
-                        i += 1;
-                    }
+                "--file" | "-f" if i + 1 < cli_args.len() => {
+                    file_path = Some(cli_args[i + 1].clone());
+                    i += 1;
                 }
                 "--stdin" => {
                     stdin = true;
                 }
-                "--target" | "-t" => {
-                    if i + 1 < cli_args.len() {
-                        target = serde_json::from_str(&format!("\"{}\"", cli_args[i + 1]))
-                            .unwrap_or(IngestionTarget::Both);
-                        i += 1;
-                    }
+                "--target" | "-t" if i + 1 < cli_args.len() => {
+                    target = serde_json::from_str(&format!("\"{}\"", cli_args[i + 1]))
+                        .unwrap_or(IngestionTarget::Both);
+                    i += 1;
                 }
-                "--graph-db" | "-g" => {
-                    if i + 1 < cli_args.len() {
-                        graph_db = serde_json::from_str(&format!("\"{}\"", cli_args[i + 1])).ok();
-                        i += 1;
-                    }
+                "--graph-db" | "-g" if i + 1 < cli_args.len() => {
+                    graph_db = serde_json::from_str(&format!("\"{}\"", cli_args[i + 1])).ok();
+                    i += 1;
+                }
+                "--ocr-lang" if i + 1 < cli_args.len() => {
+                    options.ocr_languages = ExtractOptions::parse_languages(&cli_args[i + 1]);
+                    i += 1;
+                }
+                "--drop-code-blocks" => {
+                    options.drop_code_blocks = true;
+                }
+                "--include-outputs" => {
+                    options.include_outputs = true;
+                }
+                "--text-field" if i + 1 < cli_args.len() => {
+                    options.text_field = Some(cli_args[i + 1].clone());
+                    i += 1;
+                }
+                "--metadata-fields" if i + 1 < cli_args.len() => {
+                    options.metadata_fields = ExtractOptions::parse_fields(&cli_args[i + 1]);
+                    i += 1;
+                }
+                "--id-field" if i + 1 < cli_args.len() => {
+                    options.id_field = Some(cli_args[i + 1].clone());
+                    i += 1;
+                }
+                "--id-namespace" if i + 1 < cli_args.len() => {
+                    options.id_namespace = Some(cli_args[i + 1].clone());
+                    i += 1;
+                }
+                "--encoding" if i + 1 < cli_args.len() => {
+                    options.encoding = Some(cli_args[i + 1].clone());
+                    i += 1;
+                }
+                "--chunk-strategy" if i + 1 < cli_args.len() => {
+                    options.chunk_strategy = Some(parse_arg("--chunk-strategy", &cli_args[i + 1])?);
+                    i += 1;
+                }
+                "--chunk-size" if i + 1 < cli_args.len() => {
+                    options.chunk_size = Some(cli_args[i + 1].parse().map_err(|_| {
+                        Error::string(&format!("Invalid --chunk-size: {}", cli_args[i + 1]))
+                    })?);
+                    i += 1;
+                }
+                "--chunk-overlap" if i + 1 < cli_args.len() => {
+                    options.chunk_overlap = Some(cli_args[i + 1].parse().map_err(|_| {
+                        Error::string(&format!("Invalid --chunk-overlap: {}", cli_args[i + 1]))
+                    })?);
+                    i += 1;
+                }
+                "--chunk-unit" if i + 1 < cli_args.len() => {
+                    options.chunk_unit = Some(parse_arg("--chunk-unit", &cli_args[i + 1])?);
+                    i += 1;
                 }
                 _ => {}
             }
             i += 1;
         }
 
+        let chunking = app_context.shared_store.get::<ChunkingOptions>().unwrap_or_default();
+        chunking
+            .with_overrides(&options)
+            .validate()
+            .map_err(|e| Error::string(&e.to_string()))?;
+
         // Get text from stdin or file
         let (text, filename) = if stdin {
             let mut buffer = String::new();
@@ -82,55 +135,78 @@ impl Task for IngestTask {
         println!("Created document record with ID: {}", doc.id);
 
         // Get configuration from environment
-        let chroma_url = std::env::var("CHROMA_URL").ok();
-        let graph_config = if let Some(ref db_type) = graph_db {
-            Some(match db_type {
-                GraphDbType::Neo4j => {
-                    serde_json::json!({
-                        "uri": std::env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".to_string()),
-                        "user": std::env::var("NEO4J_USER").unwrap_or_else(|_| "neo4j".to_string()),
-                        "password": std::env::var("NEO4J_PASSWORD").unwrap_or_else(|_| "password".to_string()),
-                    })
-                }
-                GraphDbType::Falkordb => {
-                    serde_json::json!({
-                        "uri": std::env::var("FALKORDB_URI").unwrap_or_else(|_| "redis://localhost:6379".to_string()),
-                        "graph_name": std::env::var("FALKORDB_GRAPH").unwrap_or_else(|_| "akashic".to_string()),
-                    })
-                }
-                GraphDbType::Graphiti => {
-                    serde_json::json!({
-                        "script_path": std::env::var("GRAPHITI_SCRIPT").unwrap_or_else(|_| "graphiti_ingest.py".to_string()),
-                    })
-                }
-            })
-        } else {
-            None
-        };
+        let chroma = ChromaConfig::from_env().map_err(|e| Error::string(&format!("Invalid ChromaDB configuration: {}", e)))?;
+        let graph_config = graph_db.as_ref().map(|db_type| match db_type {
+            GraphDbType::Neo4j => {
+                serde_json::json!({
+                    "uri": std::env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".to_string()),
+                    "user": std::env::var("NEO4J_USER").unwrap_or_else(|_| "neo4j".to_string()),
+                    "password": std::env::var("NEO4J_PASSWORD").unwrap_or_else(|_| "password".to_string()),
+                })
+            }
+            GraphDbType::Falkordb => {
+                serde_json::json!({
+                    "uri": std::env::var("FALKORDB_URI").unwrap_or_else(|_| "redis://localhost:6379".to_string()),
+                    "graph_name": std::env::var("FALKORDB_GRAPH").unwrap_or_else(|_| "akashic".to_string()),
+                })
+            }
+            GraphDbType::Graphiti => {
+                serde_json::json!({
+                    "script_path": std::env::var("GRAPHITI_SCRIPT").unwrap_or_else(|_| "graphiti_ingest.py".to_string()),
+                })
+            }
+        });
 
         // Create pipeline
-        let pipeline = IngestionPipeline::new(
+        let mut pipeline = IngestionPipeline::new(
             app_context.db.clone(),
-            chroma_url.as_deref(),
+            chroma,
             graph_db,
             graph_config,
         )
         .await
-        .map_err(|e| Error::string(&format!("Failed to create pipeline: {}", e)))?;
+        .map_err(|e| Error::string(&format!("Failed to create pipeline: {}", e)))?
+        .with_extractors(
+            app_context
+                .shared_store
+                .get::<Arc<ExtractorRegistry>>()
+                .unwrap_or_else(ExtractorRegistry::builtin),
+        )
+        .with_chunking(chunking);
+        if let Some(embedder) = app_context.shared_store.get::<Arc<dyn Embedder>>() {
+            pipeline = pipeline.with_embedder(embedder);
+        }
 
         // Process
         println!("Starting ingestion...");
         let result = if let Some(text_content) = text {
-            pipeline.process_text(doc.id, &text_content, target).await
+            pipeline.process_text(doc.id, &text_content, target.clone(), &options).await.map(|()| Vec::new())
         } else {
-            pipeline.process_file(doc.id, &filename, target).await
+            pipeline.process_file(doc.id, &filename, target.clone(), &options).await
         };
 
         match result {
-            Ok(_) => {
+            Ok(children) if children.is_empty() => {
                 println!("✓ Ingestion completed successfully for document {}", doc.id);
                 Ok(())
             }
+            Ok(children) => {
+                // Embedded files (archive entries, attachments) go through the worker
+                for child in children {
+                    IngestWorker::perform_later(app_context, IngestWorkerArgs {
+                        document_id: child.document_id,
+                        file_path: Some(child.file_path),
+                        text: None,
+                        target: format!("{:?}", target),
+                        graph_db: doc.graph_db.clone(),
+                        options: child.options,
+                    })
+                    .await?;
+                    println!("  Queued embedded file as document {}", child.document_id);
+                }
+                println!("✓ Document {} extracted, embedded files queued for ingestion", doc.id);
+                Ok(())
+            }
             Err(e) => {
                 eprintln!("✗ Ingestion failed: {}", e);
                 pipeline.handle_error(doc.id, &e.to_string()).await
@@ -140,3 +216,9 @@ impl Task for IngestTask {
         }
     }
 }
+
+/// An argument naming one of an enum's variants, e.g. `--chunk-unit tokens`
+fn parse_arg<T: serde::de::DeserializeOwned>(flag: &str, value: &str) -> Result<T> {
+    serde_json::from_value(serde_json::Value::String(value.to_string()))
+        .map_err(|_| Error::string(&format!("Invalid {}: {}", flag, value)))
+}
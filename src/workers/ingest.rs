@@ -1,8 +1,8 @@
+use loco_rs::config::WorkerMode;
 use loco_rs::prelude::*;
 use serde::{Deserialize, Serialize};
-use crate::ingestion::{IngestionTarget, GraphDbType, pipeline::IngestionPipeline};
-use crate::models::_entities::documents::Entity as Documents;
-use sea_orm::EntityTrait;
+use std::sync::Arc;
+use crate::ingestion::{IngestionTarget, GraphDbType, ExtractOptions, chunking::ChunkingOptions, embeddings::Embedder, extractors::ExtractorRegistry, pipeline::IngestionPipeline, stores::ChromaConfig};
 
 #[derive(Deserialize, Debug, Serialize)]
 pub struct IngestWorkerArgs {
@@ -11,6 +11,8 @@ pub struct IngestWorkerArgs {
     pub text: Option<String>,
     pub target: String,
     pub graph_db: Option<String>,
+    #[serde(default)]
+    pub options: ExtractOptions,
 }
 
 pub struct IngestWorker {
@@ -32,65 +34,99 @@ impl BackgroundWorker<IngestWorkerArgs> for IngestWorker {
     async fn perform(&self, args: IngestWorkerArgs) -> Result<()> {
         tracing::info!("Processing ingestion for document {}", args.document_id);
 
-        // Parse target and graph_db
-        let target: IngestionTarget = serde_json::from_str(&format!("\"{}\"", args.target))
-            .map_err(|e| Error::BadRequest(e.to_string()))?;
+        // Parse target and graph_db; callers pass their `Debug` names, e.g. "Vector"
+        let target: IngestionTarget =
+            serde_json::from_str(&format!("\"{}\"", args.target.to_lowercase()))
+                .map_err(|e| Error::BadRequest(e.to_string()))?;
         
         let graph_db: Option<GraphDbType> = args.graph_db
-            .and_then(|g| serde_json::from_str(&format!("\"{}\"", g)).ok());
+            .as_deref()
+            .and_then(|g| serde_json::from_str(&format!("\"{}\"", g.to_lowercase())).ok());
 
         // Get configuration from environment or config
-        let chroma_url = std::env::var("CHROMA_URL").ok();
-        let graph_config = if let Some(ref db_type) = graph_db {
-            Some(match db_type {
-                GraphDbType::Neo4j => {
-                    serde_json::json!({
-                        "uri": std::env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".to_string()),
-                        "user": std::env::var("NEO4J_USER").unwrap_or_else(|_| "neo4j".to_string()),
-                        "password": std::env::var("NEO4J_PASSWORD").unwrap_or_else(|_| "password".to_string()),
-                    })
-                }
-                GraphDbType::Falkordb => {
-                    serde_json::json!({
-                        "uri": std::env::var("FALKORDB_URI").unwrap_or_else(|_| "redis://localhost:6379".to_string()),
-                        "graph_name": std::env::var("FALKORDB_GRAPH").unwrap_or_else(|_| "akashic".to_string()),
-                    })
-                }
-                GraphDbType::Graphiti => {
-                    serde_json::json!({
-                        "script_path": std::env::var("GRAPHITI_SCRIPT").unwrap_or_else(|_| "graphiti_ingest.py".to_string()),
-                    })
-                }
-            })
-        } else {
-            None
-        };
+        let chroma = ChromaConfig::from_env().map_err(|e| Error::BadRequest(e.to_string()))?;
+        let graph_config = graph_db.as_ref().map(|db_type| match db_type {
+            GraphDbType::Neo4j => {
+                serde_json::json!({
+                    "uri": std::env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".to_string()),
+                    "user": std::env::var("NEO4J_USER").unwrap_or_else(|_| "neo4j".to_string()),
+                    "password": std::env::var("NEO4J_PASSWORD").unwrap_or_else(|_| "password".to_string()),
+                })
+            }
+            GraphDbType::Falkordb => {
+                serde_json::json!({
+                    "uri": std::env::var("FALKORDB_URI").unwrap_or_else(|_| "redis://localhost:6379".to_string()),
+                    "graph_name": std::env::var("FALKORDB_GRAPH").unwrap_or_else(|_| "akashic".to_string()),
+                })
+            }
+            GraphDbType::Graphiti => {
+                serde_json::json!({
+                    "script_path": std::env::var("GRAPHITI_SCRIPT").unwrap_or_else(|_| "graphiti_ingest.py".to_string()),
+                })
+            }
+        });
 
         // Create pipeline
-        let pipeline = IngestionPipeline::new(
+        let mut pipeline = IngestionPipeline::new(
             self.ctx.db.clone(),
-            chroma_url.as_deref(),
+            chroma,
             graph_db,
             graph_config,
         )
         .await
-        .map_err(|e| Error::BadRequest(e.to_string()))?;
+        .map_err(|e| Error::BadRequest(e.to_string()))?
+        .with_extractors(
+            self.ctx
+                .shared_store
+                .get::<Arc<ExtractorRegistry>>()
+                .unwrap_or_else(ExtractorRegistry::builtin),
+        )
+        .with_chunking(self.ctx.shared_store.get::<ChunkingOptions>().unwrap_or_default());
+        if let Some(embedder) = self.ctx.shared_store.get::<Arc<dyn Embedder>>() {
+            pipeline = pipeline.with_embedder(embedder);
+        }
 
         // Process based on whether we have a file or text
-        let result = if let Some(file_path) = args.file_path {
-            pipeline.process_file(args.document_id, &file_path, target).await
-        } else if let Some(text) = args.text {
-            pipeline.process_text(args.document_id, &text, target).await
+        let result = if let Some(file_path) = &args.file_path {
+            pipeline.process_file(args.document_id, file_path, target, &args.options).await
+        } else if let Some(text) = &args.text {
+            pipeline.process_text(args.document_id, text, target, &args.options).await.map(|()| Vec::new())
         } else {
             Err(anyhow::anyhow!("Neither file_path nor text provided"))
         };
 
         // Handle errors
-        if let Err(e) = result {
-            tracing::error!("Ingestion failed for document {}: {}", args.document_id, e);
-            pipeline.handle_error(args.document_id, &e.to_string()).await
-                .map_err(|e| Error::BadRequest(e.to_string()))?;
-            return Err(Error::BadRequest(e.to_string()));
+        let children = match result {
+            Ok(children) => children,
+            Err(e) => {
+                tracing::error!("Ingestion failed for document {}: {}", args.document_id, e);
+                pipeline.handle_error(args.document_id, &e.to_string()).await
+                    .map_err(|e| Error::BadRequest(e.to_string()))?;
+                return Err(Error::BadRequest(e.to_string()));
+            }
+        };
+
+        // Files unpacked from this one (archive entries, attachments) are ingested as
+        // jobs of their own
+        for child in children {
+            let queued = Self::perform_later(&self.ctx, IngestWorkerArgs {
+                document_id: child.document_id,
+                file_path: Some(child.file_path),
+                text: None,
+                target: args.target.clone(),
+                graph_db: args.graph_db.clone(),
+                options: child.options,
+            })
+            .await;
+
+            if let Err(e) = queued {
+                tracing::error!("Ingestion failed for document {}: {}", child.document_id, e);
+                // Blocking workers run the child right away, and it records its own failure
+                if self.ctx.config.workers.mode != WorkerMode::ForegroundBlocking {
+                    pipeline.handle_error(child.document_id, &e.to_string()).await
+                        .map_err(|e| Error::BadRequest(e.to_string()))?;
+                }
+            }
         }
 
         tracing::info!("Successfully processed document {}", args.document_id);
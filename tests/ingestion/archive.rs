@@ -0,0 +1,242 @@
+use super::write_zip;
+use akashic::{
+    app::App,
+    ingestion::{
+        extractors::{ArchiveExtractor, MAX_ARCHIVE_ENTRIES},
+        pipeline::IngestionPipeline,
+        ExtractOptions, Extractor,
+    },
+    models::documents::{self, Documents},
+    workers::ingest::{IngestWorker, IngestWorkerArgs},
+};
+use loco_rs::{bgworker::BackgroundWorker, testing::prelude::*};
+use sea_orm::{ActiveModelTrait, EntityTrait, Set};
+use serial_test::serial;
+
+fn summary(attachments: &[akashic::ingestion::Attachment]) -> Vec<(String, String, String)> {
+    attachments
+        .iter()
+        .map(|a| {
+            (
+                a.filename.clone(),
+                a.metadata["archive_path"].as_str().unwrap().to_string(),
+                std::fs::read_to_string(&a.path).unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn can_unpack_zip_entries() {
+    let file = write_zip::<&[u8]>(
+        ".zip",
+        &[
+            ("reports/", b""),
+            ("reports/q1.md", b"# Q1\n\nRevenue up."),
+            ("reports/.DS_Store", b"\0\0"),
+            ("__MACOSX/reports/._q1.md", b"\0\0"),
+            ("notes.txt", b"Ship it."),
+        ],
+    );
+    let dir = tempfile::tempdir().unwrap();
+
+    let attachments = ArchiveExtractor
+        .extract_attachments(file.path().to_str().unwrap(), dir.path())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        summary(&attachments),
+        vec![
+            (
+                "q1.md".to_string(),
+                "reports/q1.md".to_string(),
+                "# Q1\n\nRevenue up.".to_string()
+            ),
+            (
+                "notes.txt".to_string(),
+                "notes.txt".to_string(),
+                "Ship it.".to_string()
+            ),
+        ]
+    );
+    assert!(attachments.iter().all(|a| a.path.starts_with(dir.path())));
+
+    // Archives have no text of their own
+    let sections = ArchiveExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await
+        .unwrap()
+        .sections;
+    assert!(sections.is_empty());
+}
+
+#[tokio::test]
+async fn can_unpack_tar_gz_entries() {
+    let file = tempfile::Builder::new()
+        .suffix(".tar.gz")
+        .tempfile()
+        .unwrap();
+    let encoder =
+        flate2::write::GzEncoder::new(file.reopen().unwrap(), flate2::Compression::default());
+    let mut tar = tar::Builder::new(encoder);
+    for (name, content) in [
+        ("./docs/readme.md", &b"# Readme"[..]),
+        ("./docs/.hidden.txt", b"secret"),
+    ] {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        tar.append_data(&mut header, name, content).unwrap();
+    }
+    tar.into_inner().unwrap().finish().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+
+    let attachments = ArchiveExtractor
+        .extract_attachments(file.path().to_str().unwrap(), dir.path())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        summary(&attachments),
+        vec![(
+            "readme.md".to_string(),
+            "docs/readme.md".to_string(),
+            "# Readme".to_string()
+        )]
+    );
+}
+
+#[tokio::test]
+async fn rejects_archives_with_too_many_entries() {
+    let names: Vec<String> = (0..=MAX_ARCHIVE_ENTRIES)
+        .map(|i| format!("{}.txt", i))
+        .collect();
+    let entries: Vec<(&str, &[u8])> = names.iter().map(|n| (n.as_str(), &b""[..])).collect();
+    let file = write_zip(".zip", &entries);
+    let dir = tempfile::tempdir().unwrap();
+
+    let error = ArchiveExtractor
+        .extract_attachments(file.path().to_str().unwrap(), dir.path())
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("more than"));
+}
+
+#[tokio::test]
+#[serial]
+async fn ingests_archive_entries_as_child_documents() {
+    let boot = boot_test::<App>().await.unwrap();
+    let db = boot.app_context.db.clone();
+    let inner = write_zip(".zip", &[("q2.md", "# Q2")]);
+    let inner = std::fs::read(inner.path()).unwrap();
+    let file = write_zip::<&[u8]>(
+        ".zip",
+        &[
+            ("reports/q1.md", b"# Q1\n\nRevenue up."),
+            ("reports/broken.pdf", b"not a pdf"),
+            ("logo.bin", b"\0\x01\x02\x03"),
+            ("reports/older.zip", &inner),
+        ],
+    );
+
+    let parent = documents::ActiveModel {
+        filename: Set(Some("reports.zip".to_string())),
+        status: Set(Some("queued".to_string())),
+        ingestion_type: Set(Some("Vector".to_string())),
+        ..Default::default()
+    }
+    .insert(&db)
+    .await
+    .unwrap();
+
+    IngestWorker::perform_later(
+        &boot.app_context,
+        IngestWorkerArgs {
+            document_id: parent.id,
+            file_path: Some(file.path().to_str().unwrap().to_string()),
+            text: None,
+            target: "Vector".to_string(),
+            graph_db: None,
+            options: ExtractOptions::default(),
+        },
+    )
+    .await
+    .unwrap();
+
+    // Entries no extractor reads, and archives inside the archive, are not ingested
+    let children = Documents::find_children(&db, parent.id).await.unwrap();
+    let summary: Vec<_> = children
+        .iter()
+        .map(|c| {
+            (
+                c.filename.as_deref().unwrap(),
+                c.status.as_deref().unwrap(),
+                c.ingestion_type.as_deref().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("q1.md", "completed", "Vector"),
+            ("broken.pdf", "failed", "Vector"),
+        ]
+    );
+
+    let parent = Documents::find_by_id(parent.id)
+        .one(&db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(parent.status.as_deref(), Some("completed"));
+    assert_eq!(parent.progress, Some(100));
+    assert_eq!(
+        parent.error_message.as_deref(),
+        Some("Failed to ingest 1 of 2 embedded files: broken.pdf")
+    );
+
+    // Unpacked entries are removed once every child has finished
+    let unpacked = std::env::temp_dir()
+        .join("akashic-attachments")
+        .join(parent.id.to_string());
+    assert!(!unpacked.exists());
+}
+
+#[tokio::test]
+#[serial]
+async fn keeps_a_finished_parent_finished() {
+    let boot = boot_test::<App>().await.unwrap();
+    let db = boot.app_context.db.clone();
+    let document = |status: &str, parent_id: Option<i32>| documents::ActiveModel {
+        filename: Set(Some("entry.md".to_string())),
+        status: Set(Some(status.to_string())),
+        progress: Set(Some(100)),
+        parent_id: Set(parent_id),
+        ..Default::default()
+    };
+    let parent = document("completed", None).insert(&db).await.unwrap();
+    // A sibling that still looked unfinished to a child finishing at the same time
+    document("processing", Some(parent.id))
+        .insert(&db)
+        .await
+        .unwrap();
+    let child = document("processing", Some(parent.id))
+        .insert(&db)
+        .await
+        .unwrap();
+
+    let pipeline = IngestionPipeline::new(db.clone(), None, None, None)
+        .await
+        .unwrap();
+    pipeline.handle_error(child.id, "late").await.unwrap();
+
+    let parent = Documents::find_by_id(parent.id)
+        .one(&db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(parent.status.as_deref(), Some("completed"));
+    assert_eq!(parent.progress, Some(100));
+}
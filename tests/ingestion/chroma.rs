@@ -0,0 +1,297 @@
+use akashic::ingestion::{
+    embeddings::content_hash,
+    stores::{ChromaApiVersion, ChromaConfig, ChromaDbStore},
+    ExtractedDocument, Section, VectorStore,
+};
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{Method, StatusCode, Uri},
+    Json, Router,
+};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+
+/// A Chroma server speaking one API version, recording `METHOD path?query` and body
+/// of every request
+struct MockChroma {
+    version: &'static str,
+    fail_create: bool,
+    requests: Mutex<Vec<(String, Value)>>,
+}
+
+impl MockChroma {
+    fn requests(&self) -> Vec<(String, Value)> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+async fn serve(version: &'static str, fail_create: bool) -> (String, Arc<MockChroma>) {
+    async fn handle(
+        State(mock): State<Arc<MockChroma>>,
+        method: Method,
+        uri: Uri,
+        body: Bytes,
+    ) -> (StatusCode, Json<Value>) {
+        let body = serde_json::from_slice(&body).unwrap_or(Value::Null);
+        mock.requests
+            .lock()
+            .unwrap()
+            .push((format!("{} {}", method, uri), body));
+
+        let prefix = format!("/api/{}/", mock.version);
+        let path = uri.path();
+        if !path.starts_with(&prefix) {
+            // Newer servers answer the v1 API with 410 Gone
+            return (StatusCode::GONE, Json(json!({"error": "Unimplemented"})));
+        }
+        if path.ends_with("/heartbeat") {
+            (StatusCode::OK, Json(json!({"nanosecond heartbeat": 1})))
+        } else if path.ends_with("/collections") && mock.fail_create {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "database not found"})),
+            )
+        } else if path.ends_with("/collections") {
+            (
+                StatusCode::OK,
+                Json(json!({"id": "3f2c0d4e-collection", "name": "akashic"})),
+            )
+        } else {
+            (StatusCode::OK, Json(json!(true)))
+        }
+    }
+
+    let mock = Arc::new(MockChroma {
+        version,
+        fail_create,
+        requests: Mutex::default(),
+    });
+    let router = Router::new().fallback(handle).with_state(mock.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    (url, mock)
+}
+
+fn document() -> ExtractedDocument {
+    ExtractedDocument::new(vec![
+        Section::new("First chunk"),
+        Section::new("Second chunk"),
+    ])
+}
+
+#[tokio::test]
+async fn can_use_v2_api_with_tenant_and_database() {
+    let (url, mock) = serve("v2", false).await;
+    let config = ChromaConfig {
+        tenant: "acme".to_string(),
+        database: "docs".to_string(),
+        ..ChromaConfig::new(&url)
+    };
+
+    let store = ChromaDbStore::new(&config).await.unwrap();
+    assert_eq!(store.api_version(), ChromaApiVersion::V2);
+    assert_eq!(store.collection_id(), "3f2c0d4e-collection");
+    store.ingest_document("7", &document()).await.unwrap();
+
+    let requests = mock.requests();
+    let paths: Vec<&str> = requests.iter().map(|(path, _)| path.as_str()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "GET /api/v2/heartbeat",
+            "POST /api/v2/tenants/acme/databases/docs/collections",
+            "POST /api/v2/tenants/acme/databases/docs/collections/3f2c0d4e-collection/upsert",
+        ]
+    );
+    assert_eq!(
+        requests[1].1,
+        json!({"name": "akashic", "get_or_create": true})
+    );
+    assert_eq!(requests[2].1["ids"], json!(["7_0", "7_1"]));
+}
+
+#[tokio::test]
+async fn percent_encodes_tenant_and_database() {
+    let (url, mock) = serve("v2", false).await;
+    let config = ChromaConfig {
+        tenant: "acme corp".to_string(),
+        database: "docs/2024?v=1".to_string(),
+        api_version: ChromaApiVersion::V2,
+        ..ChromaConfig::new(&url)
+    };
+
+    ChromaDbStore::new(&config).await.unwrap();
+    assert_eq!(
+        mock.requests()[0].0,
+        "POST /api/v2/tenants/acme%20corp/databases/docs%2F2024%3Fv=1/collections"
+    );
+}
+
+#[tokio::test]
+async fn falls_back_to_v1_api() {
+    let (url, mock) = serve("v1", false).await;
+
+    let store = ChromaDbStore::new(&ChromaConfig::new(&url)).await.unwrap();
+    assert_eq!(store.api_version(), ChromaApiVersion::V1);
+    store.ingest_document("7", &document()).await.unwrap();
+
+    let paths: Vec<String> = mock.requests().into_iter().map(|(path, _)| path).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "GET /api/v2/heartbeat",
+            "GET /api/v1/heartbeat",
+            "POST /api/v1/collections?tenant=default_tenant&database=default_database",
+            "POST /api/v1/collections/3f2c0d4e-collection/upsert",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn skips_detection_when_version_is_configured() {
+    let (url, mock) = serve("v2", false).await;
+    let config = ChromaConfig {
+        collection: "papers".to_string(),
+        api_version: ChromaApiVersion::V2,
+        ..ChromaConfig::new(&url)
+    };
+
+    ChromaDbStore::new(&config).await.unwrap();
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(
+        requests[0].0,
+        "POST /api/v2/tenants/default_tenant/databases/default_database/collections"
+    );
+    assert_eq!(requests[0].1["name"], "papers");
+}
+
+#[tokio::test]
+async fn reports_failure_to_create_collection() {
+    let (url, _) = serve("v2", true).await;
+
+    let error = ChromaDbStore::new(&ChromaConfig::new(&url))
+        .await
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(error.contains("Failed to create ChromaDB collection akashic (500"));
+    assert!(error.contains("database not found"));
+}
+
+#[tokio::test]
+async fn reports_unreachable_server() {
+    // Nothing listens on a port that was just released
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    drop(listener);
+
+    let error = ChromaDbStore::new(&ChromaConfig::new(&url))
+        .await
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(error.starts_with("Failed to reach ChromaDB at"));
+}
+
+#[tokio::test]
+async fn namespaces_external_ids_and_removes_stale_parts() {
+    let (url, mock) = serve("v2", false).await;
+    let store = ChromaDbStore::new(&ChromaConfig::new(&url)).await.unwrap();
+    let record = |text: &str, id: &str| {
+        Section::new(text)
+            .with("external_id", id)
+            .with("id_namespace", "tickets.jsonl:id")
+    };
+    let document = ExtractedDocument::new(vec![
+        record("Ticket one", "7_0"),
+        record("continued", "7_0"),
+        record("Ticket two", "t-2"),
+        Section::new("Loose text"),
+    ]);
+    store.ingest_document("7", &document).await.unwrap();
+
+    let requests = mock.requests();
+    let upsert = &requests[2].1;
+    let scope = &content_hash("tickets.jsonl:id")[..16];
+    assert_eq!(
+        upsert["ids"],
+        json!([
+            format!("ext:{}:7_0#0", scope),
+            format!("ext:{}:7_0#1", scope),
+            format!("ext:{}:t-2#0", scope),
+            "7_3"
+        ])
+    );
+    assert_eq!(upsert["metadatas"][1]["external_part"], 1);
+
+    assert!(requests[3]
+        .0
+        .ends_with("/collections/3f2c0d4e-collection/delete"));
+    assert_eq!(
+        requests[3].1,
+        json!({"where": {"$and": [
+            {"id_namespace": {"$eq": "tickets.jsonl:id"}},
+            {"external_id": {"$in": ["7_0", "t-2"]}},
+            {"document_id": {"$ne": "7"}}
+        ]}})
+    );
+}
+
+#[tokio::test]
+async fn keeps_overlapping_external_ids_of_different_sources_apart() {
+    let (url, mock) = serve("v2", false).await;
+    let store = ChromaDbStore::new(&ChromaConfig::new(&url)).await.unwrap();
+    let document = ExtractedDocument::new(vec![
+        Section::new("Ticket")
+            .with("external_id", "1")
+            .with("id_namespace", "tickets"),
+        Section::new("Invoice")
+            .with("external_id", "1")
+            .with("id_namespace", "invoices"),
+    ]);
+    store.ingest_document("7", &document).await.unwrap();
+
+    let requests = mock.requests();
+    let ids = &requests[2].1["ids"];
+    assert_eq!(
+        ids[0],
+        format!("ext:{}:1#0", &content_hash("tickets")[..16])
+    );
+    assert_eq!(
+        ids[1],
+        format!("ext:{}:1#0", &content_hash("invoices")[..16])
+    );
+    assert_eq!(requests[2].1["metadatas"][1]["external_part"], 0);
+
+    let deletes: Vec<_> = requests[3..].iter().map(|(_, body)| body.clone()).collect();
+    assert_eq!(
+        deletes,
+        vec![
+            json!({"where": {"$and": [
+                {"id_namespace": {"$eq": "tickets"}},
+                {"external_id": {"$in": ["1"]}},
+                {"document_id": {"$ne": "7"}}
+            ]}}),
+            json!({"where": {"$and": [
+                {"id_namespace": {"$eq": "invoices"}},
+                {"external_id": {"$in": ["1"]}},
+                {"document_id": {"$ne": "7"}}
+            ]}}),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn only_deletes_for_records_with_external_ids() {
+    let (url, mock) = serve("v2", false).await;
+    let store = ChromaDbStore::new(&ChromaConfig::new(&url)).await.unwrap();
+    store.ingest_document("7", &document()).await.unwrap();
+
+    assert!(mock
+        .requests()
+        .iter()
+        .all(|(path, _)| !path.ends_with("/delete")));
+}
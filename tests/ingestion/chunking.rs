@@ -0,0 +1,203 @@
+use akashic::ingestion::{
+    chunking::{
+        create_chunker, ChunkStrategy, ChunkUnit, Chunker, ChunkingOptions, FixedSizeChunker,
+        HeadingChunker, RecursiveChunker, SemanticChunker, SentenceChunker,
+    },
+    embeddings::HashingEmbedder,
+    ExtractOptions, Section,
+};
+use serde_json::json;
+use std::sync::Arc;
+
+fn texts(chunks: &[Section]) -> Vec<&str> {
+    chunks.iter().map(|c| c.text.as_str()).collect()
+}
+
+#[tokio::test]
+async fn fixed_chunks_overlap() {
+    let sections = vec![Section::new("abcdefghij").with("page", 1)];
+
+    let chunks = FixedSizeChunker::new(4, 1, ChunkUnit::Chars)
+        .chunk(&sections)
+        .await
+        .unwrap();
+    assert_eq!(texts(&chunks), vec!["abcd", "defg", "ghij"]);
+    assert!(chunks.iter().all(|c| c.metadata["page"] == json!(1)));
+
+    let sections = vec![Section::new("One two, three four five. Six")];
+    let chunks = FixedSizeChunker::new(3, 0, ChunkUnit::Tokens)
+        .chunk(&sections)
+        .await
+        .unwrap();
+    assert_eq!(texts(&chunks), vec!["One two,", "three four five", ". Six"]);
+}
+
+#[tokio::test]
+async fn recursive_chunks_split_at_coarsest_separator() {
+    let text = "First paragraph is here.\n\nSecond one. It has two sentences.\n\nThird";
+    let chunker = RecursiveChunker::new(35, 0, ChunkUnit::Chars);
+
+    let chunks = chunker.chunk(&[Section::new(text)]).await.unwrap();
+    assert_eq!(
+        texts(&chunks),
+        vec![
+            "First paragraph is here.",
+            "Second one. It has two sentences.",
+            "Third"
+        ]
+    );
+    assert!(chunks.iter().all(|c| c.text.chars().count() <= 35));
+
+    // Words longer than a chunk fall back to fixed windows
+    assert_eq!(
+        RecursiveChunker::new(4, 0, ChunkUnit::Chars).split("abcdefghij kl"),
+        vec!["abcd", "efgh", "ij", "kl"]
+    );
+}
+
+#[tokio::test]
+async fn sentence_chunks_keep_whole_sentences() {
+    let text = "The sky is blue. Grass is green. Snow is white. Coal is black.";
+    let chunker = SentenceChunker::new(35, 17, ChunkUnit::Chars);
+
+    let chunks = chunker.chunk(&[Section::new(text)]).await.unwrap();
+    assert_eq!(
+        texts(&chunks),
+        vec![
+            "The sky is blue. Grass is green.",
+            "Grass is green. Snow is white.",
+            "Snow is white. Coal is black."
+        ]
+    );
+}
+
+#[tokio::test]
+async fn heading_chunks_never_cross_headings() {
+    let record = json!({"id": 1}).as_object().unwrap().clone();
+    let section = Section {
+        text: "Preamble.\n\n# Setup\n\nInstall it.\n\n## Install ##\n\nRun the installer. Then reboot."
+            .to_string(),
+        records: vec![record],
+        ..Section::default()
+    };
+
+    let chunks = HeadingChunker::new(30, 0, ChunkUnit::Chars)
+        .chunk(&[section])
+        .await
+        .unwrap();
+    let summary: Vec<_> = chunks
+        .iter()
+        .map(|c| (c.text.as_str(), c.metadata.get("heading").cloned()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("Preamble.", None),
+            ("# Setup\n\nInstall it.", Some(json!("Setup"))),
+            ("## Install ##", Some(json!("Install"))),
+            ("Run the installer.", Some(json!("Install"))),
+            ("Then reboot.", Some(json!("Install"))),
+        ]
+    );
+    assert_eq!(chunks[0].records.len(), 1);
+    assert!(chunks[1..].iter().all(|c| c.records.is_empty()));
+}
+
+#[tokio::test]
+async fn semantic_chunks_split_where_topic_shifts() {
+    let text = "Cats purr and cats nap. Cats chase mice and cats nap. Cats purr when cats nap. \
+                Rust compiles code fast. Rust checks code borrows. Rust code runs fast.";
+    let embedder = Arc::new(HashingEmbedder::default());
+
+    let chunks = SemanticChunker::new(embedder.clone(), 10.0, 0, 1000, ChunkUnit::Chars)
+        .chunk(&[Section::new(text).with("page", 3)])
+        .await
+        .unwrap();
+    assert_eq!(
+        texts(&chunks),
+        vec![
+            "Cats purr and cats nap. Cats chase mice and cats nap. Cats purr when cats nap.",
+            "Rust compiles code fast. Rust checks code borrows. Rust code runs fast."
+        ]
+    );
+    assert!(chunks.iter().all(|c| c.metadata["page"] == json!(3)));
+
+    // The minimum size postpones the cut, the maximum forces one
+    let chunks = SemanticChunker::new(embedder, 10.0, 0, 60, ChunkUnit::Chars)
+        .chunk(&[Section::new(text)])
+        .await
+        .unwrap();
+    assert_eq!(chunks.len(), 4);
+    assert!(chunks.iter().all(|c| c.text.chars().count() <= 60));
+}
+
+#[tokio::test]
+async fn requests_override_configured_chunking() {
+    let configured: ChunkingOptions =
+        serde_yaml::from_str("strategy: recursive\nchunk_size: 20").unwrap();
+    assert_eq!(configured.chunk_overlap, 0);
+    assert_eq!(configured.unit, ChunkUnit::Chars);
+
+    let options = ExtractOptions {
+        chunk_strategy: Some(ChunkStrategy::Fixed),
+        chunk_size: Some(5),
+        ..ExtractOptions::default()
+    };
+    let chunking = configured.with_overrides(&options);
+    assert_eq!(chunking.strategy, ChunkStrategy::Fixed);
+    assert_eq!(chunking.chunk_size, 5);
+
+    let chunks = create_chunker(&chunking, None)
+        .unwrap()
+        .chunk(&[Section::new("0123456789")])
+        .await
+        .unwrap();
+    assert_eq!(texts(&chunks), vec!["01234", "56789"]);
+
+    // Paragraphs by default, whatever their size
+    let chunks = create_chunker(&ChunkingOptions::default(), None)
+        .unwrap()
+        .chunk(&[Section::new("One.\n\nTwo.")])
+        .await
+        .unwrap();
+    assert_eq!(texts(&chunks), vec!["One.", "Two."]);
+
+    // Semantic chunking needs a model rather than silently comparing words
+    let semantic = ChunkingOptions {
+        strategy: ChunkStrategy::Semantic,
+        ..ChunkingOptions::default()
+    };
+    let error = create_chunker(&semantic, None).err().unwrap();
+    assert!(error.to_string().contains("settings.embeddings"));
+    assert!(create_chunker(&semantic, Some(Arc::new(HashingEmbedder::default()))).is_ok());
+}
+
+#[test]
+fn rejects_tiny_chunks_and_large_overlaps() {
+    let configured = ChunkingOptions::default();
+    assert!(configured.validate().is_ok());
+
+    let options = ExtractOptions {
+        chunk_size: Some(8),
+        ..ExtractOptions::default()
+    };
+    let error = configured.with_overrides(&options).validate().unwrap_err();
+    assert_eq!(error.to_string(), "chunk_size must be at least 32, got 8");
+
+    // An overlap alone is checked against the configured size
+    let options = ExtractOptions {
+        chunk_overlap: Some(500),
+        ..ExtractOptions::default()
+    };
+    let error = configured.with_overrides(&options).validate().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "chunk_overlap must be less than half of chunk_size (1000), got 500"
+    );
+
+    let options = ExtractOptions {
+        chunk_overlap: Some(499),
+        ..ExtractOptions::default()
+    };
+    assert!(configured.with_overrides(&options).validate().is_ok());
+}
@@ -0,0 +1,215 @@
+use super::write_file;
+use akashic::ingestion::{
+    extractors::{split_code, CodeExtractor, CodeLanguage, ExtractorRegistry},
+    ExtractOptions, Extractor, Section,
+};
+
+fn summary(sections: &[Section]) -> Vec<(Option<&str>, u64, u64)> {
+    sections
+        .iter()
+        .map(|s| {
+            (
+                s.metadata.get("symbol").and_then(|v| v.as_str()),
+                s.metadata["line_start"].as_u64().unwrap(),
+                s.metadata["line_end"].as_u64().unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn can_split_rust_into_items() {
+    let source = r##"//! Token helpers
+
+use std::fmt;
+use std::collections::HashMap;
+
+/// A lexed token
+#[derive(Debug, Clone)]
+pub struct Token<'a> {
+    text: &'a str,
+}
+
+impl<'a> fmt::Display for Token<'a>
+where
+    'a: 'static,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Braces in strings and chars do not count: "}" '}'
+        write!(f, r#"{}}"#, self.text)
+    }
+}
+
+pub(crate) async fn lex(input: &str) -> Vec<Token<'_>> {
+    let open = '{';
+    /* } */
+    Vec::new()
+}
+
+const LIMIT: usize = 10;
+
+macro_rules! token {
+    ($text:expr) => {
+        Token { text: $text }
+    };
+}
+"##;
+    let file = write_file(".rs", source);
+
+    let sections = CodeExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await
+        .unwrap()
+        .sections;
+
+    assert_eq!(
+        summary(&sections),
+        vec![
+            (None, 1, 4),
+            (Some("Token"), 6, 10),
+            (Some("impl fmt::Display for Token<'a>"), 12, 20),
+            (Some("lex"), 22, 26),
+            (Some("LIMIT"), 28, 28),
+            (Some("token"), 30, 34),
+        ]
+    );
+    assert!(sections[1].text.starts_with("/// A lexed token\n#[derive"));
+    assert_eq!(sections[3].metadata["language"], "rust");
+}
+
+#[test]
+fn can_split_python_into_functions_and_classes() {
+    let source = r#"import os
+from typing import List
+
+THRESHOLD = 3
+
+
+@dataclass
+class Report:
+    """A report.
+
+def not_a_function():
+    """
+    name: str
+
+# commented out:
+#    return 1
+    def title(self):
+        return self.name.title()
+
+
+async def load(paths: List[str]) -> List[Report]:
+    return [
+Report(p) for p in paths
+    ]
+
+if __name__ == "__main__":
+    load(["a"])
+"#;
+
+    let sections = split_code(source, CodeLanguage::Python);
+
+    assert_eq!(
+        summary(&sections),
+        vec![
+            (None, 1, 4),
+            (Some("Report"), 7, 18),
+            (Some("load"), 21, 24),
+            (None, 26, 27),
+        ]
+    );
+}
+
+#[test]
+fn can_split_go_typescript_and_java() {
+    let go = r#"package main
+
+import (
+	"fmt"
+)
+
+// Server serves requests.
+type Server struct {
+	addr string
+}
+
+func (s *Server) Start() error {
+	fmt.Println(`{`)
+	return nil
+}
+
+func main() {
+}
+"#;
+    assert_eq!(
+        summary(&split_code(go, CodeLanguage::Go)),
+        vec![
+            (None, 1, 5),
+            (Some("Server"), 7, 10),
+            (Some("Server.Start"), 12, 15),
+            (Some("main"), 17, 18),
+        ]
+    );
+
+    let typescript = r#"import { readFile } from "fs";
+
+/**
+ * Load a config file.
+ */
+export async function loadConfig(path: string): Promise<Config> {
+  const text = `${await readFile(path)} }`;
+  return JSON.parse(text)
+}
+
+export const DEFAULTS = {
+  port: 8080,
+}
+
+@Component({ selector: "app" })
+export class App {}
+"#;
+    assert_eq!(
+        summary(&split_code(typescript, CodeLanguage::TypeScript)),
+        vec![
+            (None, 1, 1),
+            (Some("loadConfig"), 3, 9),
+            (Some("DEFAULTS"), 11, 13),
+            (Some("App"), 15, 16),
+        ]
+    );
+
+    let java = r#"package com.example;
+
+import java.util.List;
+
+/** Greets people. */
+@Service
+public final class Greeter
+{
+    public String greet(String name) {
+        return "Hello, " + name + "}";
+    }
+}
+"#;
+    assert_eq!(
+        summary(&split_code(java, CodeLanguage::Java)),
+        vec![(None, 1, 3), (Some("Greeter"), 5, 12)]
+    );
+}
+
+#[test]
+fn detects_language_from_extension() {
+    assert_eq!(
+        CodeLanguage::from_path("src/main.RS"),
+        Some(CodeLanguage::Rust)
+    );
+    assert_eq!(
+        CodeLanguage::from_path("app/page.tsx"),
+        Some(CodeLanguage::TypeScript)
+    );
+    assert_eq!(CodeLanguage::from_path("notes.txt"), None);
+    assert!(ExtractorRegistry::builtin()
+        .get("/tmp/missing/lib.py", None)
+        .is_some());
+}
@@ -0,0 +1,159 @@
+use akashic::ingestion::{extractors::DocExtractor, ExtractOptions, Extractor};
+use std::io::Write;
+
+/// Build a minimal Word 97 document whose text is split into one 8-bit piece and one
+/// UTF-16 piece, the way Word stores documents edited after a fast save.
+fn write_doc(compressed: &str, utf16: &str) -> tempfile::NamedTempFile {
+    write_doc_with_rows(compressed, utf16, &[])
+}
+
+/// Like [`write_doc`], with paragraph properties flagging the characters at
+/// `row_ends` as the marks ending table rows
+fn write_doc_with_rows(
+    compressed: &str,
+    utf16: &str,
+    row_ends: &[usize],
+) -> tempfile::NamedTempFile {
+    let text_offset = 1024usize;
+    let compressed_bytes: Vec<u8> = compressed.bytes().collect();
+    let utf16_offset = text_offset + compressed_bytes.len() + compressed_bytes.len() % 2;
+    let utf16_units: Vec<u16> = utf16.encode_utf16().collect();
+    let ccp_text = compressed_bytes.len() + utf16_units.len();
+
+    let mut word = vec![0u8; utf16_offset + utf16_units.len() * 2];
+    word[0..2].copy_from_slice(&0xA5ECu16.to_le_bytes());
+    word[2..4].copy_from_slice(&0x00C1u16.to_le_bytes());
+    word[0x0A..0x0C].copy_from_slice(&0x0200u16.to_le_bytes());
+    word[32..34].copy_from_slice(&14u16.to_le_bytes());
+    word[62..64].copy_from_slice(&22u16.to_le_bytes());
+    word[76..80].copy_from_slice(&(ccp_text as u32).to_le_bytes());
+    word[152..154].copy_from_slice(&93u16.to_le_bytes());
+    word[text_offset..text_offset + compressed_bytes.len()].copy_from_slice(&compressed_bytes);
+    for (i, unit) in utf16_units.iter().enumerate() {
+        word[utf16_offset + i * 2..utf16_offset + i * 2 + 2].copy_from_slice(&unit.to_le_bytes());
+    }
+
+    let mut plc = Vec::new();
+    for cp in [0, compressed_bytes.len(), ccp_text] {
+        plc.extend_from_slice(&(cp as u32).to_le_bytes());
+    }
+    for fc in [
+        ((text_offset * 2) as u32) | 0x4000_0000,
+        utf16_offset as u32,
+    ] {
+        plc.extend_from_slice(&[0, 0]);
+        plc.extend_from_slice(&fc.to_le_bytes());
+        plc.extend_from_slice(&[0, 0]);
+    }
+    let mut table = vec![0x02];
+    table.extend_from_slice(&(plc.len() as u32).to_le_bytes());
+    table.extend_from_slice(&plc);
+
+    word[418..422].copy_from_slice(&0u32.to_le_bytes());
+    word[422..426].copy_from_slice(&(table.len() as u32).to_le_bytes());
+
+    if !row_ends.is_empty() {
+        // One PapxFkp page after the text: a run per row mark, with `sprmPFTtp` set,
+        // and runs without properties in between
+        let fc = |cp: usize| match cp.checked_sub(compressed_bytes.len()) {
+            Some(i) => (utf16_offset + i * 2, 2),
+            None => (text_offset + cp, 1),
+        };
+        let mut bounds = vec![text_offset];
+        let mut ttp = Vec::new();
+        for &cp in row_ends {
+            let (start, width) = fc(cp);
+            bounds.extend([start, start + width]);
+            ttp.extend([false, true]);
+        }
+        bounds.push(word.len());
+        ttp.push(false);
+
+        let pn = word.len().div_ceil(512);
+        let mut page = vec![0u8; 512];
+        for (i, bound) in bounds.iter().enumerate() {
+            page[i * 4..i * 4 + 4].copy_from_slice(&(*bound as u32).to_le_bytes());
+        }
+        let rgbx = bounds.len() * 4;
+        for (i, row_end) in ttp.iter().enumerate() {
+            page[rgbx + i * 13] = if *row_end { 128 } else { 0 };
+        }
+        // PapxInFkp at byte 256: cb = 3 (5 bytes), istd 0, sprmPFTtp = 1
+        page[256..262].copy_from_slice(&[3, 0, 0, 0x17, 0x24, 1]);
+        page[511] = ttp.len() as u8;
+        word.resize(pn * 512, 0);
+        word.extend_from_slice(&page);
+
+        let papx = table.len();
+        for value in [text_offset, bounds[bounds.len() - 1], pn] {
+            table.extend_from_slice(&(value as u32).to_le_bytes());
+        }
+        word[258..262].copy_from_slice(&(papx as u32).to_le_bytes());
+        word[262..266].copy_from_slice(&12u32.to_le_bytes());
+    }
+
+    let file = tempfile::Builder::new().suffix(".doc").tempfile().unwrap();
+    let mut compound = cfb::create(file.path()).unwrap();
+    compound
+        .create_stream("/WordDocument")
+        .unwrap()
+        .write_all(&word)
+        .unwrap();
+    compound
+        .create_stream("/1Table")
+        .unwrap()
+        .write_all(&table)
+        .unwrap();
+    compound.flush().unwrap();
+    file
+}
+
+#[tokio::test]
+async fn can_extract_doc_pieces() {
+    let file = write_doc(
+        "Annual summary\rRevenue grew \x13 PAGE \x1412\x15 percent.\r",
+        "Grüße aus Köln\u{7}Total\u{7}\u{7}\rThe end\r",
+    );
+
+    let text = DocExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await
+        .unwrap()
+        .text();
+
+    assert_eq!(
+        text,
+        "Annual summary\n\nRevenue grew 12 percent.\n\nGrüße aus Köln | Total\n\nThe end"
+    );
+}
+
+#[tokio::test]
+async fn ends_table_rows_at_their_row_marks() {
+    // The second row starts with two empty cells, the third has none filled in
+    let table = "Name\u{7}Team\u{7}Age\u{7}\u{7}\u{7}\u{7}42\u{7}\u{7}\u{7}\u{7}\u{7}\u{7}Zoë\u{7}Ops\u{7}37\u{7}\u{7}After\r";
+    // Character positions of each row's final mark, counting "Staff\r"
+    let file = write_doc_with_rows("Staff\r", table, &[20, 26, 30, 42]);
+
+    let text = DocExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await
+        .unwrap()
+        .text();
+
+    assert_eq!(
+        text,
+        "Staff\n\nName | Team | Age\n |  | 42\nZoë | Ops | 37\n\nAfter"
+    );
+}
+
+#[tokio::test]
+async fn rejects_doc_that_is_not_a_compound_file() {
+    let file = tempfile::Builder::new().suffix(".doc").tempfile().unwrap();
+    std::fs::write(file.path(), b"plain text pretending to be Word").unwrap();
+
+    let result = DocExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await;
+
+    assert!(result.is_err());
+}
@@ -0,0 +1,58 @@
+use super::write_zip;
+use akashic::ingestion::{extractors::DocxExtractor, ExtractOptions, Extractor};
+
+const W_NS: &str = r#"xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main""#;
+
+#[tokio::test]
+async fn can_extract_docx_paragraphs_headings_and_tables() {
+    let document = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<w:document {W_NS}><w:body>
+  <w:p><w:pPr><w:pStyle w:val="Heading1"/></w:pPr><w:r><w:t>Quarterly report</w:t></w:r></w:p>
+  <w:p><w:r><w:t xml:space="preserve">Revenue &amp; costs </w:t></w:r><w:r><w:t>grew.</w:t></w:r></w:p>
+  <w:p/>
+  <w:tbl>
+    <w:tr><w:tc><w:p><w:r><w:t>Region</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>Total</w:t></w:r></w:p></w:tc></w:tr>
+    <w:tr><w:tc><w:p><w:r><w:t>EMEA</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>42</w:t></w:r></w:p></w:tc></w:tr>
+  </w:tbl>
+  <w:p><w:r><w:t>Closing remarks.</w:t></w:r></w:p>
+</w:body></w:document>"#
+    );
+    let header =
+        format!(r#"<w:hdr {W_NS}><w:p><w:r><w:t>ACME Confidential</w:t></w:r></w:p></w:hdr>"#);
+    let footnotes = format!(
+        r#"<w:footnotes {W_NS}><w:footnote w:id="1"><w:p><w:r><w:t>Unaudited figures.</w:t></w:r></w:p></w:footnote></w:footnotes>"#
+    );
+
+    let file = write_zip(
+        ".docx",
+        &[
+            ("word/document.xml", document),
+            ("word/header1.xml", header.clone()),
+            ("word/header2.xml", header),
+            ("word/footnotes.xml", footnotes),
+        ],
+    );
+
+    let text = DocxExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await
+        .unwrap()
+        .text();
+
+    assert_eq!(
+        text,
+        "# Quarterly report\n\nRevenue & costs grew.\n\nRegion | Total\nEMEA | 42\n\nClosing remarks.\n\nUnaudited figures.\n\nACME Confidential"
+    );
+}
+
+#[tokio::test]
+async fn rejects_docx_without_document_part() {
+    let file = write_zip(".docx", &[("word/styles.xml", "<w:styles/>".to_string())]);
+
+    let result = DocxExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await;
+
+    assert!(result.is_err());
+}
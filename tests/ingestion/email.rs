@@ -0,0 +1,244 @@
+use super::write_file;
+use akashic::{
+    app::App,
+    ingestion::{
+        chunking::ChunkStrategy,
+        extractors::{EmlExtractor, MboxExtractor},
+        ExtractOptions, Extractor,
+    },
+    models::_entities::documents::{self, Entity as Documents},
+    workers::ingest::{IngestWorker, IngestWorkerArgs},
+};
+use loco_rs::{bgworker::BackgroundWorker, testing::prelude::*};
+use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
+use serial_test::serial;
+
+const MESSAGE: &str = "From: =?UTF-8?Q?Jos=C3=A9_Garc=C3=ADa?= <jose@example.com>\r
+To: Ada <ada@example.com>, list@example.com\r
+Subject: Release notes\r
+Date: Tue, 2 Jan 2024 10:30:00 +0100\r
+Message-ID: <release-1@example.com>\r
+MIME-Version: 1.0\r
+Content-Type: multipart/mixed; boundary=\"outer\"\r
+\r
+--outer\r
+Content-Type: multipart/alternative; boundary=\"inner\"\r
+\r
+--inner\r
+Content-Type: text/plain; charset=iso-8859-1\r
+Content-Transfer-Encoding: quoted-printable\r
+\r
+Caf=E9 is open =\r
+again.\r
+--inner\r
+Content-Type: text/html; charset=utf-8\r
+\r
+<p>HTML version</p>\r
+--inner--\r
+--outer\r
+Content-Type: text/markdown; name=\"notes.md\"\r
+Content-Disposition: attachment; filename=\"notes.md\"\r
+Content-Transfer-Encoding: base64\r
+\r
+IyBOb3RlcwoKU2hpcCBpdC4K\r
+--outer\r
+Content-Type: application/octet-stream\r
+Content-Disposition: attachment; filename=\"blob.bin\"\r
+Content-Transfer-Encoding: base64\r
+\r
+AAECAwQF\r
+--outer--\r
+";
+
+#[tokio::test]
+async fn can_extract_eml_body_headers_and_attachments() {
+    let file = write_file(".eml", MESSAGE);
+    let path = file.path().to_str().unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let (document, attachments) = EmlExtractor
+        .extract_with_attachments(path, &ExtractOptions::default(), dir.path())
+        .await
+        .unwrap();
+    let sections = &document.sections;
+    assert_eq!(sections.len(), 1);
+    assert_eq!(sections[0].text, "# Release notes\n\nCafé is open again.");
+
+    let metadata = &document.metadata;
+    assert_eq!(metadata["from"], "José García <jose@example.com>");
+    assert_eq!(metadata["to"], "Ada <ada@example.com>, list@example.com");
+    assert_eq!(metadata["date"], "2024-01-02T10:30:00+01:00");
+    assert_eq!(metadata["subject"], "Release notes");
+    assert_eq!(metadata["message_id"], "release-1@example.com");
+    assert_eq!(&sections[0].metadata, metadata);
+
+    let names: Vec<_> = attachments.iter().map(|a| a.filename.as_str()).collect();
+    assert_eq!(names, vec!["notes.md", "blob.bin"]);
+    assert_eq!(
+        std::fs::read(&attachments[0].path).unwrap(),
+        b"# Notes\n\nShip it.\n"
+    );
+    assert_eq!(
+        attachments[0].content_type.as_deref(),
+        Some("text/markdown")
+    );
+    assert_eq!(
+        attachments[0].metadata["email_message_id"],
+        "release-1@example.com"
+    );
+}
+
+#[tokio::test]
+async fn can_extract_mbox_messages() {
+    let mbox = "From alice@example.com Mon Jan  1 09:00:00 2024
+From: alice@example.com
+Subject: First
+Message-ID: <1@example.com>
+
+Hello list.
+>From the archives.
+
+From bob@example.com Mon Jan  1 10:00:00 2024
+From: bob@example.com
+Subject: Re: First
+Message-ID: <2@example.com>
+Content-Type: text/html
+
+<html><body><p>Hi <b>Alice</b></p><script>x()</script></body></html>
+";
+    let file = write_file(".mbox", mbox);
+
+    let dir = tempfile::tempdir().unwrap();
+    let (document, attachments) = MboxExtractor
+        .extract_with_attachments(
+            file.path().to_str().unwrap(),
+            &ExtractOptions::default(),
+            dir.path(),
+        )
+        .await
+        .unwrap();
+    assert!(attachments.is_empty());
+
+    let sections = document.sections;
+    let texts: Vec<_> = sections.iter().map(|s| s.text.as_str()).collect();
+    assert_eq!(
+        texts,
+        vec![
+            "# First\n\nHello list.\nFrom the archives.",
+            "# Re: First\n\nHi Alice"
+        ]
+    );
+    assert_eq!(sections[1].metadata["message_id"], "2@example.com");
+}
+
+#[tokio::test]
+#[serial]
+async fn ingests_email_attachments_as_child_documents() {
+    let boot = boot_test::<App>().await.unwrap();
+    let db = boot.app_context.db.clone();
+    let file = write_file(".eml", MESSAGE);
+
+    let parent = documents::ActiveModel {
+        filename: Set(Some("release.eml".to_string())),
+        status: Set(Some("queued".to_string())),
+        ingestion_type: Set(Some("Vector".to_string())),
+        ..Default::default()
+    }
+    .insert(&db)
+    .await
+    .unwrap();
+
+    IngestWorker::perform_later(
+        &boot.app_context,
+        IngestWorkerArgs {
+            document_id: parent.id,
+            file_path: Some(file.path().to_str().unwrap().to_string()),
+            text: None,
+            target: "Vector".to_string(),
+            graph_db: None,
+            options: ExtractOptions::default(),
+        },
+    )
+    .await
+    .unwrap();
+
+    let parent = Documents::find_by_id(parent.id)
+        .one(&db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(parent.status.as_deref(), Some("completed"));
+    let unpacked = std::env::temp_dir()
+        .join("akashic-attachments")
+        .join(parent.id.to_string());
+    assert!(!unpacked.exists());
+
+    let children = Documents::find()
+        .filter(documents::Column::Filename.eq("notes.md"))
+        .all(&db)
+        .await
+        .unwrap();
+    let child = children.last().unwrap();
+    assert_eq!(child.status.as_deref(), Some("completed"));
+    assert_eq!(child.ingestion_type.as_deref(), Some("Vector"));
+    assert_eq!(child.parent_id, Some(parent.id));
+    let metadata: serde_json::Value =
+        serde_json::from_str(child.metadata.as_deref().unwrap()).unwrap();
+    assert_eq!(metadata["email_subject"], "Release notes");
+
+    // Attachments no extractor reads are not ingested
+    let skipped = Documents::find()
+        .filter(documents::Column::Filename.eq("blob.bin"))
+        .all(&db)
+        .await
+        .unwrap();
+    assert!(skipped.is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn removes_unpacked_attachments_when_ingestion_fails() {
+    let boot = boot_test::<App>().await.unwrap();
+    let db = boot.app_context.db.clone();
+    let file = write_file(".eml", MESSAGE);
+
+    let parent = documents::ActiveModel {
+        filename: Set(Some("release.eml".to_string())),
+        status: Set(Some("queued".to_string())),
+        ingestion_type: Set(Some("Vector".to_string())),
+        ..Default::default()
+    }
+    .insert(&db)
+    .await
+    .unwrap();
+
+    // Semantic chunking fails after the attachments were unpacked, as no embedding
+    // model is configured
+    let result = IngestWorker::perform_later(
+        &boot.app_context,
+        IngestWorkerArgs {
+            document_id: parent.id,
+            file_path: Some(file.path().to_str().unwrap().to_string()),
+            text: None,
+            target: "Vector".to_string(),
+            graph_db: None,
+            options: ExtractOptions {
+                chunk_strategy: Some(ChunkStrategy::Semantic),
+                ..ExtractOptions::default()
+            },
+        },
+    )
+    .await;
+    assert!(result.is_err());
+
+    let parent = Documents::find_by_id(parent.id)
+        .one(&db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(parent.status.as_deref(), Some("failed"));
+    let unpacked = std::env::temp_dir()
+        .join("akashic-attachments")
+        .join(parent.id.to_string());
+    assert!(!unpacked.exists());
+}
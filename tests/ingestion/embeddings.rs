@@ -0,0 +1,493 @@
+use akashic::{
+    app::App,
+    ingestion::{
+        embeddings::{
+            content_hash, cosine_similarity, create_embedder, CachedEmbedder, DbEmbeddingCache,
+            Embedder, EmbeddingCache, EmbeddingCacheSettings, EmbeddingSettings, HashingEmbedder,
+            LocalEmbedder, OllamaEmbedder, OpenAiEmbedder,
+        },
+        pipeline::IngestionPipeline,
+        stores::ChromaConfig,
+        ExtractOptions, IngestionTarget,
+    },
+    models::documents,
+};
+use async_trait::async_trait;
+use axum::{
+    extract::State,
+    http::HeaderMap,
+    routing::{get, post},
+    Json, Router,
+};
+use candle_core::{DType, Device};
+use candle_nn::{VarBuilder, VarMap};
+use candle_transformers::models::bert::BertModel;
+use loco_rs::testing::prelude::*;
+use sea_orm::{ActiveModelTrait, Set};
+use serde_json::{json, Value};
+use serial_test::serial;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokenizers::{models::wordlevel::WordLevel, pre_tokenizers::whitespace::Whitespace, Tokenizer};
+
+/// Request bodies (and authorization headers) received by a mock server
+type Requests = Arc<Mutex<Vec<(Value, Option<String>)>>>;
+
+/// Serve mock OpenAI, Ollama and Chroma endpoints on a random local port
+async fn mock_server() -> (String, Requests) {
+    async fn record(requests: &Requests, headers: &HeaderMap, body: &Value) {
+        let auth = headers
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        requests.lock().unwrap().push((body.clone(), auth));
+    }
+
+    // Vectors are `[length of the text, 1]`, returned in reverse order with their index
+    async fn openai(
+        State(requests): State<Requests>,
+        headers: HeaderMap,
+        Json(body): Json<Value>,
+    ) -> Json<Value> {
+        record(&requests, &headers, &body).await;
+        let inputs = body["input"].as_array().unwrap();
+        let data: Vec<Value> = inputs
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(index, input)| {
+                json!({"index": index, "embedding": [input.as_str().unwrap().len(), 1]})
+            })
+            .collect();
+        Json(json!({ "data": data }))
+    }
+
+    async fn ollama(
+        State(requests): State<Requests>,
+        headers: HeaderMap,
+        Json(body): Json<Value>,
+    ) -> Json<Value> {
+        record(&requests, &headers, &body).await;
+        let embeddings: Vec<Value> = body["input"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|input| json!([input.as_str().unwrap().len(), 2]))
+            .collect();
+        Json(json!({ "embeddings": embeddings }))
+    }
+
+    async fn chroma(
+        State(requests): State<Requests>,
+        headers: HeaderMap,
+        Json(body): Json<Value>,
+    ) -> Json<Value> {
+        record(&requests, &headers, &body).await;
+        Json(json!({ "id": "collection-id" }))
+    }
+
+    let requests = Requests::default();
+    let router = Router::new()
+        .route("/v1/embeddings", post(openai))
+        .route("/api/embed", post(ollama))
+        .route("/api/v1/collections", post(chroma))
+        .route("/api/v1/heartbeat", get(|| async { Json(json!({})) }))
+        .route("/api/v1/collections/{id}/upsert", post(chroma))
+        .with_state(requests.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    (url, requests)
+}
+
+fn texts(texts: &[&str]) -> Vec<String> {
+    texts.iter().map(|text| text.to_string()).collect()
+}
+
+#[tokio::test]
+async fn hashing_embeddings_are_deterministic() {
+    let embedder = HashingEmbedder::new(64);
+    assert_eq!(embedder.model(), "hashing-64");
+
+    let texts = vec![
+        "The cat sat on the mat".to_string(),
+        "the CAT sat on the mat!".to_string(),
+        "Quarterly revenue grew".to_string(),
+    ];
+    let vectors = embedder.embed(&texts).await.unwrap();
+    assert_eq!(vectors.len(), 3);
+    assert!(vectors.iter().all(|v| v.len() == 64));
+    assert_eq!(vectors, embedder.embed(&texts).await.unwrap());
+
+    assert!((cosine_similarity(&vectors[0], &vectors[1]) - 1.0).abs() < 1e-6);
+    assert!(cosine_similarity(&vectors[0], &vectors[2]) < 0.5);
+    assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+}
+
+#[tokio::test]
+async fn can_embed_with_openai_compatible_api() {
+    let (url, requests) = mock_server().await;
+    let embedder = OpenAiEmbedder::new(
+        &format!("{}/v1/", url),
+        "text-embedding-3-small",
+        Some("sk-test".to_string()),
+    )
+    .unwrap()
+    .with_dimensions(Some(2))
+    .with_batch_size(2);
+
+    let vectors = embedder.embed(&texts(&["a", "bb", "ccc"])).await.unwrap();
+    assert_eq!(
+        vectors,
+        vec![vec![1.0, 1.0], vec![2.0, 1.0], vec![3.0, 1.0]]
+    );
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(
+        requests[0].0,
+        json!({"model": "text-embedding-3-small", "input": ["a", "bb"], "dimensions": 2})
+    );
+    assert_eq!(requests[1].0["input"], json!(["ccc"]));
+    assert_eq!(requests[0].1.as_deref(), Some("Bearer sk-test"));
+}
+
+#[tokio::test]
+async fn can_embed_with_ollama() {
+    let (url, requests) = mock_server().await;
+    let embedder = OllamaEmbedder::new(&url, "nomic-embed-text").unwrap();
+
+    let vectors = embedder.embed(&texts(&["a", "bb"])).await.unwrap();
+    assert_eq!(vectors, vec![vec![1.0, 2.0], vec![2.0, 2.0]]);
+    assert_eq!(
+        requests.lock().unwrap()[0].0,
+        json!({"model": "nomic-embed-text", "input": ["a", "bb"]})
+    );
+}
+
+#[test]
+fn creates_embedders_from_settings() {
+    let settings: EmbeddingSettings =
+        serde_yaml::from_str("provider: ollama\nmodel: nomic-embed-text").unwrap();
+    assert_eq!(
+        create_embedder(&settings).unwrap().model(),
+        "nomic-embed-text"
+    );
+
+    let settings: EmbeddingSettings =
+        serde_yaml::from_str("provider: openai\nmodel: text-embedding-3-large").unwrap();
+    assert_eq!(
+        create_embedder(&settings).unwrap().model(),
+        "text-embedding-3-large"
+    );
+
+    let dir = tiny_bert();
+    let settings: EmbeddingSettings = serde_yaml::from_str(&format!(
+        "provider: local\npath: {}\nmodel: tiny-bert",
+        dir.path().display()
+    ))
+    .unwrap();
+    assert_eq!(create_embedder(&settings).unwrap().model(), "tiny-bert");
+
+    let settings: EmbeddingSettings =
+        serde_yaml::from_str("provider: local\npath: /nonexistent/all-MiniLM-L6-v2").unwrap();
+    let error = format!("{:#}", create_embedder(&settings).err().unwrap());
+    assert!(error.contains("Failed to load the embedding model in /nonexistent/all-MiniLM-L6-v2"));
+
+    // The hashing test double is not a provider
+    assert!(serde_yaml::from_str::<EmbeddingSettings>("provider: hashing").is_err());
+    assert!(serde_yaml::from_str::<EmbeddingSettings>("provider: word2vec").is_err());
+}
+
+/// A randomly initialised BERT with a word-level vocabulary, laid out like a
+/// sentence-transformer model downloaded from Hugging Face
+fn tiny_bert() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let words = [
+        "[PAD]", "[UNK]", "the", "cat", "sat", "on", "mat", "rust", "code",
+    ];
+
+    let config = json!({
+        "vocab_size": words.len(),
+        "hidden_size": 8,
+        "num_hidden_layers": 1,
+        "num_attention_heads": 2,
+        "intermediate_size": 16,
+        "hidden_act": "gelu",
+        "hidden_dropout_prob": 0.0,
+        "max_position_embeddings": 32,
+        "type_vocab_size": 2,
+        "initializer_range": 0.02,
+        "layer_norm_eps": 1e-12,
+        "pad_token_id": 0,
+        "classifier_dropout": null,
+        "model_type": "bert"
+    });
+    std::fs::write(dir.path().join("config.json"), config.to_string()).unwrap();
+
+    let vocab = words
+        .iter()
+        .enumerate()
+        .map(|(id, word)| (word.to_string(), id as u32))
+        .collect();
+    let model = WordLevel::builder()
+        .vocab(vocab)
+        .unk_token("[UNK]".to_string())
+        .build()
+        .unwrap();
+    let mut tokenizer = Tokenizer::new(model);
+    tokenizer.with_pre_tokenizer(Some(Whitespace {}));
+    tokenizer
+        .save(dir.path().join("tokenizer.json"), false)
+        .unwrap();
+
+    let varmap = VarMap::new();
+    let vb = VarBuilder::from_varmap(&varmap, DType::F32, &Device::Cpu);
+    BertModel::load(vb, &serde_json::from_value(config).unwrap()).unwrap();
+    varmap.save(dir.path().join("model.safetensors")).unwrap();
+    dir
+}
+
+#[tokio::test]
+async fn can_embed_with_local_model() {
+    let dir = tiny_bert();
+    let embedder = LocalEmbedder::load(dir.path(), 16).unwrap();
+    assert_eq!(
+        embedder.model(),
+        dir.path().file_name().unwrap().to_str().unwrap()
+    );
+
+    let vectors = embedder
+        .embed(&texts(&["the cat sat on the mat", "rust code", "the cat"]))
+        .await
+        .unwrap();
+    assert_eq!(vectors.len(), 3);
+    assert!(vectors.iter().all(|v| v.len() == 8));
+    let norm = vectors[0].iter().map(|x| x * x).sum::<f32>().sqrt();
+    assert!((norm - 1.0).abs() < 1e-4);
+
+    // Padding the shorter texts of a batch does not change their vectors
+    let alone = embedder.embed(&texts(&["rust code"])).await.unwrap();
+    let difference: f32 = alone[0]
+        .iter()
+        .zip(&vectors[1])
+        .map(|(a, b)| (a - b).abs())
+        .sum();
+    assert!(difference < 1e-4);
+}
+
+#[tokio::test]
+#[serial]
+async fn sends_pipeline_embeddings_to_chroma() {
+    let boot = boot_test::<App>().await.unwrap();
+    let db = boot.app_context.db.clone();
+    let (url, requests) = mock_server().await;
+
+    let doc = documents::ActiveModel {
+        filename: Set(Some("stdin".to_string())),
+        status: Set(Some("queued".to_string())),
+        ..Default::default()
+    }
+    .insert(&db)
+    .await
+    .unwrap();
+
+    let pipeline = IngestionPipeline::new(db, Some(ChromaConfig::new(&url)), None, None)
+        .await
+        .unwrap()
+        .with_embedder(Arc::new(HashingEmbedder::new(8)));
+    pipeline
+        .process_text(
+            doc.id,
+            "First paragraph.\n\nSecond paragraph.",
+            IngestionTarget::Vector,
+            &ExtractOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    let requests = requests.lock().unwrap();
+    let upsert = &requests.last().unwrap().0;
+    assert_eq!(
+        upsert["documents"],
+        json!(["First paragraph.", "Second paragraph."])
+    );
+    let embeddings = upsert["embeddings"].as_array().unwrap();
+    assert_eq!(embeddings.len(), 2);
+    assert_eq!(embeddings[0].as_array().unwrap().len(), 8);
+    assert_eq!(upsert["metadatas"][1]["embedding_model"], "hashing-8");
+    assert_eq!(upsert["metadatas"][1]["embedding_dimension"], 8);
+}
+
+/// Hashing embedder remembering the texts it was asked to embed
+struct CountingEmbedder {
+    inner: HashingEmbedder,
+    model: String,
+    embedded: Mutex<Vec<String>>,
+}
+
+impl CountingEmbedder {
+    fn new(model: &str) -> Self {
+        Self {
+            inner: HashingEmbedder::new(8),
+            model: model.to_string(),
+            embedded: Mutex::default(),
+        }
+    }
+
+    fn embedded(&self) -> Vec<String> {
+        std::mem::take(&mut self.embedded.lock().unwrap())
+    }
+}
+
+#[async_trait]
+impl Embedder for CountingEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, texts: &[String]) -> anyhow::Result<Vec<Vec<f32>>> {
+        self.embedded.lock().unwrap().extend_from_slice(texts);
+        self.inner.embed(texts).await
+    }
+}
+
+/// Cache that is always down
+struct BrokenCache;
+
+#[async_trait]
+impl EmbeddingCache for BrokenCache {
+    async fn get(&self, _: &str, _: &[String]) -> anyhow::Result<HashMap<String, Vec<f32>>> {
+        anyhow::bail!("connection refused")
+    }
+
+    async fn put(&self, _: &str, _: &[(String, Vec<f32>)]) -> anyhow::Result<()> {
+        anyhow::bail!("connection refused")
+    }
+}
+
+#[test]
+fn hashes_content_with_sha256() {
+    assert_eq!(
+        content_hash("hello"),
+        "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn reuses_cached_embeddings_of_the_same_model() {
+    let boot = boot_test::<App>().await.unwrap();
+    let cache = Arc::new(DbEmbeddingCache::new(boot.app_context.db.clone()));
+
+    let inner = Arc::new(CountingEmbedder::new("counting-cache-test"));
+    let embedder = CachedEmbedder::new(inner.clone(), cache.clone());
+    assert_eq!(embedder.model(), "counting-cache-test");
+
+    let vectors = embedder
+        .embed(&texts(&["alpha", "beta", "alpha"]))
+        .await
+        .unwrap();
+    assert_eq!(inner.embedded(), texts(&["alpha", "beta"]));
+    assert_eq!(vectors[0], vectors[2]);
+    assert_eq!(
+        vectors,
+        HashingEmbedder::new(8)
+            .embed(&texts(&["alpha", "beta", "alpha"]))
+            .await
+            .unwrap()
+    );
+
+    // Re-ingesting only embeds what is new
+    let again = embedder
+        .embed(&texts(&["beta", "gamma", "alpha"]))
+        .await
+        .unwrap();
+    assert_eq!(inner.embedded(), texts(&["gamma"]));
+    assert_eq!(again[0], vectors[1]);
+    assert_eq!(again[2], vectors[0]);
+
+    // Another model never gets these vectors
+    let other = Arc::new(CountingEmbedder::new("counting-cache-test-2"));
+    CachedEmbedder::new(other.clone(), cache)
+        .embed(&texts(&["alpha"]))
+        .await
+        .unwrap();
+    assert_eq!(other.embedded(), texts(&["alpha"]));
+}
+
+#[tokio::test]
+#[serial]
+async fn keys_cached_embeddings_by_dimensions_and_endpoint() {
+    let boot = boot_test::<App>().await.unwrap();
+    let cache = Arc::new(DbEmbeddingCache::new(boot.app_context.db.clone()));
+    let (url, requests) = mock_server().await;
+    let base = format!("{}/v1", url);
+
+    let openai = |dimensions| {
+        OpenAiEmbedder::new(&base, "text-embedding-3-small", None)
+            .unwrap()
+            .with_dimensions(dimensions)
+    };
+    assert_eq!(
+        openai(Some(256)).cache_key(),
+        format!("text-embedding-3-small@256 {}", base)
+    );
+    assert_eq!(
+        openai(None).cache_key(),
+        format!("text-embedding-3-small {}", base)
+    );
+    let ollama = OllamaEmbedder::new("http://localhost:11434/", "nomic-embed-text").unwrap();
+    assert_eq!(
+        ollama.cache_key(),
+        "nomic-embed-text http://localhost:11434"
+    );
+
+    // Shortened vectors of the same model are embedded again, not read from the cache
+    for dimensions in [Some(256), Some(256), Some(512), None] {
+        let embedder = CachedEmbedder::new(Arc::new(openai(dimensions)), cache.clone());
+        embedder.embed(&texts(&["alpha"])).await.unwrap();
+    }
+    let asked: Vec<Value> = requests
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(body, _)| body["dimensions"].clone())
+        .collect();
+    assert_eq!(asked, vec![json!(256), json!(512), Value::Null]);
+}
+
+#[tokio::test]
+async fn embeds_without_a_working_cache() {
+    let inner = Arc::new(CountingEmbedder::new("counting-broken-cache"));
+    let embedder = CachedEmbedder::new(inner.clone(), Arc::new(BrokenCache));
+    let vectors = embedder.embed(&texts(&["alpha", "beta"])).await.unwrap();
+    assert_eq!(vectors.len(), 2);
+    assert_eq!(inner.embedded(), texts(&["alpha", "beta"]));
+}
+
+#[test]
+fn parses_embedding_cache_settings() {
+    let settings: EmbeddingCacheSettings =
+        serde_yaml::from_str("backend: redis\nurl: redis://localhost:6379\nttl_secs: 60").unwrap();
+    match settings {
+        EmbeddingCacheSettings::Redis {
+            url,
+            prefix,
+            ttl_secs,
+        } => {
+            assert_eq!(url, "redis://localhost:6379");
+            assert_eq!(prefix, "akashic:embedding:");
+            assert_eq!(ttl_secs, Some(60));
+        }
+        other => panic!("Expected the Redis backend, got {:?}", other),
+    }
+    assert!(matches!(
+        EmbeddingCacheSettings::default(),
+        EmbeddingCacheSettings::Database
+    ));
+    assert!(matches!(
+        serde_yaml::from_str("backend: none").unwrap(),
+        EmbeddingCacheSettings::None
+    ));
+}
@@ -0,0 +1,153 @@
+use super::write_zip;
+use akashic::ingestion::{extractors::EpubExtractor, ExtractOptions, Extractor};
+
+const CONTAINER: &str = r#"<?xml version="1.0"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>"#;
+
+const OPF: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="2.0" unique-identifier="bookid">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:opf="http://www.idpf.org/2007/opf">
+    <dc:title>The Art of Ingestion</dc:title>
+    <dc:creator opf:role="aut">Ada Lovelace</dc:creator>
+    <dc:creator opf:role="aut">Charles Babbage</dc:creator>
+    <dc:language>en-GB</dc:language>
+    <dc:identifier id="bookid">urn:uuid:1b4e28ba-2fa1-11d2-883f-0016d3cca427</dc:identifier>
+    <dc:identifier opf:scheme="ISBN">978-3-16-148410-0</dc:identifier>
+  </metadata>
+  <manifest>
+    <item id="ncx" href="toc.ncx" media-type="application/x-dtbncx+xml"/>
+    <item id="ch1" href="text/ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch1b" href="text/ch1b.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch2" href="text/ch2.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine toc="ncx">
+    <itemref idref="ch1"/>
+    <itemref idref="ch1b"/>
+    <itemref idref="ch2"/>
+  </spine>
+</package>"#;
+
+const NCX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ncx xmlns="http://www.daisy.org/z3986/2005/ncx/" version="2005-1">
+  <docTitle><text>The Art of Ingestion</text></docTitle>
+  <navMap>
+    <navPoint id="n1" playOrder="1">
+      <navLabel><text>Getting Started</text></navLabel>
+      <content src="text/ch1.xhtml"/>
+    </navPoint>
+    <navPoint id="n2" playOrder="2">
+      <navLabel><text>Chunks &amp; Vectors</text></navLabel>
+      <content src="text/ch2.xhtml#start"/>
+    </navPoint>
+  </navMap>
+</ncx>"#;
+
+fn chapter(body: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<html xmlns="http://www.w3.org/1999/xhtml"><head><title>The Art of Ingestion</title>
+<style>p {{ text-indent: 1em; }}</style></head><body>{body}</body></html>"#
+    )
+}
+
+fn write_epub() -> tempfile::NamedTempFile {
+    write_zip(
+        ".epub",
+        &[
+            ("mimetype", "application/epub+zip".to_string()),
+            ("META-INF/container.xml", CONTAINER.to_string()),
+            ("OEBPS/content.opf", OPF.to_string()),
+            ("OEBPS/toc.ncx", NCX.to_string()),
+            (
+                "OEBPS/text/ch1.xhtml",
+                chapter(
+                    "<header><h1>Getting Started</h1></header><p>Fish &amp; chips&nbsp;first.</p>",
+                ),
+            ),
+            (
+                "OEBPS/text/ch1b.xhtml",
+                chapter("<p>Still in <i>chapter one</i>.</p>"),
+            ),
+            (
+                "OEBPS/text/ch2.xhtml",
+                chapter(
+                    r#"<h1 id="start">Chunks</h1><p style="color: red">Split text.</p><aside>A footnote.</aside>"#,
+                ),
+            ),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn can_extract_epub_chapters_with_toc_titles() {
+    let file = write_epub();
+    let sections = EpubExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await
+        .unwrap()
+        .sections;
+
+    let texts: Vec<&str> = sections.iter().map(|s| s.text.as_str()).collect();
+    assert_eq!(
+        texts,
+        vec![
+            "# Getting Started\n\nFish & chips first.",
+            "Still in chapter one.",
+            "# Chunks\n\nSplit text.\n\nA footnote.",
+        ]
+    );
+
+    let chapters: Vec<(u64, &str)> = sections
+        .iter()
+        .map(|s| {
+            (
+                s.metadata["chapter_index"].as_u64().unwrap(),
+                s.metadata["chapter_title"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        chapters,
+        vec![
+            (0, "Getting Started"),
+            (1, "Getting Started"),
+            (2, "Chunks & Vectors"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn can_extract_epub_book_metadata() {
+    let file = write_epub();
+    let metadata = EpubExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await
+        .unwrap()
+        .metadata;
+
+    assert_eq!(metadata["title"], "The Art of Ingestion");
+    assert_eq!(metadata["author"], "Ada Lovelace, Charles Babbage");
+    assert_eq!(metadata["language"], "en-GB");
+    assert_eq!(metadata["isbn"], "9783161484100");
+}
+
+#[tokio::test]
+async fn can_extract_epub_document() {
+    let file = write_epub();
+    let document = EpubExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await
+        .unwrap();
+
+    assert_eq!(document.sections.len(), 3);
+    assert_eq!(
+        document.sections[2].metadata["chapter_title"],
+        "Chunks & Vectors"
+    );
+    assert_eq!(document.metadata["title"], "The Art of Ingestion");
+    assert_eq!(document.metadata["isbn"], "9783161484100");
+}
@@ -0,0 +1,169 @@
+use akashic::ingestion::{
+    stores::{falkor_query, FalkorDbStore},
+    ExtractedDocument, GraphStore, Section,
+};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+
+/// A Redis server answering `+OK` to everything, recording the graph queries it gets
+async fn serve() -> (String, Arc<Mutex<Vec<String>>>) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("redis://{}", listener.local_addr().unwrap());
+    let queries = Arc::new(Mutex::new(Vec::new()));
+    let recorded = queries.clone();
+    tokio::spawn(async move {
+        loop {
+            let (socket, _) = listener.accept().await.unwrap();
+            let recorded = recorded.clone();
+            tokio::spawn(async move {
+                let (read, mut write) = socket.into_split();
+                let mut reader = BufReader::new(read);
+                while let Some(command) = read_command(&mut reader).await {
+                    if command[0] == "GRAPH.QUERY" {
+                        recorded.lock().unwrap().push(command[2].clone());
+                    }
+                    write.write_all(b"+OK\r\n").await.unwrap();
+                }
+            });
+        }
+    });
+    (url, queries)
+}
+
+/// One RESP array of bulk strings
+async fn read_command<R: AsyncBufReadExt + Unpin>(reader: &mut R) -> Option<Vec<String>> {
+    let mut line = String::new();
+    reader.read_line(&mut line).await.ok()?;
+    let count: usize = line.trim_end().strip_prefix('*')?.parse().ok()?;
+    let mut args = Vec::with_capacity(count);
+    for _ in 0..count {
+        line.clear();
+        reader.read_line(&mut line).await.ok()?;
+        let len: usize = line.trim_end().strip_prefix('$')?.parse().ok()?;
+        let mut arg = vec![0; len + 2];
+        reader.read_exact(&mut arg).await.ok()?;
+        arg.truncate(len);
+        args.push(String::from_utf8(arg).ok()?);
+    }
+    Some(args)
+}
+
+#[test]
+fn passes_values_as_query_parameters() {
+    let query = falkor_query(
+        &[
+            ("id", json!("7")),
+            ("index", json!(2)),
+            ("flag", json!(null)),
+        ],
+        "MATCH (d:Document {id: $id}) RETURN d",
+    );
+    assert_eq!(
+        query,
+        r#"CYPHER id="7" index=2 flag=null MATCH (d:Document {id: $id}) RETURN d"#
+    );
+}
+
+#[test]
+fn escapes_backslashes_before_quotes() {
+    // Escaping only the quote would turn `\'` into `\\'` and end the string early
+    let query = falkor_query(&[("text", json!(r#"it\'s a "quote" \"#))], "RETURN $text");
+    assert_eq!(query, r#"CYPHER text="it\\'s a \"quote\" \\" RETURN $text"#);
+}
+
+#[test]
+fn writes_maps_and_lists_as_literals() {
+    let query = falkor_query(
+        &[("rows", json!([{"name": "Ada", "odd`key": [1, true]}]))],
+        "UNWIND $rows AS row RETURN row",
+    );
+    assert_eq!(
+        query,
+        r#"CYPHER rows=[{`name`: "Ada", `odd``key`: [1, true]}] UNWIND $rows AS row RETURN row"#
+    );
+}
+
+#[tokio::test]
+async fn merges_records_with_external_ids() {
+    let (url, queries) = serve().await;
+    let store = FalkorDbStore::new(&url, "akashic").await.unwrap();
+    let document = ExtractedDocument::new(vec![
+        Section::new("Ticket one").with("external_id", "t-1"),
+        Section::new("continued").with("external_id", "t-1"),
+        Section::new("Loose text").with("page", 3),
+    ]);
+    store.ingest_document("7", &document).await.unwrap();
+
+    let queries = queries.lock().unwrap().clone();
+    assert_eq!(queries.len(), 5);
+    assert!(queries[0].contains("CREATE (d:Document"));
+    assert!(queries[1].contains(r#"id_namespace="" external_id="t-1" part=0 records=0 "#));
+    assert!(queries[1].contains(
+        "MERGE (s:Section {id_namespace: $id_namespace, external_id: $external_id, part: $part})"
+    ));
+    assert!(queries[2].contains(r#"external_id="t-1" part=1 records=0 "#));
+    assert!(queries[3].contains("CREATE (d)-[:HAS_SECTION]->(:Section"));
+    assert!(queries[4]
+        .starts_with(r#"CYPHER id="7" id_namespace="" external_ids=["t-1"] MATCH (s:Section)"#));
+    assert!(
+        queries[4].contains("s.id_namespace = $id_namespace AND s.external_id IN $external_ids")
+    );
+    assert!(queries[4].ends_with("DETACH DELETE s, r"));
+}
+
+#[tokio::test]
+async fn scopes_overlapping_external_ids_to_their_source() {
+    let (url, queries) = serve().await;
+    let store = FalkorDbStore::new(&url, "akashic").await.unwrap();
+    let document = ExtractedDocument::new(vec![
+        Section::new("Ticket")
+            .with("external_id", "1")
+            .with("id_namespace", "tickets"),
+        Section::new("Invoice")
+            .with("external_id", "1")
+            .with("id_namespace", "invoices"),
+    ]);
+    store.ingest_document("7", &document).await.unwrap();
+
+    let queries = queries.lock().unwrap().clone();
+    assert_eq!(queries.len(), 5);
+    assert!(queries[1].contains(r#"id_namespace="tickets" external_id="1" part=0 "#));
+    assert!(queries[2].contains(r#"id_namespace="invoices" external_id="1" part=0 "#));
+    assert!(queries[3].starts_with(r#"CYPHER id="7" id_namespace="tickets" external_ids=["1"] "#));
+    assert!(queries[4].starts_with(r#"CYPHER id="7" id_namespace="invoices" external_ids=["1"] "#));
+}
+
+#[tokio::test]
+async fn creates_the_records_of_a_section_in_one_query() {
+    let (url, queries) = serve().await;
+    let store = FalkorDbStore::new(&url, "akashic").await.unwrap();
+    let mut section = Section::new("Name: Ada\nName: Grace").with("sheet", "People");
+    section.records = vec![
+        json!({"Name": "Ada", "Born": 1815, "Tags": ["math"]})
+            .as_object()
+            .unwrap()
+            .clone(),
+        json!({"Name": "Grace", "Born": null})
+            .as_object()
+            .unwrap()
+            .clone(),
+    ];
+    store
+        .ingest_document("7", &ExtractedDocument::new(vec![section]))
+        .await
+        .unwrap();
+
+    let queries = queries.lock().unwrap().clone();
+    assert_eq!(queries.len(), 3);
+    assert_eq!(
+        queries[2],
+        concat!(
+            r#"CYPHER id="7" index=0 rows=[{`data`: {`Born`: 1815, `Name`: "Ada", `Tags`: "[\"math\"]"}, `index`: 0}, "#,
+            r#"{`data`: {`Name`: "Grace"}, `index`: 1}] "#,
+            "MATCH (s:Section {document_id: $id, index: $index}) UNWIND $rows AS row ",
+            "MERGE (s)-[:HAS_RECORD]->(r:Record {index: row.index}) ",
+            "SET r = row.data, r.index = row.index, r.document_id = $id, r.section_index = $index"
+        )
+    );
+}
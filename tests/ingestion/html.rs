@@ -0,0 +1,141 @@
+use super::write_file;
+use akashic::ingestion::{
+    extractors::{HtmlDocument, HtmlExtractor},
+    ExtractOptions, Extractor,
+};
+
+const PAGE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <title> Release notes
+  </title>
+  <meta charset="utf-8">
+  <meta name="viewport" content="width=device-width">
+  <meta name="author" content="Ada Lovelace">
+  <meta property="og:site_name" content="Example Docs">
+  <style>body { color: red; }</style>
+  <script>var tracking = "should not appear";</script>
+</head>
+<body>
+  <header><a href="/">Home</a> <a href="/blog">Blog</a></header>
+  <nav><ul><li>Docs</li><li>Pricing</li></ul></nav>
+  <div class="cookie-banner" role="dialog" hidden>We use cookies</div>
+  <article>
+    <header><h1>Version&nbsp;2.0</h1></header>
+    <p>Faster   ingestion &amp; <em>better</em>
+       search.</p>
+    <h2>Changes</h2>
+    <ul>
+      <li>New <b>HTML</b> extractor</li>
+      <li>Fixed page numbers</li>
+    </ul>
+    <table>
+      <thead><tr><th>Format</th><th>Status</th></tr></thead>
+      <tbody><tr><td>HTML</td><td>Supported</td></tr></tbody>
+    </table>
+    <pre>fn main() {
+    println!("hi");
+}</pre>
+  </article>
+  <aside>Related posts</aside>
+  <footer>Copyright 2024</footer>
+</body>
+</html>"#;
+
+#[test]
+fn keeps_structured_content_and_drops_boilerplate() {
+    let document = HtmlDocument::parse(PAGE);
+
+    assert_eq!(
+        document.text,
+        "# Version 2.0\n\n\
+         Faster ingestion & better search.\n\n\
+         ## Changes\n\n\
+         - New HTML extractor\n- Fixed page numbers\n\n\
+         Format | Status\nHTML | Supported\n\n\
+         fn main() {\n    println!(\"hi\");\n}"
+    );
+}
+
+#[test]
+fn captures_title_language_and_meta_tags() {
+    let metadata = HtmlDocument::parse(PAGE).metadata;
+
+    assert_eq!(metadata["title"], "Release notes");
+    assert_eq!(metadata["language"], "en");
+    assert_eq!(metadata["author"], "Ada Lovelace");
+    assert_eq!(metadata["og:site_name"], "Example Docs");
+    assert!(!metadata.contains_key("viewport"));
+}
+
+#[test]
+fn falls_back_to_body_without_main_or_article() {
+    let document = HtmlDocument::parse(
+        "<html><body><nav>Menu</nav><p>First</p><div>Second<br>line</div></body></html>",
+    );
+
+    assert_eq!(document.text, "First\n\nSecond\nline");
+}
+
+#[tokio::test]
+async fn can_extract_html_files() {
+    let file = write_file(".html", PAGE);
+    let path = file.path().to_str().unwrap();
+
+    assert!(HtmlExtractor.supports("saved/Page.HTM"));
+    let text = HtmlExtractor
+        .extract(path, &ExtractOptions::default())
+        .await
+        .unwrap()
+        .text();
+    assert!(text.starts_with("# Version"));
+    assert!(!text.contains("tracking"));
+    assert!(!text.contains("Copyright"));
+
+    let metadata = HtmlExtractor
+        .extract(path, &ExtractOptions::default())
+        .await
+        .unwrap()
+        .metadata;
+    assert_eq!(metadata["title"], "Release notes");
+}
+
+#[test]
+fn keeps_every_main_and_article() {
+    let document = HtmlDocument::parse(
+        "<html><body>\
+         <article><h2>First post</h2><p>Hello</p></article>\
+         <aside><article>Related post</article></aside>\
+         <article><h2>Second post</h2><p>World</p><article>Quoted</article></article>\
+         </body></html>",
+    );
+
+    assert_eq!(
+        document.text,
+        "## First post\n\nHello\n\n## Second post\n\nWorld\n\nQuoted"
+    );
+}
+
+#[tokio::test]
+async fn decodes_with_the_declared_charset() {
+    let file = write_file(
+        ".html",
+        b"<html><head><meta http-equiv=\"Content-Type\" content=\"text/html; charset=iso-8859-7\"></head><body><p>\xe1\xe2\xe3</p></body></html>",
+    );
+    let path = file.path().to_str().unwrap();
+
+    let text = HtmlExtractor
+        .extract(path, &ExtractOptions::default())
+        .await
+        .unwrap()
+        .text();
+    assert_eq!(text, "αβγ");
+
+    let file = write_file(".html", b"<meta charset='shift_jis'><p>\x82\xa0</p>");
+    let text = HtmlExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await
+        .unwrap()
+        .text();
+    assert_eq!(text, "あ");
+}
@@ -0,0 +1,61 @@
+use akashic::ingestion::{
+    extractors::{ocr_sections, ImageExtractor},
+    ExtractOptions, Extractor,
+};
+use serde_json::json;
+
+#[test]
+fn supports_common_raster_formats() {
+    for name in [
+        "receipt.PNG",
+        "board.jpeg",
+        "scan.tif",
+        "fax.tiff",
+        "photo.webp",
+    ] {
+        assert!(ImageExtractor.supports(name), "{name} should be supported");
+    }
+    assert!(!ImageExtractor.supports("notes.txt"));
+}
+
+#[test]
+fn parses_ocr_language_lists() {
+    assert_eq!(
+        ExtractOptions::parse_languages("eng+deu"),
+        vec!["eng".to_string(), "deu".to_string()]
+    );
+    assert_eq!(
+        ExtractOptions::parse_languages("chi_sim, eng"),
+        vec!["chi_sim".to_string(), "eng".to_string()]
+    );
+    // Anything that could smuggle extra tesseract arguments is dropped
+    assert_eq!(
+        ExtractOptions::parse_languages("eng+--tessdata-dir"),
+        vec!["eng".to_string()]
+    );
+}
+
+#[test]
+fn splits_multi_page_ocr_output_at_form_feeds() {
+    let sections = ocr_sections("Invoice 1\n\x0c\n\x0cTotal: 42\n\x0c\n");
+
+    let pages: Vec<_> = sections
+        .iter()
+        .map(|s| (s.text.as_str(), s.metadata["page"].clone()))
+        .collect();
+    assert_eq!(
+        pages,
+        vec![
+            ("Invoice 1", json!(1)),
+            ("", json!(2)),
+            ("Total: 42", json!(3)),
+        ]
+    );
+    assert!(sections.iter().all(|s| s.metadata["ocr"] == json!(true)));
+
+    // A single page, as Tesseract ends every page with a form feed
+    let sections = ocr_sections("Receipt\n\x0c");
+    assert_eq!(sections.len(), 1);
+    assert_eq!(sections[0].text, "Receipt");
+    assert!(!sections[0].metadata.contains_key("page"));
+}
@@ -0,0 +1,100 @@
+use super::write_file;
+use akashic::ingestion::{extractors::JsonExtractor, ExtractOptions, Extractor};
+use serde_json::json;
+
+#[tokio::test]
+async fn can_map_jsonl_records_to_sections() {
+    let jsonl = r#"{"id": 101, "body": {"text": "First ticket"}, "author": {"name": "Ada"}, "tags": ["bug"]}
+
+{"id": "t-102", "body": {"text": "Second ticket"}, "author": {"name": "Linus"}}
+not json
+{"id": 103, "body": {"text": "  "}}
+"#;
+    let file = write_file(".jsonl", jsonl);
+    let options = ExtractOptions {
+        text_field: Some("body.text".to_string()),
+        metadata_fields: ExtractOptions::parse_fields("author.name, tags, missing"),
+        id_field: Some("id".to_string()),
+        ..ExtractOptions::default()
+    };
+
+    let sections = JsonExtractor
+        .extract(file.path().to_str().unwrap(), &options)
+        .await
+        .unwrap()
+        .sections;
+
+    let texts: Vec<_> = sections.iter().map(|s| s.text.as_str()).collect();
+    assert_eq!(texts, vec!["First ticket", "Second ticket"]);
+    assert_eq!(
+        serde_json::Value::Object(sections[0].metadata.clone()),
+        json!({
+            "record_index": 0,
+            "author.name": "Ada",
+            "tags": ["bug"],
+            "external_id": "101",
+            "id_namespace": format!(
+                "{}:id",
+                file.path().file_name().unwrap().to_string_lossy()
+            ),
+        })
+    );
+    assert_eq!(sections[1].metadata["external_id"], "t-102");
+    assert_eq!(sections[1].metadata["record_index"], 1);
+    assert_eq!(sections[1].records[0]["author"]["name"], "Linus");
+}
+
+#[tokio::test]
+async fn renders_json_records_without_text_field() {
+    let file = write_file(
+        ".json",
+        r#"[{"name": "Widget", "price": 9.5, "sizes": ["S", "M"], "note": null}, "plain text"]"#,
+    );
+
+    let sections = JsonExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await
+        .unwrap()
+        .sections;
+
+    let texts: Vec<_> = sections.iter().map(|s| s.text.as_str()).collect();
+    assert_eq!(
+        texts,
+        vec![
+            "name: Widget | price: 9.5 | sizes: [\"S\",\"M\"]",
+            "plain text"
+        ]
+    );
+    assert!(!sections[0].metadata.contains_key("external_id"));
+    assert!(sections[1].records.is_empty());
+}
+
+#[tokio::test]
+async fn rejects_malformed_json() {
+    let file = write_file(".json", r#"{"name": "#);
+
+    let result = JsonExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn tags_records_with_the_given_id_namespace() {
+    let file = write_file(".jsonl", r#"{"id": 1, "body": "First ticket"}"#);
+    let options = ExtractOptions {
+        text_field: Some("body".to_string()),
+        id_field: Some("id".to_string()),
+        id_namespace: Some("crm-tickets".to_string()),
+        ..ExtractOptions::default()
+    };
+
+    let sections = JsonExtractor
+        .extract(file.path().to_str().unwrap(), &options)
+        .await
+        .unwrap()
+        .sections;
+
+    assert_eq!(sections[0].metadata["external_id"], "1");
+    assert_eq!(sections[0].metadata["id_namespace"], "crm-tickets");
+}
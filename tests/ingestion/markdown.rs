@@ -0,0 +1,132 @@
+use super::write_file;
+use akashic::ingestion::{
+    extractors::{MarkdownDocument, MarkdownExtractor},
+    ExtractOptions, Extractor,
+};
+
+const NOTES: &str = r#"---
+title: Release notes
+tags: [ingestion, search]
+draft: false
+---
+
+Intro with a [link](https://example.com) and ![a diagram](diagram.png).
+
+# Setup
+
+Install the **CLI** first.
+
+## Install
+
+- Download `akashic`
+- [x] Run it
+  1. Once
+  2. Twice
+
+```bash
+cargo install akashic
+```
+
+## Configure
+
+| Key | Value |
+|-----|-------|
+| url | http://localhost |
+
+# Usage
+
+> Quoted *advice*.
+"#;
+
+#[test]
+fn splits_markdown_at_headings_with_heading_path() {
+    let document = MarkdownDocument::parse(NOTES, true).unwrap();
+
+    let sections: Vec<(&str, Option<&str>)> = document
+        .sections
+        .iter()
+        .map(|s| {
+            (
+                s.text.as_str(),
+                s.metadata.get("heading_path").and_then(|v| v.as_str()),
+            )
+        })
+        .collect();
+    assert_eq!(
+        sections,
+        vec![
+            ("Intro with a link and a diagram.", None),
+            ("# Setup\n\nInstall the CLI first.", Some("Setup")),
+            (
+                "## Install\n\n- Download akashic\n- [x] Run it\n  1. Once\n  2. Twice\n\ncargo install akashic",
+                Some("Setup > Install"),
+            ),
+            (
+                "## Configure\n\nKey | Value\nurl | http://localhost",
+                Some("Setup > Configure"),
+            ),
+            ("# Usage\n\nQuoted advice.", Some("Usage")),
+        ]
+    );
+}
+
+#[test]
+fn lifts_front_matter_into_metadata() {
+    let yaml = MarkdownDocument::parse(NOTES, true).unwrap();
+    assert_eq!(yaml.metadata["title"], "Release notes");
+    assert_eq!(
+        yaml.metadata["tags"],
+        serde_json::json!(["ingestion", "search"])
+    );
+    assert_eq!(yaml.metadata["draft"], false);
+
+    let toml = MarkdownDocument::parse(
+        "+++\ntitle = \"Notes\"\ndate = 2024-05-01\n+++\n\nBody.",
+        true,
+    )
+    .unwrap();
+    assert_eq!(toml.metadata["title"], "Notes");
+    assert_eq!(toml.metadata["date"], "2024-05-01");
+    assert_eq!(toml.sections.len(), 1);
+    assert_eq!(toml.sections[0].text, "Body.");
+}
+
+#[tokio::test]
+async fn can_drop_code_blocks() {
+    let file = write_file(
+        ".md",
+        b"# Build\n\nRun this:\n\n```sh\nmake\n```\n\n    indented code\n",
+    );
+    let path = file.path().to_str().unwrap();
+
+    let kept = MarkdownExtractor
+        .extract(path, &ExtractOptions::default())
+        .await
+        .unwrap()
+        .text();
+    assert_eq!(kept, "# Build\n\nRun this:\n\nmake\n\nindented code");
+
+    let options = ExtractOptions {
+        drop_code_blocks: true,
+        ..ExtractOptions::default()
+    };
+    let dropped = MarkdownExtractor
+        .extract(path, &options)
+        .await
+        .unwrap()
+        .text();
+    assert_eq!(dropped, "# Build\n\nRun this:");
+}
+
+#[tokio::test]
+async fn extracts_document_with_front_matter() {
+    let file = write_file(".md", NOTES);
+
+    let document = MarkdownExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(document.metadata["title"], "Release notes");
+    assert_eq!(document.sections.len(), 5);
+    assert_eq!(document.sections[1].metadata["heading_path"], "Setup");
+}
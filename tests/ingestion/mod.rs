@@ -0,0 +1,51 @@
+mod archive;
+mod chroma;
+mod chunking;
+mod code;
+mod doc;
+mod docx;
+mod email;
+mod embeddings;
+mod epub;
+mod falkordb;
+mod html;
+mod image;
+mod json;
+mod markdown;
+mod notebook;
+mod odt;
+mod pdf;
+mod presentation;
+mod registry;
+mod rtf;
+mod sniff;
+mod spreadsheet;
+mod text;
+
+use std::io::Write;
+
+/// A temporary file ending in `suffix` holding `content`
+pub fn write_file(suffix: &str, content: impl AsRef<[u8]>) -> tempfile::NamedTempFile {
+    let mut file = tempfile::Builder::new().suffix(suffix).tempfile().unwrap();
+    file.write_all(content.as_ref()).unwrap();
+    file
+}
+
+/// A temporary ZIP file ending in `suffix` with the given entries; names ending in
+/// `/` become directories
+pub fn write_zip<C: AsRef<[u8]>>(suffix: &str, entries: &[(&str, C)]) -> tempfile::NamedTempFile {
+    let file = tempfile::Builder::new().suffix(suffix).tempfile().unwrap();
+    let mut zip = zip::ZipWriter::new(file.reopen().unwrap());
+    for (name, content) in entries {
+        if name.ends_with('/') {
+            zip.add_directory(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+        } else {
+            zip.start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(content.as_ref()).unwrap();
+        }
+    }
+    zip.finish().unwrap();
+    file
+}
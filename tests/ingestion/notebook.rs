@@ -0,0 +1,117 @@
+use super::write_file;
+use akashic::ingestion::{extractors::NotebookExtractor, ExtractOptions, Extractor, Section};
+
+const NOTEBOOK: &str = r##"{
+  "cells": [
+    {
+      "cell_type": "markdown",
+      "metadata": {},
+      "source": ["# Sales analysis\n", "\n", "Loads the **Q1** figures from [the export](data.csv)."]
+    },
+    {
+      "cell_type": "code",
+      "execution_count": 1,
+      "metadata": {},
+      "outputs": [
+        {"output_type": "stream", "name": "stdout", "text": ["loaded 3 rows\n"]},
+        {"output_type": "execute_result", "execution_count": 1, "metadata": {},
+         "data": {"text/plain": ["42"], "image/png": "iVBORw0KGgo="}}
+      ],
+      "source": "import pandas as pd\ndf = pd.read_csv(\"data.csv\")\nlen(df) * 14"
+    },
+    {"cell_type": "code", "execution_count": null, "metadata": {}, "outputs": [], "source": []},
+    {
+      "cell_type": "code",
+      "execution_count": 2,
+      "metadata": {},
+      "outputs": [
+        {"output_type": "error", "ename": "KeyError", "evalue": "'region'", "traceback": []}
+      ],
+      "source": "df[\"region\"]"
+    }
+  ],
+  "metadata": {
+    "kernelspec": {"display_name": "Python 3", "language": "python", "name": "python3"},
+    "language_info": {"name": "python"}
+  },
+  "nbformat": 4,
+  "nbformat_minor": 5
+}"##;
+
+fn write_notebook() -> tempfile::NamedTempFile {
+    write_file(".ipynb", NOTEBOOK)
+}
+
+fn summary(sections: &[Section]) -> Vec<(u64, &str, &str)> {
+    sections
+        .iter()
+        .map(|s| {
+            (
+                s.metadata["cell_index"].as_u64().unwrap(),
+                s.metadata["cell_type"].as_str().unwrap(),
+                s.text.as_str(),
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn can_extract_notebook_cells() {
+    let file = write_notebook();
+    let path = file.path().to_str().unwrap();
+
+    let sections = NotebookExtractor
+        .extract(path, &ExtractOptions::default())
+        .await
+        .unwrap()
+        .sections;
+
+    assert_eq!(
+        summary(&sections),
+        vec![
+            (
+                0,
+                "markdown",
+                "# Sales analysis\n\nLoads the Q1 figures from the export."
+            ),
+            (
+                1,
+                "code",
+                "```python\nimport pandas as pd\ndf = pd.read_csv(\"data.csv\")\nlen(df) * 14\n```"
+            ),
+            (3, "code", "```python\ndf[\"region\"]\n```"),
+        ]
+    );
+
+    let metadata = NotebookExtractor
+        .extract(path, &ExtractOptions::default())
+        .await
+        .unwrap()
+        .metadata;
+    assert_eq!(metadata["language"], "python");
+    assert_eq!(metadata["kernel"], "Python 3");
+}
+
+#[tokio::test]
+async fn can_include_notebook_outputs() {
+    let file = write_notebook();
+    let options = ExtractOptions {
+        include_outputs: true,
+        ..ExtractOptions::default()
+    };
+
+    let sections = NotebookExtractor
+        .extract(file.path().to_str().unwrap(), &options)
+        .await
+        .unwrap()
+        .sections;
+
+    assert_eq!(
+        sections[1].text,
+        "```python\nimport pandas as pd\ndf = pd.read_csv(\"data.csv\")\nlen(df) * 14\n```\n\n```\nloaded 3 rows\n42\n```"
+    );
+    assert_eq!(
+        sections[2].text,
+        "```python\ndf[\"region\"]\n```\n\n```\nKeyError: 'region'\n```"
+    );
+}
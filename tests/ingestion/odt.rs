@@ -0,0 +1,69 @@
+use super::write_zip;
+use akashic::ingestion::{extractors::OdtExtractor, ExtractOptions, Extractor};
+
+const NS: &str = r#"xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:meta="urn:oasis:names:tc:opendocument:xmlns:meta:1.0""#;
+
+#[tokio::test]
+async fn can_extract_odt_text_tables_and_notes() {
+    let content = format!(
+        r#"<office:document-content {NS}><office:body><office:text>
+  <text:tracked-changes><text:changed-region><text:deletion><text:p>Deleted draft</text:p></text:deletion></text:changed-region></text:tracked-changes>
+  <text:h text:outline-level="2">Field <text:span>notes</text:span></text:h>
+  <text:p>Tea &amp; coffee<text:s text:c="2"/>were<text:note text:note-class="footnote"><text:note-citation>1</text:note-citation><text:note-body><text:p>Mostly tea.</text:p></text:note-body></text:note> served.<office:annotation><text:p>Check this</text:p></office:annotation></text:p>
+  <text:list><text:list-item><text:p>First<text:line-break/>item</text:p></text:list-item></text:list>
+  <text:p/>
+  <table:table>
+    <table:table-row><table:table-cell><text:p>Drink</text:p></table:table-cell><table:table-cell><text:p>Cups</text:p></table:table-cell></table:table-row>
+    <table:table-row><table:table-cell><text:p>Tea</text:p></table:table-cell><table:table-cell><text:p>3</text:p></table:table-cell></table:table-row>
+  </table:table>
+</office:text></office:body></office:document-content>"#
+    );
+    let meta = format!(
+        r#"<office:document-meta {NS}><office:meta><meta:initial-creator>Ada Lovelace</meta:initial-creator><dc:creator>Editor</dc:creator><dc:title>Field notes</dc:title><dc:language>en-GB</dc:language></office:meta></office:document-meta>"#
+    );
+    let file = write_zip(
+        ".odt",
+        &[
+            (
+                "mimetype",
+                "application/vnd.oasis.opendocument.text".to_string(),
+            ),
+            ("content.xml", content),
+            ("meta.xml", meta),
+        ],
+    );
+    let path = file.path().to_str().unwrap();
+
+    let text = OdtExtractor
+        .extract(path, &ExtractOptions::default())
+        .await
+        .unwrap()
+        .text();
+    assert_eq!(
+        text,
+        "## Field notes\n\nTea & coffee  were served.\n\nFirst\nitem\n\nDrink | Cups\nTea | 3\n\nMostly tea."
+    );
+
+    let metadata = OdtExtractor
+        .extract(path, &ExtractOptions::default())
+        .await
+        .unwrap()
+        .metadata;
+    assert_eq!(metadata["title"], "Field notes");
+    assert_eq!(metadata["author"], "Ada Lovelace");
+    assert_eq!(metadata["language"], "en-GB");
+}
+
+#[tokio::test]
+async fn rejects_odt_without_content() {
+    let file = write_zip(
+        ".odt",
+        &[("styles.xml", "<office:document-styles/>".to_string())],
+    );
+
+    let error = OdtExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("content.xml"));
+}
@@ -0,0 +1,183 @@
+use akashic::ingestion::{
+    chunking::paragraph_chunks, extractors::PdfExtractor, ExtractOptions, Extractor, Section,
+};
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Document, Object, Stream};
+use serde_json::json;
+use serial_test::serial;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+fn write_pdf(pages: &[&str]) -> tempfile::NamedTempFile {
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+    let font_id = doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Helvetica",
+    });
+    let resources_id = doc.add_object(dictionary! {
+        "Font" => dictionary! { "F1" => font_id },
+    });
+
+    let mut kids = Vec::new();
+    for text in pages {
+        let operations = if text.is_empty() {
+            // A scanned page: no text layer at all
+            vec![]
+        } else {
+            vec![
+                Operation::new("BT", vec![]),
+                Operation::new("Tf", vec!["F1".into(), 24.into()]),
+                Operation::new("Td", vec![100.into(), 600.into()]),
+                Operation::new("Tj", vec![Object::string_literal(*text)]),
+                Operation::new("ET", vec![]),
+            ]
+        };
+        let content = Content { operations };
+        let content_id = doc.add_object(Stream::new(dictionary! {}, content.encode().unwrap()));
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "Contents" => content_id,
+        });
+        kids.push(page_id.into());
+    }
+
+    let count = kids.len() as i64;
+    doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids,
+            "Count" => count,
+            "Resources" => resources_id,
+            "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+        }),
+    );
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    doc.trailer.set("Root", catalog_id);
+
+    let file = tempfile::Builder::new().suffix(".pdf").tempfile().unwrap();
+    doc.save(file.path()).unwrap();
+    file
+}
+
+#[tokio::test]
+async fn can_extract_pdf_pages() {
+    let file = write_pdf(&["First page", "Second page"]);
+
+    let sections = PdfExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await
+        .unwrap()
+        .sections;
+
+    assert_eq!(sections.len(), 2);
+    assert_eq!(sections[0].text, "First page");
+    assert_eq!(sections[0].metadata["page"], json!(1));
+    assert_eq!(sections[1].text, "Second page");
+    assert_eq!(sections[1].metadata["page"], json!(2));
+}
+
+#[tokio::test]
+#[serial]
+async fn keeps_text_pages_when_ocr_of_scanned_pages_fails() {
+    let file = write_pdf(&["Typed page", ""]);
+    std::env::set_var("PDF_RASTERIZER", "/nonexistent/pdftoppm");
+
+    let sections = PdfExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await;
+    std::env::remove_var("PDF_RASTERIZER");
+
+    let sections = sections.unwrap().sections;
+    assert_eq!(sections.len(), 2);
+    assert_eq!(sections[0].text, "Typed page");
+    assert_eq!(sections[1].text, "");
+    assert_eq!(sections[1].metadata["page"], json!(2));
+}
+
+/// A rasterizer named `name` that only logs its arguments, one call per line
+fn stub_rasterizer(dir: &Path, name: &str) -> PathBuf {
+    let program = dir.join(name);
+    std::fs::write(
+        &program,
+        format!(
+            "#!/bin/sh\necho \"$@\" >> {}\n",
+            dir.join("calls.log").display()
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&program, std::fs::Permissions::from_mode(0o755)).unwrap();
+    program
+}
+
+async fn rasterizer_calls(program: &Path, file: &tempfile::NamedTempFile) -> Vec<String> {
+    std::env::set_var("PDF_RASTERIZER", program);
+    let sections = PdfExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await;
+    std::env::remove_var("PDF_RASTERIZER");
+    assert_eq!(sections.unwrap().sections.len(), 4);
+
+    let log = program.parent().unwrap().join("calls.log");
+    let pdf = file.path().to_str().unwrap();
+    std::fs::read_to_string(log)
+        .unwrap()
+        .lines()
+        .map(|call| call.replace(pdf, "<pdf>"))
+        .collect()
+}
+
+#[tokio::test]
+#[serial]
+async fn only_renders_pages_without_text() {
+    let file = write_pdf(&["Typed page", "", "Another typed page", ""]);
+
+    let dir = tempfile::tempdir().unwrap();
+    let calls = rasterizer_calls(&stub_rasterizer(dir.path(), "pdftoppm"), &file).await;
+    assert_eq!(calls.len(), 2);
+    assert!(calls[0].starts_with("-r 300 -f 2 -l 2 -singlefile -png <pdf> "));
+    assert!(calls[0].ends_with("/page-2"));
+    assert!(calls[1].starts_with("-r 300 -f 4 -l 4 -singlefile -png <pdf> "));
+
+    let dir = tempfile::tempdir().unwrap();
+    let calls = rasterizer_calls(&stub_rasterizer(dir.path(), "mutool"), &file).await;
+    assert_eq!(calls.len(), 2);
+    assert!(calls[0].starts_with("draw -r 300 -o "));
+    assert!(calls[0].ends_with("/page-2.png <pdf> 2"));
+    assert!(calls[1].ends_with("/page-4.png <pdf> 4"));
+}
+
+#[test]
+fn paragraph_chunks_record_page_ranges() {
+    let sections = vec![
+        Section::new("Intro.\n\nThe results show that").with("page", 1),
+        Section::new("revenue doubled.\n\nNext topic.").with("page", 2),
+    ];
+
+    let chunks = paragraph_chunks(&sections);
+
+    let summary: Vec<_> = chunks
+        .iter()
+        .map(|c| {
+            (
+                c.text.as_str(),
+                c.metadata["page"].clone(),
+                c.metadata["page_end"].clone(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("Intro.", json!(1), json!(1)),
+            ("The results show that revenue doubled.", json!(1), json!(2)),
+            ("Next topic.", json!(2), json!(2)),
+        ]
+    );
+}
@@ -0,0 +1,234 @@
+use super::write_zip;
+use akashic::ingestion::{
+    extractors::{OdpExtractor, PptxExtractor},
+    ExtractOptions, Extractor, Section,
+};
+
+const P_NS: &str = r#"xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships""#;
+
+fn shape(placeholder: &str, paragraphs: &[&str]) -> String {
+    let paragraphs: String = paragraphs
+        .iter()
+        .map(|p| format!("<a:p><a:r><a:t>{p}</a:t></a:r></a:p>"))
+        .collect();
+    format!(
+        r#"<p:sp><p:nvSpPr><p:nvPr>{placeholder}</p:nvPr></p:nvSpPr><p:txBody>{paragraphs}</p:txBody></p:sp>"#
+    )
+}
+
+fn slide(shapes: &[String]) -> String {
+    format!(
+        r#"<p:sld {P_NS}><p:cSld><p:spTree>{}</p:spTree></p:cSld></p:sld>"#,
+        shapes.concat()
+    )
+}
+
+fn summary(sections: &[Section]) -> Vec<(u64, Option<&str>, bool, &str)> {
+    sections
+        .iter()
+        .map(|s| {
+            (
+                s.metadata["slide_number"].as_u64().unwrap(),
+                s.metadata.get("slide_title").and_then(|t| t.as_str()),
+                s.metadata.contains_key("speaker_notes"),
+                s.text.as_str(),
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn can_extract_pptx_slides_in_order_with_notes() {
+    let table = r#"<p:graphicFrame><a:graphic><a:graphicData><a:tbl>
+<a:tr><a:tc><a:txBody><a:p><a:r><a:t>Q1</a:t></a:r></a:p></a:txBody></a:tc><a:tc><a:txBody><a:p><a:r><a:t>10</a:t></a:r></a:p></a:txBody></a:tc></a:tr>
+</a:tbl></a:graphicData></a:graphic></p:graphicFrame>"#;
+
+    let file = write_zip(
+        ".pptx",
+        &[
+            (
+                "ppt/presentation.xml",
+                format!(
+                    r#"<p:presentation {P_NS}><p:sldIdLst><p:sldId id="257" r:id="rId3"/><p:sldId id="256" r:id="rId2"/></p:sldIdLst></p:presentation>"#
+                ),
+            ),
+            (
+                "ppt/_rels/presentation.xml.rels",
+                r#"<Relationships><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide1.xml"/><Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide2.xml"/></Relationships>"#
+                    .to_string(),
+            ),
+            (
+                "ppt/slides/slide1.xml",
+                slide(&[
+                    shape(r#"<p:ph type="title"/>"#, &["Results &amp; next steps"]),
+                    shape(r#"<p:ph idx="1"/>"#, &["Revenue up", "Costs down"]),
+                    table.to_string(),
+                    shape(r#"<p:ph type="sldNum" idx="12"/>"#, &["2"]),
+                ]),
+            ),
+            (
+                "ppt/slides/_rels/slide1.xml.rels",
+                r#"<Relationships><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesSlide" Target="../notesSlides/notesSlide1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink" Target="https://example.com" TargetMode="External"/></Relationships>"#
+                    .to_string(),
+            ),
+            (
+                "ppt/notesSlides/notesSlide1.xml",
+                format!(
+                    r#"<p:notes {P_NS}><p:cSld><p:spTree>{}{}</p:spTree></p:cSld></p:notes>"#,
+                    shape(r#"<p:ph type="sldImg"/>"#, &[]),
+                    shape(r#"<p:ph type="body" idx="1"/>"#, &["Mention the hiring plan."]),
+                ),
+            ),
+            (
+                "ppt/slides/slide2.xml",
+                slide(&[
+                    shape(r#"<p:ph type="ctrTitle"/>"#, &["Quarterly review"]),
+                    shape("", &["Team Akashic"]),
+                ]),
+            ),
+        ],
+    );
+
+    let sections = PptxExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await
+        .unwrap()
+        .sections;
+
+    assert_eq!(
+        summary(&sections),
+        vec![
+            (
+                1,
+                Some("Quarterly review"),
+                false,
+                "# Quarterly review\n\nTeam Akashic"
+            ),
+            (
+                2,
+                Some("Results & next steps"),
+                false,
+                "# Results & next steps\n\nRevenue up\nCosts down\n\nQ1 | 10"
+            ),
+            (
+                2,
+                Some("Results & next steps"),
+                true,
+                "Mention the hiring plan."
+            ),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn can_extract_odp_slides_with_notes() {
+    let content = r#"<office:document-content
+  xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0"
+  xmlns:draw="urn:oasis:names:tc:opendocument:xmlns:drawing:1.0"
+  xmlns:presentation="urn:oasis:names:tc:opendocument:xmlns:presentation:1.0"
+  xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+<office:body><office:presentation>
+  <draw:page draw:name="page1">
+    <draw:frame presentation:class="title"><draw:text-box><text:p>Roadmap</text:p></draw:text-box></draw:frame>
+    <draw:frame presentation:class="outline"><draw:text-box><text:list>
+      <text:list-item><text:p>Ship <text:span>EPUB</text:span></text:p></text:list-item>
+      <text:list-item><text:p>Ship<text:s/>slides</text:p></text:list-item>
+    </text:list></draw:text-box></draw:frame>
+    <draw:frame presentation:class="page-number"><draw:text-box><text:p>1</text:p></draw:text-box></draw:frame>
+    <presentation:notes>
+      <draw:page-thumbnail draw:page-number="1"/>
+      <draw:frame presentation:class="notes"><draw:text-box><text:p>Keep it short.</text:p></draw:text-box></draw:frame>
+    </presentation:notes>
+  </draw:page>
+  <draw:page draw:name="page2">
+    <draw:custom-shape><text:p>Questions?</text:p></draw:custom-shape>
+  </draw:page>
+</office:presentation></office:body></office:document-content>"#;
+    let file = write_zip(
+        ".odp",
+        &[
+            (
+                "mimetype",
+                "application/vnd.oasis.opendocument.presentation".to_string(),
+            ),
+            ("content.xml", content.to_string()),
+        ],
+    );
+
+    let sections = OdpExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await
+        .unwrap()
+        .sections;
+
+    assert_eq!(
+        summary(&sections),
+        vec![
+            (
+                1,
+                Some("Roadmap"),
+                false,
+                "# Roadmap\n\nShip EPUB\nShip slides"
+            ),
+            (1, Some("Roadmap"), true, "Keep it short."),
+            (2, None, false, "Questions?"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn numbers_pptx_slides_by_their_place_in_the_slide_list() {
+    // The first slide's part is missing, which must not renumber the slides after it
+    let file = write_zip(
+        ".pptx",
+        &[
+            (
+                "ppt/presentation.xml",
+                format!(
+                    r#"<p:presentation {P_NS}><p:sldIdLst><p:sldId id="256" r:id="rId2"/><p:sldId id="257" r:id="rId3"/></p:sldIdLst></p:presentation>"#
+                ),
+            ),
+            (
+                "ppt/_rels/presentation.xml.rels",
+                r#"<Relationships><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide1.xml"/><Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slide" Target="slides/slide2.xml"/></Relationships>"#
+                    .to_string(),
+            ),
+            (
+                "ppt/slides/slide2.xml",
+                slide(&[shape(r#"<p:ph type="title"/>"#, &["Agenda"])]),
+            ),
+        ],
+    );
+
+    let sections = PptxExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await
+        .unwrap()
+        .sections;
+
+    assert_eq!(
+        summary(&sections),
+        vec![(2, Some("Agenda"), false, "# Agenda")]
+    );
+}
+
+#[tokio::test]
+async fn numbers_odp_slides_by_page_order() {
+    let content = r#"<office:document-content
+  xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0"
+  xmlns:draw="urn:oasis:names:tc:opendocument:xmlns:drawing:1.0"
+  xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+<office:body><office:presentation>
+  <draw:page draw:name="blank"/>
+  <draw:page draw:name="page2"><draw:frame><draw:text-box><text:p>Welcome</text:p></draw:text-box></draw:frame></draw:page>
+</office:presentation></office:body></office:document-content>"#;
+    let file = write_zip(".odp", &[("content.xml", content)]);
+
+    let sections = OdpExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await
+        .unwrap()
+        .sections;
+
+    assert_eq!(summary(&sections), vec![(2, None, false, "Welcome")]);
+}
@@ -0,0 +1,125 @@
+use super::write_file;
+use akashic::{
+    app::App,
+    ingestion::{
+        extractors::{ExtractorRegistry, ExtractorSettings, BUILTIN_PRIORITY},
+        ExtractOptions, ExtractedDocument, Extractor, Section,
+    },
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use loco_rs::testing::prelude::*;
+use serial_test::serial;
+use std::sync::Arc;
+
+struct ShoutingExtractor;
+
+#[async_trait]
+impl Extractor for ShoutingExtractor {
+    async fn extract(
+        &self,
+        file_path: &str,
+        _options: &ExtractOptions,
+    ) -> Result<ExtractedDocument> {
+        let text = tokio::fs::read_to_string(file_path).await?.to_uppercase();
+        Ok(ExtractedDocument::new(vec![Section::new(text)]))
+    }
+
+    fn supports(&self, file_path: &str) -> bool {
+        file_path.ends_with(".md")
+    }
+
+    fn supports_mime_type(&self, _mime_type: &str) -> bool {
+        false
+    }
+}
+
+fn settings(yaml: &str) -> ExtractorSettings {
+    serde_yaml::from_str(yaml).unwrap()
+}
+
+#[test]
+fn higher_priority_extractors_take_formats_over() {
+    let file = write_file(".md", b"# Notes\n\nhello");
+    let path = file.path().to_str().unwrap();
+
+    let mut registry = ExtractorRegistry::with_builtins();
+    assert_eq!(registry.get_name(path, None), Some("markdown"));
+
+    // Equal priority: the built-in registered first keeps the format
+    registry.register("shouting-low", BUILTIN_PRIORITY, ShoutingExtractor);
+    assert_eq!(registry.get_name(path, None), Some("markdown"));
+
+    registry.register("shouting", BUILTIN_PRIORITY + 1, ShoutingExtractor);
+    assert_eq!(registry.get_name(path, None), Some("shouting"));
+    assert_eq!(registry.names()[0], "shouting");
+    assert_eq!(
+        registry.get_name("/tmp/missing/report.csv", None),
+        Some("csv")
+    );
+}
+
+#[tokio::test]
+async fn can_extract_with_external_command() {
+    let registry = ExtractorRegistry::from_settings(&settings(
+        r#"
+external:
+  - name: org
+    command: ["sh", "-c", "tr a-z A-Z < \"$0\"", "{file}"]
+    extensions: ["ORG"]
+    priority: 5
+  - name: failing
+    command: "sh -c false {file}"
+    mime_types: ["text/x-failing"]
+"#,
+    ))
+    .unwrap();
+
+    let file = write_file(".org", b"* Heading\nsome text\n");
+    let path = file.path().to_str().unwrap();
+    assert_eq!(registry.get_name(path, None), Some("org"));
+    let text = registry
+        .get(path, None)
+        .unwrap()
+        .extract(path, &ExtractOptions::default())
+        .await
+        .unwrap()
+        .text();
+    assert_eq!(text, "* HEADING\nSOME TEXT");
+
+    let file = write_file(".bin", b"\x00\x01");
+    let path = file.path().to_str().unwrap();
+    let error = registry
+        .get(path, Some("text/x-failing"))
+        .unwrap()
+        .extract(path, &ExtractOptions::default())
+        .await
+        .unwrap_err();
+    assert!(error.to_string().starts_with("sh failed"), "{}", error);
+}
+
+#[test]
+fn rejects_commands_without_file_placeholder() {
+    let error = ExtractorRegistry::from_settings(&settings(
+        r#"
+external:
+  - name: pandoc
+    command: "pandoc --to plain"
+"#,
+    ))
+    .err()
+    .unwrap();
+    assert!(error.to_string().contains("{file}"), "{}", error);
+}
+
+#[tokio::test]
+#[serial]
+async fn shares_registry_through_app_context() {
+    let boot = boot_test::<App>().await.unwrap();
+    let registry = boot
+        .app_context
+        .shared_store
+        .get::<Arc<ExtractorRegistry>>()
+        .unwrap();
+    assert!(registry.names().contains(&"pdf"));
+}
@@ -0,0 +1,52 @@
+use super::write_file;
+use akashic::ingestion::{
+    extractors::{ExtractorRegistry, RtfDocument, RtfExtractor},
+    ExtractOptions, Extractor,
+};
+
+#[test]
+fn parses_rtf_paragraphs_unicode_and_tables() {
+    let rtf = br#"{\rtf1\ansi\ansicpg1252\deff0
+{\fonttbl{\f0\fswiss\fcharset0 Arial;}{\f1\fnil\fcharset204 Cyr;}}
+{\colortbl;\red255\green0\blue0;}
+{\stylesheet{\s1 heading 1;}}
+{\info{\title Caf\'e9 notes}{\author Ada}{\creatim\yr2024\mo1\dy2}}
+{\*\generator Riched20 10.0;}
+\pard\outlinelevel0\b Menu\b0\par
+\pard Caf\'e9 \emdash  cr\u232?me br\u251\'fbl\u233\'e9e{\super\chftn}{\footnote\pard\plain {\super\chftn} Seasonal.}\par
+{\f1 \'cf\'f0\'e8\'e2\'e5\'f2}\line next\par
+\uc0\u8364  euro and \u-10179\u-8704  smile\par
+{\field{\*\fldinst HYPERLINK "https://example.com"}{\fldrslt link text}}\par
+\trowd\cellx1000\cellx2000
+\pard\intbl Item\cell Price\cell\row
+\pard\intbl Tea\cell 3\{\}\cell\row
+\pard After \{table\}\par
+{\pict\pngblip 89504e47}
+}"#;
+
+    let document = RtfDocument::parse(rtf).unwrap();
+
+    assert_eq!(
+        document.text,
+        "# Menu\n\nCafé — crème brûlée\n\nПривет\nnext\n\n€ euro and 😀 smile\n\nlink text\n\nItem | Price\nTea | 3{}\n\nAfter {table}\n\nSeasonal."
+    );
+    assert_eq!(document.metadata["title"], "Café notes");
+    assert_eq!(document.metadata["author"], "Ada");
+}
+
+#[tokio::test]
+async fn selects_rtf_extractor_by_content() {
+    let file = write_file(".doc", br"{\rtf1\ansi Hello\par world}");
+    let path = file.path().to_str().unwrap();
+
+    let extractor = ExtractorRegistry::builtin().get(path, None).unwrap();
+    assert_eq!(
+        extractor
+            .extract(path, &ExtractOptions::default())
+            .await
+            .unwrap()
+            .text(),
+        "Hello\n\nworld"
+    );
+    assert!(RtfExtractor.supports_mime_type("text/rtf"));
+}
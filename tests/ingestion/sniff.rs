@@ -0,0 +1,94 @@
+use super::{write_file, write_zip};
+use akashic::ingestion::extractors::{sniff_mime_type, ExtractorRegistry};
+
+fn sniff(file: &tempfile::NamedTempFile) -> Option<String> {
+    sniff_mime_type(file.path().to_str().unwrap())
+}
+
+#[test]
+fn sniffs_formats_from_content() {
+    let pdf = write_file(".tmp", b"%PDF-1.7\n%\xE2\xE3\xCF\xD3\n");
+    assert_eq!(sniff(&pdf).as_deref(), Some("application/pdf"));
+
+    let png = write_file("", b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR");
+    assert_eq!(sniff(&png).as_deref(), Some("image/png"));
+
+    // A 1x1 24-bit bitmap: 14-byte file header, 40-byte BITMAPINFOHEADER, one padded row
+    let mut bmp = b"BM\x3A\0\0\0\0\0\0\0\x36\0\0\0\x28\0\0\0".to_vec();
+    bmp.resize(58, 0);
+    let bmp = write_file("", &bmp);
+    assert_eq!(sniff(&bmp).as_deref(), Some("image/bmp"));
+
+    // Text that happens to start with "BM" is still text
+    let notes = write_file("", b"BMW service notes: replace the brake pads.\n");
+    assert_eq!(sniff(&notes).as_deref(), Some("text/plain"));
+
+    let docx = write_zip(".zip", &[("word/document.xml", "<w:document/>")]);
+    assert_eq!(
+        sniff(&docx).as_deref(),
+        Some("application/vnd.openxmlformats-officedocument.wordprocessingml.document")
+    );
+
+    let epub = write_zip(
+        "",
+        &[
+            ("mimetype", "application/epub+zip"),
+            ("META-INF/container.xml", "<container/>"),
+        ],
+    );
+    assert_eq!(sniff(&epub).as_deref(), Some("application/epub+zip"));
+
+    let other_zip = write_zip(".zip", &[("notes.txt", "hello")]);
+    assert_eq!(sniff(&other_zip).as_deref(), Some("application/zip"));
+
+    let html = write_file(
+        "",
+        b"\xEF\xBB\xBF\n  <!DOCTYPE html><html><body>Hi</body></html>",
+    );
+    assert_eq!(sniff(&html).as_deref(), Some("text/html"));
+
+    let utf16 = write_file("", b"\xFF\xFEh\0i\0");
+    assert_eq!(sniff(&utf16).as_deref(), Some("text/plain"));
+
+    let binary = write_file("", b"\x00\x01\x02\xFF\xFE\x00garbage");
+    assert_eq!(sniff(&binary), None);
+}
+
+#[test]
+fn content_wins_over_misleading_file_names() {
+    let registry = ExtractorRegistry::builtin();
+    let pdf = write_file(".txt", b"%PDF-1.4\n");
+    let extractor = registry.get(pdf.path().to_str().unwrap(), None).unwrap();
+    assert!(extractor.supports_mime_type("application/pdf"));
+
+    let scan = write_file(".PDF.tmp", b"%PDF-1.4\n");
+    let extractor = registry.get(scan.path().to_str().unwrap(), None).unwrap();
+    assert!(extractor.supports_mime_type("application/pdf"));
+}
+
+#[test]
+fn text_falls_back_to_extension_then_content_type() {
+    let registry = ExtractorRegistry::builtin();
+    let markdown = write_file(".md", b"# Title\n\nBody");
+    let extractor = registry
+        .get(markdown.path().to_str().unwrap(), None)
+        .unwrap();
+    assert!(extractor.supports_mime_type("text/markdown"));
+
+    let report = write_file("", b"# Title\n\nBody");
+    let path = report.path().to_str().unwrap();
+    let extractor = registry
+        .get(path, Some("text/markdown; charset=utf-8"))
+        .unwrap();
+    assert!(extractor.supports_mime_type("text/markdown"));
+
+    let extractor = registry
+        .get(path, Some("application/octet-stream"))
+        .unwrap();
+    assert!(extractor.supports_mime_type("text/plain"));
+
+    let binary = write_file("", b"\x00\x01\x02\xFF\xFE\x00garbage");
+    assert!(registry
+        .get(binary.path().to_str().unwrap(), None)
+        .is_none());
+}
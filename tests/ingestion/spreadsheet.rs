@@ -0,0 +1,230 @@
+use super::{write_file, write_zip};
+use akashic::ingestion::{
+    extractors::{CsvExtractor, OdsExtractor, XlsxExtractor},
+    ExtractOptions, Extractor, Section,
+};
+use std::io::Write;
+
+fn sheet_and_rows(section: &Section) -> (&str, u64, u64) {
+    (
+        section.metadata["sheet"].as_str().unwrap(),
+        section.metadata["row_start"].as_u64().unwrap(),
+        section.metadata["row_end"].as_u64().unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn can_extract_csv_rows_as_header_value_pairs() {
+    let mut file = tempfile::Builder::new()
+        .prefix("sales")
+        .suffix(".csv")
+        .tempfile()
+        .unwrap();
+    file.write_all(
+        b"Region;Notes;Total\r\nEMEA;\"Strong; \"\"record\"\"\nquarter\";42\r\n\r\nAPAC;;7\r\n",
+    )
+    .unwrap();
+
+    let sections = CsvExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await
+        .unwrap()
+        .sections;
+
+    assert_eq!(sections.len(), 1);
+    assert_eq!(
+        sections[0].text,
+        "Region: EMEA | Notes: Strong; \"record\" quarter | Total: 42\nRegion: APAC | Total: 7"
+    );
+    assert!(sheet_and_rows(&sections[0]).0.starts_with("sales"));
+    assert_eq!(
+        sheet_and_rows(&sections[0]).1..=sheet_and_rows(&sections[0]).2,
+        2..=4
+    );
+    assert_eq!(sections[0].records[1]["Region"], "APAC");
+    assert!(!sections[0].records[1].contains_key("Notes"));
+}
+
+#[tokio::test]
+async fn splits_long_sheets_into_row_ranges() {
+    let mut csv = String::from("id,name\n");
+    for idx in 1..=30 {
+        csv.push_str(&format!("{idx},item {idx}\n"));
+    }
+    let file = write_file(".csv", &csv);
+
+    let sections = CsvExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await
+        .unwrap()
+        .sections;
+
+    let ranges: Vec<(u64, u64)> = sections
+        .iter()
+        .map(|s| (sheet_and_rows(s).1, sheet_and_rows(s).2))
+        .collect();
+    assert_eq!(ranges, vec![(2, 26), (27, 31)]);
+    assert_eq!(sections[1].records.len(), 5);
+}
+
+#[tokio::test]
+async fn can_extract_xlsx_sheets() {
+    let file = write_zip(
+        ".xlsx",
+        &[
+            (
+                "xl/workbook.xml",
+                r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+<sheets><sheet name="Q1 &amp; Q2" sheetId="1" r:id="rId1"/><sheet name="Empty" sheetId="2" r:id="rId2"/></sheets></workbook>"#,
+            ),
+            (
+                "xl/_rels/workbook.xml.rels",
+                r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Target="/xl/worksheets/sheet2.xml"/></Relationships>"#,
+            ),
+            (
+                "xl/sharedStrings.xml",
+                r#"<sst><si><t>Region</t></si><si><r><t>Clo</t></r><r><t>sed</t></r><rPh><t>x</t></rPh></si><si><t>EMEA</t></si></sst>"#,
+            ),
+            (
+                "xl/styles.xml",
+                r#"<styleSheet><numFmts><numFmt numFmtId="164" formatCode="[Red]&quot;on &quot;dd/mm/yyyy"/></numFmts>
+<cellXfs><xf numFmtId="0"/><xf numFmtId="164"/></cellXfs></styleSheet>"#,
+            ),
+            (
+                "xl/worksheets/sheet1.xml",
+                r#"<worksheet><sheetData>
+<row r="1"><c r="A1" t="s"><v>0</v></c><c r="B1" t="s"><v>1</v></c><c r="D1" t="inlineStr"><is><t>Won</t></is></c></row>
+<row r="3"><c r="A3" t="s"><v>2</v></c><c r="B3" s="1"><v>45292.5</v></c><c r="C3"><v>12.5</v></c><c r="D3" t="b"><v>1</v></c></row>
+</sheetData></worksheet>"#,
+            ),
+            (
+                "xl/worksheets/sheet2.xml",
+                "<worksheet><sheetData/></worksheet>",
+            ),
+        ],
+    );
+
+    let sections = XlsxExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await
+        .unwrap()
+        .sections;
+
+    assert_eq!(sections.len(), 1);
+    assert_eq!(
+        sections[0].text,
+        "Region: EMEA | Closed: 2024-01-01 12:00:00 | Column C: 12.5 | Won: TRUE"
+    );
+    assert_eq!(sheet_and_rows(&sections[0]), ("Q1 & Q2", 3, 3));
+}
+
+#[tokio::test]
+async fn can_extract_ods_tables() {
+    let content = r#"<office:document-content
+  xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0"
+  xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0"
+  xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+<office:body><office:spreadsheet>
+<table:table table:name="Budget">
+  <table:table-row>
+    <table:table-cell><text:p>Item</text:p></table:table-cell>
+    <table:table-cell table:number-columns-repeated="2"/>
+    <table:table-cell><text:p>Cost</text:p></table:table-cell>
+  </table:table-row>
+  <table:table-row table:number-rows-repeated="2"/>
+  <table:table-row>
+    <table:table-cell><text:p>Coffee<text:s text:c="2"/>beans</text:p>
+      <office:annotation><text:p>ignore me</text:p></office:annotation></table:table-cell>
+    <table:table-cell table:number-columns-repeated="2"/>
+    <table:table-cell office:value-type="float" office:value="9.5"><text:p>9.50</text:p></table:table-cell>
+    <table:table-cell table:number-columns-repeated="16000"/>
+  </table:table-row>
+  <table:table-row table:number-rows-repeated="1048570"><table:table-cell table:number-columns-repeated="1024"/></table:table-row>
+</table:table>
+</office:spreadsheet></office:body></office:document-content>"#;
+    let file = write_zip(
+        ".ods",
+        &[
+            ("mimetype", "application/vnd.oasis.opendocument.spreadsheet"),
+            ("content.xml", content),
+        ],
+    );
+
+    let sections = OdsExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await
+        .unwrap()
+        .sections;
+
+    assert_eq!(sections.len(), 1);
+    assert_eq!(sections[0].text, "Item: Coffee beans | Cost: 9.50");
+    assert_eq!(sheet_and_rows(&sections[0]), ("Budget", 4, 4));
+    assert_eq!(sections[0].records[0]["Cost"], "9.50");
+}
+
+#[tokio::test]
+async fn skips_xlsx_cells_beyond_the_last_column() {
+    let file = write_zip(
+        ".xlsx",
+        &[
+            (
+                "xl/workbook.xml",
+                r#"<workbook xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Sheet1" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+            ),
+            (
+                "xl/_rels/workbook.xml.rels",
+                r#"<Relationships><Relationship Id="rId1" Target="worksheets/sheet1.xml"/></Relationships>"#,
+            ),
+            (
+                "xl/worksheets/sheet1.xml",
+                r#"<worksheet><sheetData>
+<row r="1"><c r="A1" t="inlineStr"><is><t>Name</t></is></c><c r="ZZZZZZZZ1" t="inlineStr"><is><t>Bomb</t></is></c></row>
+<row r="2"><c r="A2" t="inlineStr"><is><t>Ada</t></is></c><c r="XFE2" t="inlineStr"><is><t>Past XFD</t></is></c></row>
+</sheetData></worksheet>"#,
+            ),
+        ],
+    );
+
+    let sections = XlsxExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await
+        .unwrap()
+        .sections;
+
+    assert_eq!(sections.len(), 1);
+    assert_eq!(sections[0].text, "Name: Ada");
+}
+
+#[tokio::test]
+async fn caps_huge_ods_repeats() {
+    let content = r#"<office:document-content
+  xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0"
+  xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0"
+  xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+<office:body><office:spreadsheet>
+<table:table table:name="Bomb">
+  <table:table-row>
+    <table:table-cell><text:p>Name</text:p></table:table-cell>
+    <table:table-cell table:number-columns-repeated="2000000000"/>
+    <table:table-cell><text:p>Far<text:s text:c="2000000000"/>away</text:p></table:table-cell>
+  </table:table-row>
+  <table:table-row>
+    <table:table-cell><text:p>Ada</text:p></table:table-cell>
+    <table:table-cell table:number-columns-repeated="2000000000"><text:p></text:p></table:table-cell>
+    <table:table-cell table:number-columns-repeated="2000000000"/>
+    <table:table-cell><text:p>x</text:p></table:table-cell>
+  </table:table-row>
+</table:table>
+</office:spreadsheet></office:body></office:document-content>"#;
+    let file = write_zip(".ods", &[("content.xml", content)]);
+
+    let sections = OdsExtractor
+        .extract(file.path().to_str().unwrap(), &ExtractOptions::default())
+        .await
+        .unwrap()
+        .sections;
+
+    assert_eq!(sections.len(), 1);
+    assert_eq!(sections[0].text, "Name: Ada");
+}
@@ -0,0 +1,85 @@
+use super::write_file;
+use akashic::ingestion::{
+    extractors::{decode_text, ExtractorRegistry, TextExtractor},
+    ExtractOptions, Extractor,
+};
+
+#[test]
+fn detects_common_legacy_encodings() {
+    let (text, encoding) = decode_text("Crème brûlée".as_bytes(), None).unwrap();
+    assert_eq!((text.as_str(), encoding.name()), ("Crème brûlée", "UTF-8"));
+
+    let (text, encoding) = decode_text(b"\xEF\xBB\xBFbom", None).unwrap();
+    assert_eq!((text.as_str(), encoding.name()), ("bom", "UTF-8"));
+
+    let (text, encoding) = decode_text(b"Cr\xE8me \x93quoted\x94", None).unwrap();
+    assert_eq!(
+        (text.as_str(), encoding.name()),
+        ("Crème \u{201C}quoted\u{201D}", "windows-1252")
+    );
+
+    let (text, encoding) = decode_text(b"\xFF\xFEh\0\xE9\0", None).unwrap();
+    assert_eq!((text.as_str(), encoding.name()), ("hé", "UTF-16LE"));
+
+    // No BOM: recognised by the NUL in the high byte of every unit
+    let (text, encoding) = decode_text(b"\0h\0e\0l\0l\0o", None).unwrap();
+    assert_eq!((text.as_str(), encoding.name()), ("hello", "UTF-16BE"));
+}
+
+#[test]
+fn declared_encoding_takes_precedence() {
+    let (text, encoding) = decode_text(b"\x82\xA0", Some("shift_jis")).unwrap();
+    assert_eq!((text.as_str(), encoding.name()), ("あ", "Shift_JIS"));
+
+    let error = decode_text(b"abc", Some("klingon")).unwrap_err();
+    assert_eq!(error.to_string(), "Unknown text encoding: klingon");
+}
+
+#[tokio::test]
+async fn can_extract_latin1_text_files() {
+    let file = write_file("", b"Stra\xDFe und Caf\xE9\r\n");
+    let path = file.path().to_str().unwrap();
+
+    // Non-UTF-8 text without an extension is still recognised as text
+    let extractor = ExtractorRegistry::builtin().get(path, None).unwrap();
+    assert!(extractor.supports_mime_type("text/plain"));
+
+    let text = TextExtractor
+        .extract(path, &ExtractOptions::default())
+        .await
+        .unwrap()
+        .text();
+    assert_eq!(text, "Straße und Café\r\n");
+}
+
+#[tokio::test]
+async fn extracts_document_with_sections_and_metadata() {
+    let file = write_file(".txt", b"Caf\xE9 menu");
+    let path = file.path().to_str().unwrap();
+
+    let document = TextExtractor
+        .extract(path, &ExtractOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(document.text(), "Café menu");
+    assert_eq!(document.sections.len(), 1);
+    assert_eq!(document.metadata["encoding"], "windows-1252");
+    assert_eq!(
+        document.metadata_value(),
+        Some(serde_json::json!({"encoding": "windows-1252"}))
+    );
+}
+
+#[tokio::test]
+async fn reports_declared_encoding_in_document_metadata() {
+    let file = write_file(".txt", b"\x82\xA0");
+    let path = file.path().to_str().unwrap();
+
+    let options = ExtractOptions {
+        encoding: Some("shift_jis".to_string()),
+        ..Default::default()
+    };
+    let document = TextExtractor.extract(path, &options).await.unwrap();
+    assert_eq!(document.text(), "あ");
+    assert_eq!(document.metadata["encoding"], "shift_jis");
+}
@@ -1,3 +1,4 @@
+mod ingestion;
 mod models;
 mod requests;
 mod tasks;
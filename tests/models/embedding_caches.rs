@@ -0,0 +1,45 @@
+use akashic::{app::App, models::embedding_caches};
+use loco_rs::testing::prelude::*;
+use sea_orm::Set;
+use serial_test::serial;
+
+fn row(model: &str, hash: &str, vector: Vec<u8>) -> embedding_caches::ActiveModel {
+    embedding_caches::ActiveModel {
+        model: Set(model.to_string()),
+        content_hash: Set(hash.to_string()),
+        dimension: Set((vector.len() / 4) as i32),
+        vector: Set(vector),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn can_find_cached_embeddings_by_model_and_hash() {
+    let boot = boot_test::<App>().await.unwrap();
+    let db = &boot.app_context.db;
+
+    embedding_caches::Entity::insert_missing(
+        db,
+        vec![
+            row("model-a", "hash-1", vec![1, 0, 0, 0]),
+            row("model-a", "hash-2", vec![2, 0, 0, 0]),
+            row("model-b", "hash-1", vec![3, 0, 0, 0]),
+        ],
+    )
+    .await
+    .unwrap();
+    // Already cached rows are kept as they are
+    embedding_caches::Entity::insert_missing(db, vec![row("model-a", "hash-1", vec![9, 0, 0, 0])])
+        .await
+        .unwrap();
+
+    let hashes = vec!["hash-1".to_string(), "hash-3".to_string()];
+    let found = embedding_caches::Entity::find_by_hashes(db, "model-a", &hashes)
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].content_hash, "hash-1");
+    assert_eq!(found[0].vector, vec![1, 0, 0, 0]);
+    assert_eq!(found[0].dimension, 1);
+}
@@ -1,3 +1,4 @@
 mod users;
 
-mod documents;
\ No newline at end of file
+mod documents;
+mod embedding_caches;
\ No newline at end of file
@@ -15,3 +15,51 @@ async fn can_get_ingests() {
     .await;
 }
 
+/// A `multipart/form-data` body uploading `notes.txt` with the given fields
+fn upload_form(fields: &[(&str, &str)]) -> String {
+    let mut body = String::new();
+    for (name, value) in fields {
+        body.push_str(&format!(
+            "--boundary\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+            name, value
+        ));
+    }
+    body.push_str(
+        "--boundary\r\nContent-Disposition: form-data; name=\"file\"; filename=\"notes.txt\"\r\n\
+         Content-Type: text/plain\r\n\r\nSome notes\r\n--boundary--\r\n",
+    );
+    body
+}
+
+#[tokio::test]
+#[serial]
+async fn rejects_invalid_chunking_fields() {
+    request::<App, _, _>(|request, _ctx| async move {
+        let cases = [
+            (("chunk_size", "lots"), "Invalid chunk_size: lots"),
+            (("chunk_overlap", "-1"), "Invalid chunk_overlap: -1"),
+            (("chunk_strategy", "magic"), "Invalid chunk_strategy: magic"),
+            (("chunk_unit", "pages"), "Invalid chunk_unit: pages"),
+            // The test settings configure no embedding model
+            (
+                ("chunk_strategy", "semantic"),
+                "Semantic chunking needs an embedding model",
+            ),
+            (("chunk_size", "4"), "chunk_size must be at least 32, got 4"),
+            (
+                ("chunk_overlap", "600"),
+                "chunk_overlap must be less than half of chunk_size (1000), got 600",
+            ),
+        ];
+        for ((name, value), message) in cases {
+            let res = request
+                .post("/api/ingest/file")
+                .text(upload_form(&[(name, value)]))
+                .content_type("multipart/form-data; boundary=boundary")
+                .await;
+            assert_eq!(res.status_code(), 400, "{}={}", name, value);
+            assert!(res.text().contains(message), "{}", res.text());
+        }
+    })
+    .await;
+}